@@ -0,0 +1,38 @@
+//! Compares searching a multi-megabyte [`Rope`] with [`Search`], against collecting it into a [`String`] and searching with the `regex` crate.
+//!
+//! Run with `cargo bench --bench search`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use ropey::Rope;
+use regex::Regex;
+use edit_core::search::Search;
+
+const RUNS: u32 = 10;
+
+/// Returns the mean time of `RUNS` calls of `f`.
+fn time<T>(mut f: impl FnMut() -> T) -> Duration{
+    let start = Instant::now();
+    for _ in 0..RUNS{
+        black_box(f());
+    }
+    start.elapsed() / RUNS
+}
+
+fn main(){
+    let line = "idk some shit, something else. fn main(){println!(\"héllo 😀\");}\n";
+    for megabytes in [1, 8, 32]{
+        let text = Rope::from(line.repeat(megabytes * 1024 * 1024 / line.len()));
+        println!("{:.1} MB, {} lines", text.len_bytes() as f64 / (1024.0 * 1024.0), text.len_lines());
+        for pattern in ["idk", r"s\w+e", r"\d{4}-\d{2}", r"(?m)^fn \w+$"]{
+            let search = Search::new(pattern).unwrap();
+            let regex = Regex::new(pattern).unwrap();
+            let rope_matches = search.find_all(&text).len();
+            let string_matches = regex.find_iter(&text.to_string()).count();
+            assert_eq!(string_matches, rope_matches);
+            let rope_time = time(|| search.find_all(&text));
+            let string_time = time(|| regex.find_iter(&text.to_string()).map(|search_match| (text.byte_to_char(search_match.start()), text.byte_to_char(search_match.end()))).collect::<Vec<_>>());
+            println!("    {pattern:<16} {rope_matches:>8} matches    rope: {rope_time:>12.3?}    to_string + regex: {string_time:>12.3?}");
+        }
+    }
+}
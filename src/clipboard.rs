@@ -0,0 +1,176 @@
+//! System clipboard integration.
+//!
+//! Copy/cut/paste reach the system clipboard through the `+` register(see [`RegisterName::Clipboard`](crate::register::RegisterName::Clipboard)),
+//! which reads from and writes to a [`ClipboardProvider`].
+
+use std::env;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+
+
+#[derive(Debug)]
+pub enum ClipboardError{
+    /// the provider's command could not be run
+    Io(io::Error),
+    /// the provider's command ran, but exited unsuccessfully
+    CommandFailed(String),
+    /// the clipboard contents were not valid UTF-8
+    InvalidContents,
+}
+
+/// Reads and writes a clipboard outside of edit_core.
+pub trait ClipboardProvider: Send{
+    /// Returns a short, human readable name for this provider.
+    fn name(&self) -> &str;
+    fn get_contents(&mut self) -> Result<String, ClipboardError>;
+    fn set_contents(&mut self, contents: &str) -> Result<(), ClipboardError>;
+}
+
+/// Returns the best available [`ClipboardProvider`] for the current environment.
+/// Prefers `wl-copy`/`wl-paste` under Wayland, then `xclip` or `xsel` under X11, and falls back to an [`InMemoryProvider`].
+/// An [`Osc52Provider`] is never returned, because it needs the terminal to write to. Use [`Editor::set_clipboard`](crate::editor::Editor::set_clipboard) for that.
+#[must_use] pub fn detect() -> Box<dyn ClipboardProvider>{
+    if env::var_os("WAYLAND_DISPLAY").is_some() && command_exists("wl-copy") && command_exists("wl-paste"){
+        return Box::new(CommandProvider::wayland());
+    }
+    if env::var_os("DISPLAY").is_some(){
+        if command_exists("xclip"){return Box::new(CommandProvider::xclip());}
+        if command_exists("xsel"){return Box::new(CommandProvider::xsel());}
+    }
+    Box::new(InMemoryProvider::default())
+}
+
+/// Returns `true` if an executable named `command` is found in a `PATH` directory.
+fn command_exists(command: &str) -> bool{
+    env::var_os("PATH").is_some_and(|paths| {
+        env::split_paths(&paths).any(|dir| Path::new(&dir).join(command).is_file())
+    })
+}
+
+/// A clipboard that only exists in memory. Used in tests, and when no system clipboard is available.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InMemoryProvider{
+    contents: String,
+}
+impl ClipboardProvider for InMemoryProvider{
+    fn name(&self) -> &str{"in-memory"}
+    fn get_contents(&mut self) -> Result<String, ClipboardError>{
+        Ok(self.contents.clone())
+    }
+    fn set_contents(&mut self, contents: &str) -> Result<(), ClipboardError>{
+        self.contents = contents.to_string();
+        Ok(())
+    }
+}
+
+/// A clipboard accessed by shelling out to external commands, like `xclip`, `xsel`, or `wl-copy`/`wl-paste`.
+/// The copy command receives contents on stdin, and the paste command writes contents to stdout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandProvider{
+    name: String,
+    copy_command: Vec<String>,
+    paste_command: Vec<String>,
+}
+impl CommandProvider{
+    /// Returns a new instance of [`CommandProvider`]. Each command is a program followed by its args.
+    #[must_use] pub fn new(name: &str, copy_command: &[&str], paste_command: &[&str]) -> Self{
+        Self{
+            name: name.to_string(),
+            copy_command: copy_command.iter().map(|arg| arg.to_string()).collect(),
+            paste_command: paste_command.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+    #[must_use] pub fn xclip() -> Self{
+        Self::new("xclip", &["xclip", "-selection", "clipboard", "-in"], &["xclip", "-selection", "clipboard", "-out"])
+    }
+    #[must_use] pub fn xsel() -> Self{
+        Self::new("xsel", &["xsel", "--clipboard", "--input"], &["xsel", "--clipboard", "--output"])
+    }
+    #[must_use] pub fn wayland() -> Self{
+        Self::new("wl-clipboard", &["wl-copy", "--type", "text/plain"], &["wl-paste", "--no-newline"])
+    }
+
+    fn command(args: &[String]) -> Result<Command, ClipboardError>{
+        let (program, args) = args.split_first().ok_or(ClipboardError::CommandFailed("empty command".to_string()))?;
+        let mut command = Command::new(program);
+        command.args(args);
+        Ok(command)
+    }
+}
+impl ClipboardProvider for CommandProvider{
+    fn name(&self) -> &str{&self.name}
+    fn get_contents(&mut self) -> Result<String, ClipboardError>{
+        let output = Self::command(&self.paste_command)?
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .map_err(ClipboardError::Io)?;
+        if !output.status.success(){return Err(ClipboardError::CommandFailed(self.paste_command.join(" ")));}
+        String::from_utf8(output.stdout).map_err(|_| ClipboardError::InvalidContents)
+    }
+    fn set_contents(&mut self, contents: &str) -> Result<(), ClipboardError>{
+        let mut child = Self::command(&self.copy_command)?
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(ClipboardError::Io)?;
+        if let Some(mut stdin) = child.stdin.take(){
+            stdin.write_all(contents.as_bytes()).map_err(ClipboardError::Io)?;
+        }   // stdin dropped here, so the command sees end of input
+        let status = child.wait().map_err(ClipboardError::Io)?;
+        if !status.success(){return Err(ClipboardError::CommandFailed(self.copy_command.join(" ")));}
+        Ok(())
+    }
+}
+
+/// A clipboard set through the OSC 52 terminal escape sequence, which works over ssh and inside terminal multiplexers.
+/// Terminals rarely allow reading the clipboard back, so pasting returns the contents most recently set through this provider.
+/// Only usable when `writer` is the terminal. A frontend serving the protocol over stdout should not use this with stdout.
+pub struct Osc52Provider{
+    writer: Box<dyn Write + Send>,
+    contents: String,
+}
+impl Osc52Provider{
+    #[must_use] pub fn new(writer: Box<dyn Write + Send>) -> Self{
+        Self{writer, contents: String::new()}
+    }
+    /// Returns the OSC 52 escape sequence that sets the system clipboard to `contents`.
+    /// ```
+    /// # use edit_core::clipboard::Osc52Provider;
+    ///
+    /// assert_eq!("\x1b]52;c;aWRr\x07", Osc52Provider::escape_sequence("idk"));
+    /// ```
+    #[must_use] pub fn escape_sequence(contents: &str) -> String{
+        format!("\x1b]52;c;{}\x07", base64_encode(contents.as_bytes()))
+    }
+}
+impl ClipboardProvider for Osc52Provider{
+    fn name(&self) -> &str{"osc52"}
+    fn get_contents(&mut self) -> Result<String, ClipboardError>{
+        Ok(self.contents.clone())
+    }
+    fn set_contents(&mut self, contents: &str) -> Result<(), ClipboardError>{
+        self.writer.write_all(Self::escape_sequence(contents).as_bytes()).map_err(ClipboardError::Io)?;
+        self.writer.flush().map_err(ClipboardError::Io)?;
+        self.contents = contents.to_string();
+        Ok(())
+    }
+}
+
+/// Standard, padded base64 encoding, as required by OSC 52.
+fn base64_encode(bytes: &[u8]) -> String{
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3){
+        let b = [chunk[0], chunk.get(1).copied().unwrap_or(0), chunk.get(2).copied().unwrap_or(0)];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        encoded.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        encoded.push(ALPHABET[(n >> 12) as usize & 63] as char);
+        encoded.push(if chunk.len() > 1{ALPHABET[(n >> 6) as usize & 63] as char}else{'='});
+        encoded.push(if chunk.len() > 2{ALPHABET[n as usize & 63] as char}else{'='});
+    }
+    encoded
+}
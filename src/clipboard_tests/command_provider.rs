@@ -0,0 +1,23 @@
+use crate::clipboard::{ClipboardProvider, CommandProvider};
+
+#[test] fn round_trips_contents_through_commands(){
+    let path = std::env::temp_dir().join(format!("edit_core_clipboard_tests_{}_round_trips_contents_through_commands", std::process::id()));
+    let path = path.to_str().unwrap();
+    let copy = format!("cat > {path}");
+    let mut provider = CommandProvider::new("cat", &["sh", "-c", &copy], &["cat", path]);
+    assert!(provider.set_contents("idk\nsome\nshit😀\n").is_ok());
+    assert_eq!("idk\nsome\nshit😀\n", provider.get_contents().unwrap());
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn errors_if_command_fails(){
+    let mut provider = CommandProvider::new("false", &["false"], &["false"]);
+    assert!(provider.set_contents("idk").is_err());
+    assert!(provider.get_contents().is_err());
+}
+
+#[test] fn errors_if_command_does_not_exist(){
+    let mut provider = CommandProvider::new("missing", &["edit_core_command_that_does_not_exist"], &["edit_core_command_that_does_not_exist"]);
+    assert!(provider.set_contents("idk").is_err());
+    assert!(provider.get_contents().is_err());
+}
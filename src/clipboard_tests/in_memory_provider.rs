@@ -0,0 +1,8 @@
+use crate::clipboard::{ClipboardProvider, InMemoryProvider};
+
+#[test] fn returns_most_recently_set_contents(){
+    let mut provider = InMemoryProvider::default();
+    assert_eq!("", provider.get_contents().unwrap());
+    assert!(provider.set_contents("idk\nsome").is_ok());
+    assert_eq!("idk\nsome", provider.get_contents().unwrap());
+}
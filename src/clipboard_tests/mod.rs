@@ -0,0 +1,3 @@
+mod in_memory_provider;
+mod command_provider;
+mod osc52_provider;
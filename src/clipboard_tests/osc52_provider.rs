@@ -0,0 +1,30 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use crate::clipboard::{ClipboardProvider, Osc52Provider};
+
+/// Collects everything written to it, so tests can inspect what would have been sent to the terminal.
+#[derive(Clone, Default)]
+struct SharedWriter(Arc<Mutex<Vec<u8>>>);
+impl Write for SharedWriter{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize>{
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()>{Ok(())}
+}
+
+#[test] fn escape_sequence_base64_encodes_contents(){
+    assert_eq!("\x1b]52;c;\x07", Osc52Provider::escape_sequence(""));
+    assert_eq!("\x1b]52;c;aQ==\x07", Osc52Provider::escape_sequence("i"));
+    assert_eq!("\x1b]52;c;aWQ=\x07", Osc52Provider::escape_sequence("id"));
+    assert_eq!("\x1b]52;c;aWRrCnNvbWU=\x07", Osc52Provider::escape_sequence("idk\nsome"));
+    assert_eq!("\x1b]52;c;8J+YgA==\x07", Osc52Provider::escape_sequence("😀"));
+}
+
+#[test] fn set_contents_writes_escape_sequence_and_is_returned_by_get_contents(){
+    let writer = SharedWriter::default();
+    let mut provider = Osc52Provider::new(Box::new(writer.clone()));
+    assert!(provider.set_contents("idk").is_ok());
+    assert_eq!(b"\x1b]52;c;aWRr\x07".to_vec(), *writer.0.lock().unwrap());
+    assert_eq!("idk", provider.get_contents().unwrap());
+}
@@ -0,0 +1,126 @@
+//! Word completion, suggesting words already used in a document.
+//!
+//! A [`WordIndex`] counts the words in a document's text. It is kept up to date from the [`Edit`]s of each applied
+//! [`ChangeSet`](crate::history::ChangeSet), re-indexing only the lines an edit touched.
+
+use std::collections::HashMap;
+use ropey::{Rope, RopeSlice};
+use crate::history::Edit;
+use crate::selections::Selections;
+use crate::selection::CursorSemantics;
+use crate::text_util;
+
+
+
+/// Counts of each word in a text. Words are runs of [`text_util::is_word_char`] chars.
+#[derive(Debug, Clone, Default)]
+pub struct WordIndex{
+    counts: HashMap<String, usize>,
+    text: Rope,     //the text `counts` were taken from
+}
+impl WordIndex{
+    /// Returns a [`WordIndex`] of every word in `text`.
+    #[must_use] pub fn new(text: &Rope) -> Self{
+        let mut index = Self{counts: HashMap::new(), text: text.clone()};
+        index.add_words(text.slice(..));
+        index
+    }
+
+    /// Re-indexes the lines touched by `edits`, which turned the indexed text into `text`.
+    /// `edits` are in application order, each in the coordinates left by the edits before it.
+    pub fn update(&mut self, edits: &[Edit], text: &Rope){
+        // each edit's lines, as a range of the indexed text, and the range of `text` it became
+        let mut changed: Vec<((usize, usize), (usize, usize))> = Vec::with_capacity(edits.len());
+        for (i, edit) in edits.iter().enumerate(){
+            // undoing the edits before this one gives its position in the indexed text, and applying the edits after it, its position in `text`
+            let (mut old_start, mut old_end) = (edit.at, edit.at + edit.removed);
+            for earlier in edits[..i].iter().rev().map(Edit::inverse){
+                (old_start, old_end) = (earlier.map(old_start), earlier.map(old_end));
+            }
+            let (mut new_start, mut new_end) = (edit.at, edit.at + edit.inserted);
+            for later in &edits[i + 1..]{
+                (new_start, new_end) = (later.map(new_start), later.map(new_end));
+            }
+            // words never span lines, so re-counting whole lines re-counts any word joined or split by the edit
+            changed.push((whole_lines(old_start, old_end, &self.text), whole_lines(new_start, new_end, text)));
+        }
+
+        // edits touching the same lines are re-indexed together, so no line is counted twice
+        changed.sort_by_key(|(_, (new_start, _))| *new_start);
+        let mut merged: Vec<((usize, usize), (usize, usize))> = Vec::with_capacity(changed.len());
+        for (old, new) in changed{
+            match merged.last_mut(){
+                Some((last_old, last_new)) if old.0 <= last_old.1 || new.0 <= last_new.1 => {
+                    *last_old = (last_old.0.min(old.0), last_old.1.max(old.1));
+                    *last_new = (last_new.0.min(new.0), last_new.1.max(new.1));
+                }
+                _ => merged.push((old, new))
+            }
+        }
+
+        let old_text = std::mem::replace(&mut self.text, text.clone());
+        for ((old_start, old_end), (new_start, new_end)) in merged{
+            self.remove_words(old_text.slice(old_start..old_end));
+            self.add_words(text.slice(new_start..new_end));
+        }
+    }
+
+    /// Returns how many times `word` appears in the indexed text.
+    #[must_use] pub fn count(&self, word: &str) -> usize{
+        self.counts.get(word).copied().unwrap_or(0)
+    }
+
+    /// Returns the indexed words starting with, and longer than, `prefix`. Most used words come first, then shorter words, then alphabetically.
+    #[must_use] pub fn candidates(&self, prefix: &str) -> Vec<String>{
+        if prefix.is_empty(){return Vec::new();}
+        let mut candidates: Vec<(&String, usize)> = self.counts.iter()
+            .filter(|(word, _)| word.starts_with(prefix) && word.len() > prefix.len())
+            .map(|(word, &count)| (word, count))
+            .collect();
+        candidates.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.chars().count().cmp(&b.chars().count())).then(a.cmp(b)));
+        candidates.into_iter().map(|(word, _)| word.clone()).collect()
+    }
+
+    /// Returns the [`candidates`](WordIndex::candidates) for the word prefix before each selection's cursor, in selection order.
+    #[must_use] pub fn candidates_at(&self, selections: &Selections, text: &Rope, semantics: CursorSemantics) -> Vec<Vec<String>>{
+        selections.iter()
+            .map(|selection| {
+                let cursor = selection.cursor(text, semantics);
+                self.candidates(&text.slice(word_prefix_start(cursor, text)..cursor).to_string())
+            })
+            .collect()
+    }
+
+    fn add_words(&mut self, slice: RopeSlice){
+        for word in words(slice){
+            *self.counts.entry(word).or_insert(0) += 1;
+        }
+    }
+    fn remove_words(&mut self, slice: RopeSlice){
+        for word in words(slice){
+            if let Some(count) = self.counts.get_mut(&word){
+                *count -= 1;
+                if *count == 0{self.counts.remove(&word);}
+            }
+        }
+    }
+}
+
+/// Returns `start..end` grown to the start of its first line, and the end of its last line(including the newline).
+fn whole_lines(start: usize, end: usize, text: &Rope) -> (usize, usize){
+    let (start, end) = (start.min(text.len_chars()), end.min(text.len_chars()));
+    (text.line_to_char(text.char_to_line(start)), text.line_to_char((text.char_to_line(end) + 1).min(text.len_lines())))
+}
+
+/// Returns the start of the word prefix ending at `cursor`. Returns `cursor` if the char before it is not a word char.
+#[must_use] pub fn word_prefix_start(cursor: usize, text: &Rope) -> usize{
+    (0..cursor.min(text.len_chars())).rev().take_while(|&index| text_util::is_word_char(text.char(index))).last().unwrap_or(cursor)
+}
+
+fn words(slice: RopeSlice) -> Vec<String>{
+    slice.to_string()
+        .split(|char: char| !text_util::is_word_char(char))
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1 @@
+mod word_index;
@@ -0,0 +1,97 @@
+use ropey::Rope;
+use crate::completion::WordIndex;
+use crate::history::Edit;
+use crate::document::Document;
+use crate::selection::{Selection, CursorSemantics};
+use crate::selections::Selections;
+use crate::utilities::{insert_string, backspace, undo, redo};
+
+#[test] fn counts_words(){
+    let index = WordIndex::new(&Rope::from("idk some_shit\nsome idk, idk\n"));
+    assert_eq!(3, index.count("idk"));
+    assert_eq!(2, index.count("some"));
+    assert_eq!(1, index.count("shit"));
+    assert_eq!(0, index.count("some_shit"));
+}
+
+#[test] fn ranks_candidates_by_count_then_length_then_alphabetically(){
+    let index = WordIndex::new(&Rope::from("sometimes something somewhere somewhere some so\n"));
+    assert_eq!(vec!["somewhere", "some", "something", "sometimes"], index.candidates("so"));
+    assert_eq!(Vec::<String>::new(), index.candidates("some_"));
+    assert_eq!(Vec::<String>::new(), index.candidates(""));
+}
+
+#[test] fn candidates_at_each_cursor(){
+    let semantics = CursorSemantics::Bar;
+    //                     1
+    // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5
+    // i d k   s o m e   s   i   s h i t
+    let text = Rope::from("idk some s i shit\n");
+    let index = WordIndex::new(&text);
+    let selections = Selections::new(vec![
+        Selection::new_from_components(10, 10, None, &text, semantics),
+        Selection::new_from_components(12, 12, None, &text, semantics),
+        Selection::new_from_components(13, 13, None, &text, semantics),
+    ], 0, &text, semantics);
+    assert_eq!(vec![vec!["shit".to_string(), "some".to_string()], vec!["idk".to_string()], vec![]], index.candidates_at(&selections, &text, semantics));
+}
+
+#[test] fn is_updated_as_change_sets_are_applied_and_undone(){
+    let semantics = CursorSemantics::Bar;
+    let text = Rope::from("idk\n");
+    let mut doc = Document::new(semantics)
+        .with_text(text.clone())
+        .with_selections(Selections::new(vec![Selection::new_from_components(3, 3, None, &text, semantics)], 0, &text, semantics));
+    assert!(insert_string::document_impl(&mut doc, "s", false, 4, semantics).is_ok());
+    assert_eq!(0, doc.word_index.count("idk"));
+    assert_eq!(1, doc.word_index.count("idks"));
+    assert!(insert_string::document_impl(&mut doc, " some", false, 4, semantics).is_ok());
+    assert!(backspace::document_impl(&mut doc, 6, false, 4, semantics).is_ok());
+    assert_eq!(1, doc.word_index.count("idk"));
+    assert_eq!(0, doc.word_index.count("some"));
+    assert!(undo::document_impl(&mut doc, semantics).is_ok());
+    assert_eq!(1, doc.word_index.count("some"));
+    assert!(redo::document_impl(&mut doc, semantics).is_ok());
+    assert_eq!(0, doc.word_index.count("some"));
+    assert_eq!(1, doc.word_index.count("idk"));
+}
+
+#[test] fn is_updated_by_change_sets_with_many_changes(){
+    let semantics = CursorSemantics::Bar;
+    let text = Rope::from("idk\nsome\nshit\n");
+    let mut doc = Document::new(semantics)
+        .with_text(text.clone())
+        .with_selections(Selections::new(vec![
+            Selection::new_from_components(1, 1, None, &text, semantics),
+            Selection::new_from_components(9, 9, None, &text, semantics),
+        ], 0, &text, semantics));
+    assert!(insert_string::document_impl(&mut doc, "x", false, 4, semantics).is_ok());
+    for (word, count) in [("ixdk", 1), ("some", 1), ("xshit", 1), ("idk", 0), ("shit", 0)]{
+        assert_eq!(count, doc.word_index.count(word), "{word}");
+    }
+    assert_eq!(WordIndex::new(&doc.text).candidates("i"), doc.word_index.candidates("i"));
+}
+
+#[test] fn is_updated_by_edits_in_any_order(){
+    // 0 1 2 3 4 5 6 7 8 9 0 1 2 3
+    // i d k \n s o m e \n s h i t \n
+    let old_text = Rope::from("idk\nsome\nshit\n");
+    for (edits, new_text) in [
+        // in text order, as a change set applies them
+        (vec![Edit{at: 1, removed: 0, inserted: 1}, Edit{at: 10, removed: 0, inserted: 1}], "ixdk\nsome\nxshit\n"),
+        // in reverse text order, as undo applies them
+        (vec![Edit{at: 9, removed: 0, inserted: 1}, Edit{at: 1, removed: 0, inserted: 1}], "ixdk\nsome\nxshit\n"),
+        // a later edit within the text inserted by an earlier one
+        (vec![Edit{at: 4, removed: 0, inserted: 6}, Edit{at: 5, removed: 2, inserted: 0}], "idk\nsom\nsome\nshit\n"),
+        // a later edit joining lines across an earlier one
+        (vec![Edit{at: 4, removed: 1, inserted: 1}, Edit{at: 3, removed: 1, inserted: 0}, Edit{at: 7, removed: 1, inserted: 0}], "idkxomeshit\n"),
+    ]{
+        let mut index = WordIndex::new(&old_text);
+        let new_text = Rope::from(new_text);
+        index.update(&edits, &new_text);
+        let expected = WordIndex::new(&new_text);
+        for word in ["idk", "ixdk", "some", "som", "shit", "xshit", "idkxomeshit", "xome", "idkxome"]{
+            assert_eq!(expected.count(word), index.count(word), "{word}");
+        }
+    }
+}
@@ -9,7 +9,14 @@ use crate::view::View;
 use crate::range::Range;
 use crate::selection::{CursorSemantics, Movement, Selection, Direction};
 use crate::selections::{Selections, SelectionsError};
-use crate::history::{Operation, Change, ChangeSet};
+use crate::selection_history::{SelectionHistory, SelectionChange};
+use crate::history::{Operation, Change, ChangeSet, UndoTree};
+use crate::register::{Registers, RegisterName};
+use crate::undo_file;
+use crate::text_util;
+use crate::clipboard::ClipboardError;
+use crate::shell::ShellError;
+use crate::completion::WordIndex;
 use std::fs::File;
 use std::error::Error;
 use std::io::BufReader;
@@ -18,25 +25,39 @@ use ropey::Rope;
 
 
 
+/// The indentation used by a document's text. See [`Document::indent_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle{
+    HardTab,
+    /// Indentation made of the held number of spaces per indent level
+    Spaces(usize),
+}
+
 /// Represents errors that can occur when performing operations on a document.
 #[derive(Debug)]
 pub enum DocumentError{
     NoChangesToUndo,
     NoChangesToRedo,
+    NoOtherUndoBranch,
+    NoEarlierSelections,
+    NoLaterSelections,
     SelectionAtDocBounds,
     InvalidInput,
-    SelectionsError(SelectionsError)
+    SelectionsError(SelectionsError),
+    ClipboardError(ClipboardError),
+    ShellError(ShellError),
 }
-/// Holds the document instance's text, selection data, and other state like undo/redo stacks and clipboard.
+/// Holds the document instance's text, selection data, and other state like undo/redo stacks and registers.
 pub struct Document{
     pub text: Rope, //the actual text buffer being edited
     pub file_path: Option<PathBuf>,
     pub selections: Selections, //Hashmap<ClientID, Selections>
+    pub selection_history: SelectionHistory,
     pub client_view: View,      //Hashmap<ClientID, View>       //TODO: client should pass view as param where needed, instead of storing this. this ensures a single source of truth (the client)
-    pub undo_stack: Vec<ChangeSet>,
-    pub redo_stack: Vec<ChangeSet>,
+    pub undo_tree: UndoTree,    //every change set ever made, including undone branches. the only record of undo/redo history
     pub last_saved_text: Rope,
-    pub clipboard: String,
+    pub registers: Registers,
+    pub word_index: WordIndex,  //words used in text, for completion. updated as change sets are applied
 }
 impl Document{
     ////////////////////////////////////////////////////////////////////// Testing Only ///////////////////////////////////////////////////////////////////////////
@@ -47,6 +68,7 @@ impl Document{
     /// Add [Rope]-based text to an existing instance of [Document]. Only for testing. Potentially also useful for opening a scratch buffer with content from stdin
     #[must_use] pub fn with_text(mut self, text: Rope) -> Self{
         self.text = text.clone();
+        self.word_index = WordIndex::new(&text);
         self.last_saved_text = text;
         self
     }
@@ -60,14 +82,14 @@ impl Document{
         self.client_view = view;
         self
     }
-    /// Add [String]-based text to an existing instance of [Document]. Clipboard is scoped to the editor only, not the system clipboard. Only for testing.
-    #[must_use] pub fn with_clipboard(mut self, clipboard: String) -> Self{
-        self.clipboard = clipboard;
+    /// Store `fragments` in register `name` of an existing instance of [Document]. Registers are scoped to the editor only, not the system clipboard. Only for testing.
+    #[must_use] pub fn with_register(mut self, name: RegisterName, fragments: Vec<String>) -> Self{
+        self.registers.set(name, fragments);
         self
     }
     /// Add [Vec<ChangeSet>] undo stack to an existing instance of [Document]. Only for testing.
     #[must_use] pub fn with_undo_stack(mut self, undo_stack: Vec<ChangeSet>) -> Self{
-        self.undo_stack = undo_stack;
+        self.undo_tree = UndoTree::from_stacks(&undo_stack, &[]);
         self
     }
     /// Add `last_saved_text` to an existing instance of [Document]. Only for testing.
@@ -81,9 +103,14 @@ impl Document{
     pub fn open(path: &PathBuf, cursor_semantics: CursorSemantics) -> Result<Self, Box<dyn Error>>{
         let text = Rope::from_reader(BufReader::new(File::open(path)?))?;   // pass errors up
 
-        // TODO: make text tab use match settings for USE_HARD_TAB and TAB_WIDTH
+        // text tabs are left as is. a frontend can compare `indent_style` with its settings, and use the `convert_indentation` utility
 
-        Ok(Self::initialize_fields(Some(path.clone()), &text, cursor_semantics))
+        let mut document = Self::initialize_fields(Some(path.clone()), &text, cursor_semantics);
+        // restore undo history saved alongside the file. history is dropped if the file was changed since, or the undo file is corrupt
+        if let Ok(undo_tree) = undo_file::load(path, &text){
+            document.undo_tree = undo_tree;
+        }
+        Ok(document)
     }
     fn initialize_fields(
         file_path: Option<PathBuf>,
@@ -98,14 +125,40 @@ impl Document{
             text: text.clone(),
             file_path,
             selections,
+            selection_history: SelectionHistory::default(),
             client_view: View::default(),
-            undo_stack: Vec::new(),
-            redo_stack: Vec::new(),
+            undo_tree: UndoTree::default(),
             last_saved_text: text.clone(),
-            clipboard: String::new(),
+            registers: Registers::default(),
+            word_index: WordIndex::new(text),
         }
     }
 
+    /// Records `change_set` as the latest edit, as a new branch of the undo tree. Redo follows the new branch, so it has nothing
+    /// to re-apply(undone changes remain reachable through the undo tree).
+    pub fn push_change_set(&mut self, change_set: ChangeSet, semantics: CursorSemantics){
+        self.selection_history.map_edits(&change_set.edits(), &self.text, semantics);
+        self.word_index.update(&change_set.edits(), &self.text);
+        self.undo_tree.push(change_set);
+    }
+
+    /// Returns the [`ChangeSet`]s undo can revert, oldest first. A read only view of the current branch of the undo tree.
+    #[must_use] pub fn undo_stack(&self) -> Vec<ChangeSet>{
+        self.undo_tree.undo_stack()
+    }
+    /// Returns the [`ChangeSet`]s redo can re-apply, next redo last. A read only view of the current branch of the undo tree.
+    #[must_use] pub fn redo_stack(&self) -> Vec<ChangeSet>{
+        self.undo_tree.redo_stack()
+    }
+
+    /// Replaces the document's selections with `selections`, recording the replaced selections in the selection history.
+    /// Does nothing if `selections` match the current selections.
+    pub fn set_selections(&mut self, selections: Selections, change: SelectionChange){
+        if selections == self.selections{return;}
+        let previous = std::mem::replace(&mut self.selections, selections);
+        self.selection_history.record(previous, change);
+    }
+
     // TODO: document + test
     #[must_use] pub fn file_name(&self, use_full_file_path: bool) -> Option<String>{
         match &self.file_path{
@@ -134,6 +187,19 @@ impl Document{
         self.text.len_lines()
     }
 
+    /// Returns the indentation style detected from the document's text, or `None` if no line is indented.
+    /// ```
+    /// # use ropey::Rope;
+    /// # use edit_core::document::{Document, IndentStyle};
+    /// # use edit_core::selection::CursorSemantics;
+    ///
+    /// let doc = Document::new(CursorSemantics::Bar).with_text(Rope::from("idk{\n  some\n    shit\n  }\n}\n"));
+    /// assert_eq!(Some(IndentStyle::Spaces(2)), doc.indent_style());
+    /// ```
+    #[must_use] pub fn indent_style(&self) -> Option<IndentStyle>{
+        text_util::indent_style(&self.text)
+    }
+
     //TODO: document + test
     #[must_use] pub fn is_modified(&self) -> bool{
         self.text != self.last_saved_text
@@ -151,13 +217,11 @@ impl Document{
     // TODO: test. should test rope is edited correctly and selection is moved correctly, not necessarily the returned change. behavior, not impl
     pub fn apply_insert(doc_text: &mut Rope, string: &str, selection: &mut Selection, semantics: CursorSemantics) -> Change{    //TODO: Error if string is empty
         let old_selection = selection.clone();
-        doc_text.insert(selection.cursor(doc_text, semantics), string);
-        for _ in 0..string.len(){
-            //*selection = selection.move_right(doc_text, semantics);
-            //if let Ok(new_selection) = selection.move_right(doc_text, semantics){
-            if let Ok(new_selection) = crate::utilities::move_cursor_right::selection_impl(selection, doc_text, semantics){
-                *selection = new_selection;
-            }
+        let cursor = selection.cursor(doc_text, semantics);
+        doc_text.insert(cursor, string);
+        // selection indices are char indices, so the cursor must move by the inserted char count, not its byte len
+        if let Ok(new_selection) = selection.put_cursor(cursor.saturating_add(string.chars().count()), doc_text, Movement::Move, semantics, true){
+            *selection = new_selection;
         }
 
         Change::new(Operation::Insert{inserted_text: string.to_string()}, old_selection, selection.clone(), Operation::Delete)
@@ -202,6 +266,70 @@ impl Document{
 
         Change::new(Operation::Delete, old_selection, selection.clone(), Operation::Insert{inserted_text: change_text.to_string()})
     }
+
+    /// Replaces the text in each range of `replacements` with its string, and records every edit as a single change set.
+    /// There must be one replacement per selection, with ranges in text order that don't overlap.
+    /// `new_selection` is called with each replacement's index, the range of its new text, and the cursor left after its new text,
+    /// and returns the selection to leave for that replacement.
+    /// Errors, leaving text and selections unchanged, if no replacement changes the text.
+    pub(crate) fn replace_ranges(
+        &mut self,
+        replacements: &[(Range, &str)],
+        mut new_selection: impl FnMut(usize, Range, Selection) -> Selection,
+        semantics: CursorSemantics
+    ) -> Result<(), DocumentError>{
+        let selections_before_changes = self.selections.clone();
+        let mut changes = Vec::with_capacity(replacements.len());
+        let mut new_selections = Vec::with_capacity(replacements.len());
+        // ranges are in text order, so each is offset by the length change of all replacements before it
+        let mut offset: isize = 0;
+        for (i, (range, content)) in replacements.iter().enumerate(){
+            let (start, end) = (range.start.saturating_add_signed(offset), range.end.saturating_add_signed(offset));
+            let content_len = content.chars().count();
+            let mut replaced = if start == end{
+                // nothing is replaced, so the content is only inserted, at a cursor
+                Selection::new(Range::new(start, match semantics{CursorSemantics::Bar => start, CursorSemantics::Block => start + 1}), Direction::Forward)
+            }else{Selection::new(Range::new(start, end), Direction::Forward)};
+            match (start == end, content.is_empty()){
+                (true, true) => {}
+                (true, false) => changes.push(Document::apply_insert(&mut self.text, content, &mut replaced, semantics)),
+                (false, true) => changes.push(Document::apply_delete(&mut self.text, &mut replaced, semantics)),
+                (false, false) => changes.push(Document::apply_replace(&mut self.text, content, &mut replaced, semantics))
+            }
+            new_selections.push(new_selection(i, Range::new(start, start + content_len), replaced));
+            offset += content_len as isize - (end - start) as isize;
+        }
+        if changes.is_empty(){return Err(DocumentError::InvalidInput);}
+
+        self.selections = Selections::new(new_selections, selections_before_changes.primary_selection_index, &self.text, semantics);
+        self.push_change_set(ChangeSet::new(changes, selections_before_changes, self.selections.clone(), semantics), semantics);
+        Ok(())
+    }
+
+    /// Returns the text of each selection, in selection order.
+    pub(crate) fn selection_contents(&self) -> Vec<String>{
+        self.selections.iter().map(|selection| selection.contents_as_string(&self.text)).collect()
+    }
+
+    /// Replaces the text of each selection with `contents[i]`, using [`Document::replace_ranges`], and selects the new text.
+    /// All replacements are recorded as a single change set.
+    /// Errors if there is only one selection, or if `contents` matches the current contents.
+    pub(crate) fn replace_selection_contents(&mut self, contents: Vec<String>, semantics: CursorSemantics) -> Result<(), DocumentError>{
+        if self.selections.count() < 2{return Err(DocumentError::SelectionsError(SelectionsError::SingleSelection));}
+        if contents == self.selection_contents(){return Err(DocumentError::SelectionsError(SelectionsError::ResultsInSameState));}
+
+        let replacements: Vec<(Range, &str)> = self.selections.iter().zip(&contents).map(|(selection, content)| (selection.range.clone(), content.as_str())).collect();
+        let directions: Vec<Direction> = self.selections.iter().map(|selection| selection.direction).collect();
+        self.replace_ranges(&replacements, |i, new_text, _| Selection::new(Self::selected_text(new_text, semantics), directions[i]), semantics)
+    }
+
+    /// Returns the range selecting `new_text`, or a cursor at its start if it is empty.
+    pub(crate) fn selected_text(new_text: Range, semantics: CursorSemantics) -> Range{
+        match (new_text.start == new_text.end, semantics){
+            (true, CursorSemantics::Block) => Range::new(new_text.start, new_text.start + 1),
+            _ => new_text
+        }
+    }
     
     
 
@@ -1,36 +1,203 @@
-use crate::document::Document;
-use crate::id::ClientID;
-use std::{collections::HashMap, error::Error, path::PathBuf};
+use crate::clipboard::{self, ClipboardProvider};
+use crate::document::{Document, DocumentError};
+use crate::history::UndoNode;
+use crate::id::{ClientID, ClientIDManager};
+use crate::range::Range;
+use crate::selection::{CursorSemantics, Direction, Selection};
+use crate::selections::Selections;
+use crate::selection_history::SelectionHistory;
+use crate::view::View;
+use crate::utilities::save::SaveOutcome;
+use ropey::Rope;
+use std::{collections::HashMap, error::Error, path::{Path, PathBuf}};
 
 
 
+#[derive(Debug)]
+pub enum EditorError{
+    InvalidClient,
+    NoOpenDocument,
+    DocumentError(DocumentError),
+}
+
+/// A client's personal state within a shared [`Document`].
+pub struct Client{
+    document_path: Option<PathBuf>,
+    pub selections: Selections,
+    pub selection_history: SelectionHistory,
+    pub view: View,
+    pub semantics: CursorSemantics,
+}
+
 /// An Editor holds documents, and handles document interactions.
-#[derive(Default)]
+/// Each document is opened once per path, and shared between every client that opens that path.
+/// Each client has its own [`Selections`], selection history, and [`View`] into a shared document.
+/// Every document shares the editor's system clipboard.
 pub struct Editor{
-    documents: HashMap<ClientID, Document>, //how could this be made to handle multiple clients manipulating the same document?
+    documents: HashMap<PathBuf, Document>,
+    clients: HashMap<ClientID, Client>,
+    id_manager: ClientIDManager,
+    clipboard: Box<dyn ClipboardProvider>,  //the system clipboard, used by the Clipboard register
+}
+impl Default for Editor{
+    fn default() -> Self{
+        Self{
+            documents: HashMap::new(),
+            clients: HashMap::new(),
+            id_manager: ClientIDManager::default(),
+            clipboard: clipboard::detect(),
+        }
+    }
 }
 impl Editor{
+    /// Uses `clipboard` as the system clipboard, in place of the one found by [`clipboard::detect`].
+    /// [`Osc52Provider`](crate::clipboard::Osc52Provider) is never detected, so a frontend that wants it must set it here.
+    pub fn set_clipboard(&mut self, clipboard: Box<dyn ClipboardProvider>){
+        self.clipboard = clipboard;
+    }
+    /// Returns the system clipboard used by every document.
+    pub fn clipboard_mut(&mut self) -> &mut dyn ClipboardProvider{
+        self.clipboard.as_mut()
+    }
+
+    /// Assigns a new `ClientID`. The client has no open document until [`Editor::open_document`] is called.
+    pub fn connect_client(&mut self, semantics: CursorSemantics) -> ClientID{
+        let client_id = self.id_manager.assign_id();
+        self.clients.insert(client_id, Client{
+            document_path: None,
+            selections: Self::initial_selections(&Rope::new(), semantics),
+            selection_history: SelectionHistory::default(),
+            view: View::default(),
+            semantics,
+        });
+        client_id
+    }
+
+    /// Closes any document associated with `ClientID`, and releases `ClientID` for reuse.
+    pub fn disconnect_client(&mut self, client_id: ClientID){
+        self.close_document(client_id);
+        if self.clients.remove(&client_id).is_some(){
+            self.id_manager.release_id(client_id);
+        }
+    }
+
+    /// Returns a reference to the client state associated with `ClientID`, if one exists.
+    #[must_use] pub fn client(&self, client_id: ClientID) -> Option<&Client>{self.clients.get(&client_id)}
+
+    /// Returns a mutable reference to the client state associated with `ClientID`, if one exists.
+    pub fn client_mut(&mut self, client_id: ClientID) -> Option<&mut Client>{self.clients.get_mut(&client_id)}
+
     /// Returns a reference to the document associated with `ClientID`, if one exists.
-    #[must_use] pub fn document(&self, client_id: ClientID) -> Option<&Document>{self.documents.get(&client_id)}
-    
-    /// Returns a mutable reference to the document associated with `ClientID`, if one exists.
-    pub fn document_mut(&mut self, client_id: ClientID) -> Option<&mut Document>{self.documents.get_mut(&client_id)}
-    
+    /// The returned document's `selections` and `client_view` are not necessarily this client's. use [`Editor::client`] for those.
+    #[must_use] pub fn document(&self, client_id: ClientID) -> Option<&Document>{
+        let path = self.clients.get(&client_id)?.document_path.as_ref()?;
+        self.documents.get(path)
+    }
+
     /// Attempts to open specified document, and associate it with `ClientID`.
-    pub fn open_document(&mut self, path: &PathBuf, client_id: ClientID) -> Result<(), Box<dyn Error>>{
-        // could the hashmap use path as key and doc as value, to allow multiple clients manipulating same doc?
-        // if hashmap contains doc with key path, return
-        let doc = Document::open(path, crate::selection::CursorSemantics::Bar)?;
-        self.documents.insert(client_id, doc);
+    /// If the document is already open for another client, the same document is shared, instead of being opened again.
+    /// Reopening the client's current document keeps the document, and only resets the client's selections and view.
+    pub fn open_document(&mut self, path: &Path, client_id: ClientID) -> Result<(), Box<dyn Error>>{
+        let Some(client) = self.clients.get(&client_id) else{return Err("no client with this ClientID is connected".into());};
+        let path = Self::document_key(path);
+        // closing the current document first would drop it, if this client were its only one
+        let reopening = client.document_path.as_ref() == Some(&path);
+
+        if !self.documents.contains_key(&path){
+            let doc = Document::open(&path, client.semantics)?;    //document selections are replaced by the acting client's
+            self.documents.insert(path.clone(), doc);
+        }
+        if !reopening{self.close_document(client_id);}
+
+        let text = &self.documents[&path].text;
+        if let Some(client) = self.clients.get_mut(&client_id){
+            client.selections = Self::initial_selections(text, client.semantics);
+            client.selection_history = SelectionHistory::default();
+            client.view = View::default();
+            client.document_path = Some(path);
+        }
 
         Ok(())
     }
-    
-    /// Removes the document associated with `ClientID`, if one exists.
-    // TODO: if doc is associated with multiple client ids, remove association with this ClientID
+
+    /// Removes association between `ClientID` and its document, if one exists.
+    /// The document itself is removed once no clients are associated with it.
     pub fn close_document(&mut self, client_id: ClientID){
-        if self.documents.contains_key(&client_id){
-            self.documents.remove(&client_id);
+        let Some(path) = self.clients.get_mut(&client_id).and_then(|client| client.document_path.take()) else{return;};
+        let still_open = self.clients.values().any(|client| client.document_path.as_ref() == Some(&path));
+        if !still_open{
+            self.documents.remove(&path);
+        }
+    }
+
+    /// Performs `action` on the document associated with `ClientID`, using that client's [`Selections`], selection history, and [`View`].
+    /// Any text changes made by `action` are mapped over the [`Selections`] of every other client sharing the document.
+    /// `action` is intended to be one of the `utilities::*::document_impl` fns.
+    pub fn apply<F>(&mut self, client_id: ClientID, action: F) -> Result<(), EditorError>
+        where F: FnOnce(&mut Document) -> Result<(), DocumentError>
+    {
+        self.apply_with_clipboard(client_id, |document, _| action(document))
+    }
+
+    /// Same as [`Editor::apply`], but `action` is also given the system clipboard, for utilities like copy/cut/paste.
+    pub fn apply_with_clipboard<F>(&mut self, client_id: ClientID, action: F) -> Result<(), EditorError>
+        where F: FnOnce(&mut Document, &mut dyn ClipboardProvider) -> Result<(), DocumentError>
+    {
+        let Some(client) = self.clients.get_mut(&client_id) else{return Err(EditorError::InvalidClient);};
+        let Some(path) = client.document_path.clone() else{return Err(EditorError::NoOpenDocument);};
+        let Some(document) = self.documents.get_mut(&path) else{return Err(EditorError::NoOpenDocument);};
+
+        // swap this client's state into the document, so utilities operate on it
+        std::mem::swap(&mut document.selections, &mut client.selections);
+        std::mem::swap(&mut document.selection_history, &mut client.selection_history);
+        std::mem::swap(&mut document.client_view, &mut client.view);
+        let state_before = document.undo_tree.current();
+
+        let result = action(document, self.clipboard.as_mut());
+
+        std::mem::swap(&mut document.selections, &mut client.selections);
+        std::mem::swap(&mut document.selection_history, &mut client.selection_history);
+        std::mem::swap(&mut document.client_view, &mut client.view);
+
+        // determine which change sets were reverted(undo) and applied(edit/redo) by action, from the undo tree path it moved along
+        let mut edits = Vec::new();
+        let (undone, redone) = document.undo_tree.path(state_before, document.undo_tree.current());
+        for id in undone{
+            if let Some(change_set) = document.undo_tree.node(id).and_then(UndoNode::change_set){edits.extend(change_set.inverse_edits());}
         }
+        for id in redone{
+            if let Some(change_set) = document.undo_tree.node(id).and_then(UndoNode::change_set){edits.extend(change_set.edits());}
+        }
+
+        if !edits.is_empty(){
+            for (id, other) in &mut self.clients{
+                if *id != client_id && other.document_path.as_ref() == Some(&path){
+                    other.selections = other.selections.map_edits(&edits, &document.text, other.semantics);
+                    other.selection_history.map_edits(&edits, &document.text, other.semantics);
+                }
+            }
+        }
+
+        result.map_err(EditorError::DocumentError)
+    }
+
+    /// Saves the document associated with `ClientID` to its file path, along with its undo history.
+    pub fn save(&mut self, client_id: ClientID) -> Result<SaveOutcome, Box<dyn Error>>{
+        let Some(client) = self.clients.get(&client_id) else{return Err("no client with this ClientID is connected".into());};
+        let Some(document) = client.document_path.as_ref().and_then(|path| self.documents.get_mut(path)) else{return Err("no document is open for this client".into());};
+        crate::utilities::save::document_impl(document)
+    }
+
+    /// Returns the [`Selections`] a client starts with in a newly opened document: a single cursor at text start.
+    fn initial_selections(text: &Rope, semantics: CursorSemantics) -> Selections{
+        match semantics{
+            CursorSemantics::Bar => Selections::new(vec![Selection::new(Range::new(0, 0), Direction::Forward)], 0, text, semantics),
+            CursorSemantics::Block => Selections::new(vec![Selection::new(Range::new(0, 1), Direction::Forward)], 0, text, semantics)
+        }
+    }
+
+    /// Returns the key a document at `path` is stored under, so that different spellings of the same path share a document.
+    fn document_key(path: &Path) -> PathBuf{
+        std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
     }
 }
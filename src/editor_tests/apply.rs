@@ -0,0 +1,123 @@
+use ropey::Rope;
+use crate::editor::{Editor, EditorError};
+use crate::selection::{Selection, CursorSemantics};
+use crate::selections::Selections;
+use crate::utilities::{insert_string, delete, undo, redo, undo_earlier, jump_back, move_cursor_right, move_cursor_document_end};
+use super::temp_file;
+
+fn setup(name: &str, text: &str, semantics_a: CursorSemantics, semantics_b: CursorSemantics) -> (Editor, usize, usize, std::path::PathBuf){
+    let path = temp_file(name, text);
+    let mut editor = Editor::default();
+    let client_a = editor.connect_client(semantics_a);
+    let client_b = editor.connect_client(semantics_b);
+    assert!(editor.open_document(&path, client_a).is_ok());
+    assert!(editor.open_document(&path, client_b).is_ok());
+    (editor, client_a, client_b, path)
+}
+fn set_selections(editor: &mut Editor, client: usize, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+    let text = editor.document(client).unwrap().text.clone();
+    let semantics = editor.client(client).unwrap().semantics;
+    let mut vec_selections = Vec::new();
+    for tuple in tuple_selections{
+        vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+    }
+    editor.client_mut(client).unwrap().selections = Selections::new(vec_selections, primary, &text, semantics);
+}
+fn assert_selections(editor: &Editor, client: usize, tuple_expected_selections: Vec<(usize, usize)>){
+    let selections = &editor.client(client).unwrap().selections;
+    let ranges: Vec<(usize, usize)> = selections.iter().map(|selection| (selection.anchor(), selection.head())).collect();
+    assert_eq!(tuple_expected_selections, ranges);
+}
+
+#[test] fn edit_by_one_client_shifts_other_clients_selections(){
+    let (mut editor, client_a, client_b, path) = setup("edit_by_one_client_shifts_other_clients_selections", "idk\nsome\nshit\n", CursorSemantics::Bar, CursorSemantics::Block);
+    set_selections(&mut editor, client_b, vec![(0, 1, None), (4, 6, None)], 1);
+    assert!(editor.apply(client_a, |doc| insert_string::document_impl(doc, "xy", false, 4, CursorSemantics::Bar)).is_ok());
+    assert_eq!(Rope::from("xyidk\nsome\nshit\n"), editor.document(client_b).unwrap().text);
+    assert_selections(&editor, client_a, vec![(2, 2)]);
+    assert_selections(&editor, client_b, vec![(2, 3), (6, 8)]);
+    assert_eq!(1, editor.client(client_b).unwrap().selections.primary_selection_index);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn deletion_collapses_other_clients_selections_inside_deleted_text(){
+    let (mut editor, client_a, client_b, path) = setup("deletion_collapses_other_clients_selections_inside_deleted_text", "idk\nsome\nshit\n", CursorSemantics::Bar, CursorSemantics::Bar);
+    set_selections(&mut editor, client_a, vec![(4, 9, None)], 0);
+    set_selections(&mut editor, client_b, vec![(6, 6, None), (10, 12, None)], 0);
+    assert!(editor.apply(client_a, |doc| delete::document_impl(doc, 1, CursorSemantics::Bar)).is_ok());
+    assert_eq!(Rope::from("idk\nshit\n"), editor.document(client_b).unwrap().text);
+    assert_selections(&editor, client_b, vec![(4, 4), (5, 7)]);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn undo_and_redo_by_one_client_shift_other_clients_selections(){
+    let (mut editor, client_a, client_b, path) = setup("undo_and_redo_by_one_client_shift_other_clients_selections", "idk\nsome\nshit\n", CursorSemantics::Bar, CursorSemantics::Bar);
+    set_selections(&mut editor, client_b, vec![(9, 9, None)], 0);
+    assert!(editor.apply(client_a, |doc| insert_string::document_impl(doc, "a", false, 4, CursorSemantics::Bar)).is_ok());
+    assert!(editor.apply(client_a, |doc| insert_string::document_impl(doc, "b", false, 4, CursorSemantics::Bar)).is_ok());
+    assert_selections(&editor, client_b, vec![(11, 11)]);
+    assert!(editor.apply(client_a, |doc| undo::document_impl(doc, CursorSemantics::Bar)).is_ok());
+    assert_eq!(Rope::from("idk\nsome\nshit\n"), editor.document(client_b).unwrap().text);
+    assert_selections(&editor, client_b, vec![(9, 9)]);
+    assert!(editor.apply(client_a, |doc| redo::document_impl(doc, CursorSemantics::Bar)).is_ok());
+    assert_selections(&editor, client_b, vec![(11, 11)]);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn selection_movement_does_not_affect_other_clients(){
+    let (mut editor, client_a, client_b, path) = setup("selection_movement_does_not_affect_other_clients", "idk\nsome\nshit\n", CursorSemantics::Bar, CursorSemantics::Bar);
+    assert!(editor.apply(client_a, |doc| move_cursor_right::document_impl(doc, 1, CursorSemantics::Bar)).is_ok());
+    assert_selections(&editor, client_a, vec![(1, 1)]);
+    assert_selections(&editor, client_b, vec![(0, 0)]);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn errors_without_open_document(){
+    let mut editor = Editor::default();
+    let client = editor.connect_client(CursorSemantics::Bar);
+    assert!(matches!(editor.apply(client, |doc| move_cursor_right::document_impl(doc, 1, CursorSemantics::Bar)), Err(EditorError::NoOpenDocument)));
+    assert!(matches!(editor.apply(client + 1, |doc| move_cursor_right::document_impl(doc, 1, CursorSemantics::Bar)), Err(EditorError::InvalidClient)));
+}
+
+#[test] fn undo_tree_navigation_shifts_other_clients_selections(){
+    let (mut editor, client_a, client_b, path) = setup("undo_tree_navigation_shifts_other_clients_selections", "idk\n", CursorSemantics::Bar, CursorSemantics::Bar);
+    set_selections(&mut editor, client_b, vec![(3, 3, None)], 0);
+    assert!(editor.apply(client_a, |doc| insert_string::document_impl(doc, "some\n", false, 4, CursorSemantics::Bar)).is_ok());
+    assert!(editor.apply(client_a, |doc| undo::document_impl(doc, CursorSemantics::Bar)).is_ok());
+    assert!(editor.apply(client_a, |doc| insert_string::document_impl(doc, "x", false, 4, CursorSemantics::Bar)).is_ok());
+    assert_selections(&editor, client_b, vec![(4, 4)]);
+    // moving back in time to "some\n" undoes "x" and redoes "some\n"
+    assert!(editor.apply(client_a, |doc| undo_earlier::document_impl(doc, CursorSemantics::Bar)).is_ok());
+    assert_eq!(Rope::from("some\nidk\n"), editor.document(client_b).unwrap().text);
+    assert_selections(&editor, client_b, vec![(8, 8)]);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn each_client_has_its_own_selection_history(){
+    let (mut editor, client_a, client_b, path) = setup("each_client_has_its_own_selection_history", "idk\nsome\nshit\n", CursorSemantics::Bar, CursorSemantics::Bar);
+    assert!(editor.apply(client_b, |doc| move_cursor_document_end::document_impl(doc, CursorSemantics::Bar)).is_ok());
+    assert!(editor.apply(client_a, jump_back::document_impl).is_err());
+    // client_b's recorded selections follow client_a's edits
+    assert!(editor.apply(client_a, |doc| insert_string::document_impl(doc, "xy", false, 4, CursorSemantics::Bar)).is_ok());
+    assert!(editor.apply(client_b, jump_back::document_impl).is_ok());
+    assert_selections(&editor, client_b, vec![(2, 2)]);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn undo_and_redo_replay_changes_with_the_semantics_they_were_made_with(){
+    let (mut editor, client_a, client_b, path) = setup("undo_and_redo_replay_changes_with_the_semantics_they_were_made_with", "idk\nsome\n", CursorSemantics::Block, CursorSemantics::Bar);
+    set_selections(&mut editor, client_b, vec![(4, 4, None)], 0);
+    assert!(editor.apply(client_a, |doc| insert_string::document_impl(doc, "x", false, 4, CursorSemantics::Block)).is_ok());
+    assert_eq!(Rope::from("xidk\nsome\n"), editor.document(client_a).unwrap().text);
+    assert_selections(&editor, client_b, vec![(5, 5)]);
+
+    assert!(editor.apply(client_b, |doc| undo::document_impl(doc, CursorSemantics::Bar)).is_ok());
+    assert_eq!(Rope::from("idk\nsome\n"), editor.document(client_b).unwrap().text);
+    assert_selections(&editor, client_b, vec![(4, 4)]);
+    assert_selections(&editor, client_a, vec![(0, 1)]);
+
+    assert!(editor.apply(client_b, |doc| redo::document_impl(doc, CursorSemantics::Bar)).is_ok());
+    assert_eq!(Rope::from("xidk\nsome\n"), editor.document(client_b).unwrap().text);
+    assert_selections(&editor, client_b, vec![(5, 5)]);
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,42 @@
+use ropey::Rope;
+use crate::clipboard::{InMemoryProvider, Osc52Provider};
+use crate::editor::Editor;
+use crate::register::RegisterName;
+use crate::selection::CursorSemantics;
+use crate::utilities::{copy, paste, select_line};
+use super::temp_file;
+
+#[test] fn documents_share_the_editors_clipboard(){
+    let path_a = temp_file("documents_share_the_editors_clipboard_a", "idk\n");
+    let path_b = temp_file("documents_share_the_editors_clipboard_b", "some\n");
+    let semantics = CursorSemantics::Bar;
+    let mut editor = Editor::default();
+    editor.set_clipboard(Box::new(InMemoryProvider::default()));
+    let client_a = editor.connect_client(semantics);
+    let client_b = editor.connect_client(semantics);
+    assert!(editor.open_document(&path_a, client_a).is_ok());
+    assert!(editor.open_document(&path_b, client_b).is_ok());
+
+    assert!(editor.apply(client_a, |doc| select_line::document_impl(doc, semantics)).is_ok());
+    assert!(editor.apply_with_clipboard(client_a, |doc, clipboard| copy::document_impl(doc, RegisterName::Clipboard, clipboard)).is_ok());
+    assert_eq!("idk\n", editor.clipboard_mut().get_contents().unwrap());
+    assert!(editor.apply_with_clipboard(client_b, |doc, clipboard| paste::document_impl(doc, RegisterName::Clipboard, clipboard, false, 4, semantics)).is_ok());
+    assert_eq!(Rope::from("idk\nsome\n"), editor.document(client_b).unwrap().text);
+    let _ = std::fs::remove_file(path_a);
+    let _ = std::fs::remove_file(path_b);
+}
+
+#[test] fn set_clipboard_selects_osc52(){
+    let path = temp_file("set_clipboard_selects_osc52", "idk\n");
+    let semantics = CursorSemantics::Bar;
+    let mut editor = Editor::default();
+    editor.set_clipboard(Box::new(Osc52Provider::new(Box::new(std::io::sink()))));
+    let client = editor.connect_client(semantics);
+    assert!(editor.open_document(&path, client).is_ok());
+
+    assert!(editor.apply(client, |doc| select_line::document_impl(doc, semantics)).is_ok());
+    assert!(editor.apply_with_clipboard(client, |doc, clipboard| copy::document_impl(doc, RegisterName::Clipboard, clipboard)).is_ok());
+    assert_eq!("osc52", editor.clipboard_mut().name());
+    assert_eq!("idk\n", editor.clipboard_mut().get_contents().unwrap());
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,20 @@
+use crate::editor::Editor;
+use crate::selection::CursorSemantics;
+use super::temp_file;
+
+#[test] fn document_stays_open_while_other_clients_use_it(){
+    let path = temp_file("document_stays_open_while_other_clients_use_it", "idk\nsome\nshit\n");
+    let mut editor = Editor::default();
+    let client_a = editor.connect_client(CursorSemantics::Bar);
+    let client_b = editor.connect_client(CursorSemantics::Bar);
+    assert!(editor.open_document(&path, client_a).is_ok());
+    assert!(editor.open_document(&path, client_b).is_ok());
+
+    editor.close_document(client_a);
+    assert!(editor.document(client_a).is_none());
+    assert!(editor.document(client_b).is_some());
+
+    editor.close_document(client_b);
+    assert!(editor.document(client_b).is_none());
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,12 @@
+use crate::editor::Editor;
+use crate::selection::CursorSemantics;
+
+#[test] fn releases_client_id_for_reuse(){
+    let mut editor = Editor::default();
+    let client_a = editor.connect_client(CursorSemantics::Bar);
+    let client_b = editor.connect_client(CursorSemantics::Bar);
+    editor.disconnect_client(client_a);
+    assert!(editor.client(client_a).is_none());
+    assert!(editor.client(client_b).is_some());
+    assert_eq!(client_a, editor.connect_client(CursorSemantics::Bar));
+}
@@ -0,0 +1,12 @@
+mod open_document;
+mod close_document;
+mod disconnect_client;
+mod apply;
+mod clipboard;
+
+/// Writes `contents` to a file in the system temp dir, unique to the calling test, and returns its path.
+fn temp_file(name: &str, contents: &str) -> std::path::PathBuf{
+    let path = std::env::temp_dir().join(format!("edit_core_editor_tests_{}_{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
@@ -0,0 +1,57 @@
+use ropey::Rope;
+use crate::editor::Editor;
+use crate::selection::CursorSemantics;
+use crate::utilities::insert_string;
+use super::temp_file;
+
+#[test] fn clients_opening_same_path_share_document(){
+    let path = temp_file("clients_opening_same_path_share_document", "idk\nsome\nshit\n");
+    let mut editor = Editor::default();
+    let client_a = editor.connect_client(CursorSemantics::Bar);
+    let client_b = editor.connect_client(CursorSemantics::Block);
+    assert!(editor.open_document(&path, client_a).is_ok());
+    assert!(editor.open_document(&path, client_b).is_ok());
+    assert!(std::ptr::eq(editor.document(client_a).unwrap(), editor.document(client_b).unwrap()));
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn each_client_gets_its_own_selections(){
+    let path = temp_file("each_client_gets_its_own_selections", "idk\nsome\nshit\n");
+    let mut editor = Editor::default();
+    let client_a = editor.connect_client(CursorSemantics::Bar);
+    let client_b = editor.connect_client(CursorSemantics::Block);
+    assert!(editor.open_document(&path, client_a).is_ok());
+    assert!(editor.open_document(&path, client_b).is_ok());
+    assert_eq!(0, editor.client(client_a).unwrap().selections.primary().range.end);
+    assert_eq!(1, editor.client(client_b).unwrap().selections.primary().range.end);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn reopening_current_document_keeps_it_open(){
+    let path = temp_file("reopening_current_document_keeps_it_open", "idk\n");
+    let semantics = CursorSemantics::Bar;
+    let mut editor = Editor::default();
+    let client = editor.connect_client(semantics);
+    assert!(editor.open_document(&path, client).is_ok());
+    assert!(editor.apply(client, |doc| insert_string::document_impl(doc, "some\n", false, 4, semantics)).is_ok());
+    assert!(editor.open_document(&path, client).is_ok());
+    assert_eq!(Rope::from("some\nidk\n"), editor.document(client).unwrap().text);
+    assert_eq!(0, editor.client(client).unwrap().selections.primary().range.start);
+    assert!(editor.apply(client, |doc| insert_string::document_impl(doc, "x", false, 4, semantics)).is_ok());
+    assert_eq!(Rope::from("xsome\nidk\n"), editor.document(client).unwrap().text);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn errors_if_client_not_connected(){
+    let path = temp_file("errors_if_client_not_connected", "idk\n");
+    let mut editor = Editor::default();
+    assert!(editor.open_document(&path, 0).is_err());
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn errors_if_file_does_not_exist(){
+    let mut editor = Editor::default();
+    let client = editor.connect_client(CursorSemantics::Bar);
+    assert!(editor.open_document(&std::env::temp_dir().join("edit_core_editor_tests_file_that_does_not_exist"), client).is_err());
+    assert!(editor.document(client).is_none());
+}
@@ -1,5 +1,7 @@
-use crate::selection::Selection;
+use crate::selection::{Selection, CursorSemantics};
 use crate::selections::Selections;
+use crate::json::Json;
+use unicode_segmentation::UnicodeSegmentation;
 
 
 
@@ -12,6 +14,31 @@ pub enum Operation{
     ///operation associated with it, so that vec of changes and vec of selections are always same len
     NoOp,
 }
+impl Operation{
+    /// Returns a [`Json`] representation of [`Operation`], as stored in an undo file.
+    #[must_use]
+    pub fn to_json(&self) -> Json{
+        let (operation, text) = match self{
+            Operation::Insert{inserted_text} => ("insert", Json::from(inserted_text.as_str())),
+            Operation::Delete => ("delete", Json::Null),
+            Operation::Replace{replacement_text} => ("replace", Json::from(replacement_text.as_str())),
+            Operation::NoOp => ("no_op", Json::Null),
+        };
+        Json::Object(vec![("operation".to_string(), Json::from(operation)), ("text".to_string(), text)])
+    }
+    /// Returns the [`Operation`] represented by `json`, if `json` is valid output of [`Operation::to_json`].
+    #[must_use]
+    pub fn from_json(json: &Json) -> Option<Self>{
+        let text = || json.get("text").and_then(Json::as_str).map(str::to_string);
+        match json.get("operation")?.as_str()?{
+            "insert" => Some(Operation::Insert{inserted_text: text()?}),
+            "delete" => Some(Operation::Delete),
+            "replace" => Some(Operation::Replace{replacement_text: text()?}),
+            "no_op" => Some(Operation::NoOp),
+            _ => None
+        }
+    }
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Change{
@@ -34,9 +61,10 @@ impl Change{
     pub fn operation(&self) -> Operation{
         self.operation.clone()
     }
-    //pub fn selection_after_change(&self) -> Selection{    //this doesn't appear to be needed just yet...
-    //    self.selection_after_change.clone()
-    //}
+    #[must_use]
+    pub fn selection_after_change(&self) -> Selection{
+        self.selection_after_change.clone()
+    }
     #[must_use]
     pub fn selection_before_change(&self) -> Selection{
         self.selection_before_change.clone()
@@ -45,6 +73,49 @@ impl Change{
     pub fn inverse(&self) -> Operation{
         self.inverse_operation.clone()
     }
+    /// Returns a [`Json`] representation of [`Change`], as stored in an undo file.
+    #[must_use]
+    pub fn to_json(&self) -> Json{
+        Json::Object(vec![
+            ("operation".to_string(), self.operation.to_json()),
+            ("selection_before_change".to_string(), self.selection_before_change.to_json()),
+            ("selection_after_change".to_string(), self.selection_after_change.to_json()),
+            ("inverse_operation".to_string(), self.inverse_operation.to_json()),
+        ])
+    }
+    /// Returns the [`Change`] represented by `json`, if `json` is valid output of [`Change::to_json`].
+    #[must_use]
+    pub fn from_json(json: &Json) -> Option<Self>{
+        Some(Self{
+            operation: Operation::from_json(json.get("operation")?)?,
+            selection_before_change: Selection::from_json(json.get("selection_before_change")?)?,
+            selection_after_change: Selection::from_json(json.get("selection_after_change")?)?,
+            inverse_operation: Operation::from_json(json.get("inverse_operation")?)?,
+        })
+    }
+}
+
+/// A primitive edit over the char indices of a text: `removed` chars starting at `at` were replaced by `inserted` chars.
+/// Used to map positions that were not part of an edit(like another client's selections) across that edit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edit{
+    pub at: usize,
+    pub removed: usize,
+    pub inserted: usize,
+}
+impl Edit{
+    /// Returns `index` mapped across this [`Edit`]. Indices inside the removed text collapse to `at`.
+    #[must_use]
+    pub fn map(&self, index: usize) -> usize{
+        if index < self.at{index}
+        else if index >= self.at.saturating_add(self.removed){index.saturating_sub(self.removed).saturating_add(self.inserted)}
+        else{self.at}
+    }
+    /// Returns the [`Edit`] that reverts this one.
+    #[must_use]
+    pub fn inverse(&self) -> Self{
+        Self{at: self.at, removed: self.inserted, inserted: self.removed}
+    }
 }
 
 /// `ChangeSet` holds a vec of Changes that should coinside with the vec of Selection in Selections(so the change at changes[0], should be associated with the selection at selections[0])
@@ -53,11 +124,16 @@ pub struct ChangeSet{
     changes: Vec<Change>,
     selections_before_changes: Selections, //this could be selections without positions offset by any previous change applied     // could need this for certain things to work. ex. Backspace
     selections_after_changes: Selections,  //this is prob the same as selection_after_change from Change
+    /// `true` if this [`ChangeSet`] continues the typing/deletion run of the [`ChangeSet`] below it on the stack,
+    /// so undo/redo should treat both as a single unit
+    grouped_with_previous: bool,
+    /// the cursor semantics of the client that made the changes. undo/redo replay them with these, whatever the acting client's
+    semantics: CursorSemantics,
 }
 impl ChangeSet{
     #[must_use]
-    pub fn new(changes: Vec<Change>, selections_before_changes: Selections, selections_after_changes: Selections) -> Self{
-        Self{changes, selections_before_changes, selections_after_changes}
+    pub fn new(changes: Vec<Change>, selections_before_changes: Selections, selections_after_changes: Selections, semantics: CursorSemantics) -> Self{
+        Self{changes, selections_before_changes, selections_after_changes, grouped_with_previous: false, semantics}
     }
     #[must_use]
     pub fn changes(&self) -> Vec<Change>{
@@ -71,4 +147,312 @@ impl ChangeSet{
     pub fn selections_after_changes(self) -> Selections{
         self.selections_after_changes.clone()
     }
+    /// Returns the [`Edit`]s this [`ChangeSet`] applies to the text, in application order.
+    /// Each [`Change`]'s selections are already offset by any previous change applied, so edits can be mapped over in sequence.
+    #[must_use]
+    pub fn edits(&self) -> Vec<Edit>{
+        let mut edits = Vec::with_capacity(self.changes.len());
+        for change in &self.changes{
+            match (&change.operation, &change.inverse_operation){
+                // inserts only happen at non extended selections, where the cursor is at range start for either cursor semantics
+                (Operation::Insert{inserted_text}, _) => edits.push(Edit{at: change.selection_before_change.range.start, removed: 0, inserted: inserted_text.chars().count()}),
+                // deleting always leaves the selection at the start of the deleted text
+                (Operation::Delete, Operation::Insert{inserted_text}) => edits.push(Edit{at: change.selection_after_change.range.start, removed: inserted_text.chars().count(), inserted: 0}),
+                (Operation::Replace{replacement_text}, Operation::Replace{replacement_text: replaced_text}) => edits.push(Edit{at: change.selection_before_change.range.start, removed: replaced_text.chars().count(), inserted: replacement_text.chars().count()}),
+                _ => {}
+            }
+        }
+        edits
+    }
+    /// Returns the [`Edit`]s applied to the text when this [`ChangeSet`] is undone, in application order.
+    #[must_use]
+    pub fn inverse_edits(&self) -> Vec<Edit>{
+        self.edits().iter().rev().map(Edit::inverse).collect()
+    }
+    /// Returns a [`Json`] representation of [`ChangeSet`], as stored in an undo file.
+    #[must_use]
+    pub fn to_json(&self) -> Json{
+        Json::Object(vec![
+            ("changes".to_string(), Json::Array(self.changes.iter().map(Change::to_json).collect())),
+            ("selections_before_changes".to_string(), self.selections_before_changes.to_json()),
+            ("selections_after_changes".to_string(), self.selections_after_changes.to_json()),
+            ("grouped_with_previous".to_string(), Json::Bool(self.grouped_with_previous)),
+            ("semantics".to_string(), Json::from(match self.semantics{CursorSemantics::Bar => "bar", CursorSemantics::Block => "block"})),
+        ])
+    }
+    /// Returns the [`ChangeSet`] represented by `json`, if `json` is valid output of [`ChangeSet::to_json`].
+    #[must_use]
+    pub fn from_json(json: &Json) -> Option<Self>{
+        let Json::Array(changes) = json.get("changes")? else{return None;};
+        Some(Self{
+            changes: changes.iter().map(Change::from_json).collect::<Option<Vec<Change>>>()?,
+            selections_before_changes: Selections::from_json(json.get("selections_before_changes")?)?,
+            selections_after_changes: Selections::from_json(json.get("selections_after_changes")?)?,
+            grouped_with_previous: json.get("grouped_with_previous")?.as_bool()?,
+            semantics: match json.get("semantics")?.as_str()?{
+                "bar" => CursorSemantics::Bar,
+                "block" => CursorSemantics::Block,
+                _ => return None
+            },
+        })
+    }
+    #[must_use]
+    pub fn is_grouped_with_previous(&self) -> bool{
+        self.grouped_with_previous
+    }
+    /// Returns the cursor semantics this [`ChangeSet`]'s selections were recorded with.
+    #[must_use]
+    pub fn semantics(&self) -> CursorSemantics{
+        self.semantics
+    }
+
+    /// Groups `self` with `previous`, if `self` continues the same run of consecutive typing or deletion.
+    /// A run is broken when the kind of edit changes, or when selections were repositioned between edits.
+    pub fn group_with(&mut self, previous: Option<&ChangeSet>, semantics: CursorSemantics){
+        self.grouped_with_previous = match previous{
+            Some(previous) => {
+                previous.selections_after_changes == self.selections_before_changes
+                && (
+                    (previous.is_typing() && self.is_typing())
+                    || (previous.is_deleting(semantics) && self.is_deleting(semantics))
+                )
+            }
+            None => false
+        };
+    }
+    /// Returns `true` if every [`Change`] inserts a single grapheme other than a newline, or is a [`Operation::NoOp`].
+    fn is_typing(&self) -> bool{
+        self.changes.iter().any(|change| change.operation != Operation::NoOp)
+        && self.changes.iter().all(|change| {
+            match &change.operation{
+                Operation::Insert{inserted_text} => inserted_text != "\n" && inserted_text.graphemes(true).count() == 1,
+                Operation::NoOp => true,
+                _ => false
+            }
+        })
+    }
+    /// Returns `true` if every [`Change`] deletes from a non extended selection(backspace/delete), or is a [`Operation::NoOp`].
+    fn is_deleting(&self, semantics: CursorSemantics) -> bool{
+        self.changes.iter().any(|change| change.operation != Operation::NoOp)
+        && self.changes.iter().all(|change| {
+            match change.operation{
+                Operation::Delete => !change.selection_before_change.is_extended(semantics),
+                Operation::NoOp => true,
+                _ => false
+            }
+        })
+    }
+}
+
+/// A state in an [`UndoTree`], reached by applying `change_set` to its parent's state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UndoNode{
+    parent: Option<usize>,
+    change_set: Option<ChangeSet>,  //None only for the root node, which represents the text before any recorded change
+    children: Vec<usize>,
+    active_child: Option<usize>,    //the child redo moves to
+}
+impl UndoNode{
+    #[must_use]
+    pub fn parent(&self) -> Option<usize>{
+        self.parent
+    }
+    #[must_use]
+    pub fn change_set(&self) -> Option<&ChangeSet>{
+        self.change_set.as_ref()
+    }
+    /// Returns the ids of this node's children, oldest first. Each child is a separate branch of history.
+    #[must_use]
+    pub fn children(&self) -> &[usize]{
+        &self.children
+    }
+    #[must_use]
+    pub fn active_child(&self) -> Option<usize>{
+        self.active_child
+    }
+}
+
+/// Every state a document's text has been in, as a tree of [`ChangeSet`]s.
+/// Undoing and then making a new edit starts a new branch, instead of discarding the undone changes.
+///
+/// Node ids are assigned in creation order, so they also order states by time. Node 0 is the root.
+/// Undo reverts the change set leading to the current node, and redo re-applies the one leading to its active child.
+/// The tree is the only record of a document's history. Undo/redo stacks are derived from it, as read only views.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UndoTree{
+    nodes: Vec<UndoNode>,
+    current: usize,
+}
+impl Default for UndoTree{
+    fn default() -> Self{
+        Self{nodes: vec![UndoNode{parent: None, change_set: None, children: Vec::new(), active_child: None}], current: 0}
+    }
+}
+impl UndoTree{
+    /// Returns a new [`UndoTree`] with a single branch, holding `undo_stack` before the current node, and `redo_stack` after it.
+    #[must_use]
+    pub fn from_stacks(undo_stack: &[ChangeSet], redo_stack: &[ChangeSet]) -> Self{
+        let mut tree = Self::default();
+        for change_set in undo_stack.iter().chain(redo_stack.iter().rev()){
+            tree.push(change_set.clone());
+        }
+        for _ in redo_stack{
+            tree.move_to_parent();
+        }
+        tree
+    }
+    #[must_use]
+    pub fn len(&self) -> usize{
+        self.nodes.len()
+    }
+    #[must_use]
+    pub fn current(&self) -> usize{
+        self.current
+    }
+    #[must_use]
+    pub fn node(&self, id: usize) -> Option<&UndoNode>{
+        self.nodes.get(id)
+    }
+
+    /// Returns the [`ChangeSet`] undo reverts next: the one leading to the current node. None at the root.
+    #[must_use]
+    pub fn next_undo(&self) -> Option<&ChangeSet>{
+        self.nodes[self.current].change_set.as_ref()
+    }
+    /// Returns the [`ChangeSet`] redo re-applies next: the one leading to the current node's active child, if any.
+    #[must_use]
+    pub fn next_redo(&self) -> Option<&ChangeSet>{
+        self.nodes[self.current].active_child.and_then(|child| self.nodes[child].change_set.as_ref())
+    }
+
+    /// Adds `change_set` as a new, active child of the current node, and makes it current.
+    pub fn push(&mut self, change_set: ChangeSet){
+        let id = self.nodes.len();
+        self.nodes.push(UndoNode{parent: Some(self.current), change_set: Some(change_set), children: Vec::new(), active_child: None});
+        let current = &mut self.nodes[self.current];
+        current.children.push(id);
+        current.active_child = Some(id);
+        self.current = id;
+    }
+    /// Makes the current node's parent current, and remembers the current node as the parent's active child. Does nothing at the root.
+    pub fn move_to_parent(&mut self){
+        if let Some(parent) = self.nodes[self.current].parent{
+            self.nodes[parent].active_child = Some(self.current);
+            self.current = parent;
+        }
+    }
+    /// Makes the current node's active child current. Does nothing if the current node has no children.
+    pub fn move_to_active_child(&mut self){
+        if let Some(child) = self.nodes[self.current].active_child{
+            self.current = child;
+        }
+    }
+    /// Makes `child` the active child of its parent. Does nothing if `child` is the root, or does not exist.
+    pub fn set_active_child(&mut self, child: usize){
+        if let Some(parent) = self.nodes.get(child).and_then(|node| node.parent){
+            self.nodes[parent].active_child = Some(child);
+        }
+    }
+
+    /// Returns the [`ChangeSet`]s from the root to the current node, as held by an undo stack.
+    #[must_use]
+    pub fn undo_stack(&self) -> Vec<ChangeSet>{
+        let mut undo_stack: Vec<ChangeSet> = self.ancestors(self.current).iter()
+            .filter_map(|&id| self.nodes[id].change_set.clone())
+            .collect();
+        undo_stack.reverse();
+        undo_stack
+    }
+    /// Returns the [`ChangeSet`]s from the current node following active children, as held by a redo stack(next redo last).
+    #[must_use]
+    pub fn redo_stack(&self) -> Vec<ChangeSet>{
+        let mut redo_stack = Vec::new();
+        let mut id = self.current;
+        while let Some(child) = self.nodes[id].active_child{
+            if let Some(change_set) = &self.nodes[child].change_set{redo_stack.push(change_set.clone());}
+            id = child;
+        }
+        redo_stack.reverse();
+        redo_stack
+    }
+
+    /// Returns the ids of the nodes between `from` and `to`: the nodes undone walking up from `from`(in order, excluding their
+    /// common ancestor), and the nodes redone walking down to `to`(in order).
+    #[must_use]
+    pub fn path(&self, from: usize, to: usize) -> (Vec<usize>, Vec<usize>){
+        let from_ancestors = self.ancestors(from);
+        let mut down = Vec::new();
+        let mut id = to;
+        while !from_ancestors.contains(&id){
+            down.push(id);
+            match self.nodes[id].parent{
+                Some(parent) => id = parent,
+                None => break
+            }
+        }
+        down.reverse();
+        let up = from_ancestors.into_iter().take_while(|&ancestor| ancestor != id).collect();
+        (up, down)
+    }
+    /// Returns `id`, followed by each of its ancestors, ending with the root.
+    fn ancestors(&self, id: usize) -> Vec<usize>{
+        let mut ancestors = vec![id];
+        let mut id = id;
+        while let Some(parent) = self.nodes[id].parent{
+            ancestors.push(parent);
+            id = parent;
+        }
+        ancestors
+    }
+
+    /// Returns `true` if the state at node `id` was immediately continued by the next change set in its group(consecutive typing
+    /// or deletion). Undo/redo never stop at such states, so moving through states by time skips them too.
+    #[must_use]
+    pub fn is_mid_group(&self, id: usize) -> bool{
+        self.nodes.get(id + 1).is_some_and(|next| {
+            next.parent == Some(id) && next.change_set.as_ref().is_some_and(ChangeSet::is_grouped_with_previous)
+        })
+    }
+
+    /// Returns a [`Json`] representation of [`UndoTree`], as stored in an undo file.
+    #[must_use]
+    pub fn to_json(&self) -> Json{
+        let nodes = self.nodes.iter()
+            .map(|node| Json::Object(vec![
+                ("parent".to_string(), node.parent.map_or(Json::Null, Json::from)),
+                ("change_set".to_string(), node.change_set.as_ref().map_or(Json::Null, ChangeSet::to_json)),
+                ("active_child".to_string(), node.active_child.map_or(Json::Null, Json::from)),
+            ]))
+            .collect();
+        Json::Object(vec![("nodes".to_string(), Json::Array(nodes)), ("current".to_string(), Json::from(self.current))])
+    }
+    /// Returns the [`UndoTree`] represented by `json`, if `json` is valid output of [`UndoTree::to_json`].
+    #[must_use]
+    pub fn from_json(json: &Json) -> Option<Self>{
+        let Json::Array(json_nodes) = json.get("nodes")? else{return None;};
+        let mut nodes: Vec<UndoNode> = Vec::with_capacity(json_nodes.len());
+        for (id, json_node) in json_nodes.iter().enumerate(){
+            let optional_usize = |key: &str| -> Option<Option<usize>>{
+                match json_node.get(key)?{
+                    Json::Null => Some(None),
+                    value => Some(Some(value.as_usize()?))
+                }
+            };
+            let parent = optional_usize("parent")?;
+            let change_set = match json_node.get("change_set")?{
+                Json::Null => None,
+                value => Some(ChangeSet::from_json(value)?)
+            };
+            // only the root may lack a parent/change set, and parents always precede their children
+            if (id == 0) != parent.is_none() || (id == 0) != change_set.is_none() || parent.is_some_and(|parent| parent >= id){return None;}
+            if let Some(parent) = parent{nodes[parent].children.push(id);}
+            nodes.push(UndoNode{parent, change_set, children: Vec::new(), active_child: optional_usize("active_child")?});
+        }
+        for node in &nodes{
+            if node.active_child.is_some_and(|child| !node.children.contains(&child)){return None;}
+        }
+        let current = json.get("current")?.as_usize()?;
+        if current >= nodes.len(){return None;}
+        Some(Self{nodes, current})
+    }
 }
@@ -0,0 +1,2 @@
+mod to_json;
+mod undo_tree;
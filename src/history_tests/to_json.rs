@@ -0,0 +1,49 @@
+use ropey::Rope;
+use crate::document::Document;
+use crate::history::{ChangeSet, Operation};
+use crate::json::Json;
+use crate::selection::{Selection, CursorSemantics};
+use crate::selections::Selections;
+use crate::utilities::{insert_string, delete, backspace};
+
+fn round_trip(change_set: &ChangeSet) -> Option<ChangeSet>{
+    ChangeSet::from_json(&Json::parse(&change_set.to_json().to_string()).ok()?)
+}
+
+#[test] fn change_sets_round_trip_through_json(){
+    let text = Rope::from("idk\nsome\nshit\n");
+    let semantics = CursorSemantics::Block;
+    let mut doc = Document::new(semantics)
+        .with_text(text.clone())
+        .with_selections(Selections::new(vec![
+            Selection::new_from_components(0, 1, None, &text, semantics),
+            Selection::new_from_components(4, 9, Some(2), &text, semantics),
+        ], 1, &text, semantics));
+    assert!(insert_string::document_impl(&mut doc, "\"é\"\n\t", false, 4, semantics).is_ok());  //insert and replace
+    assert!(insert_string::document_impl(&mut doc, "a", false, 4, semantics).is_ok());
+    assert!(insert_string::document_impl(&mut doc, "b", false, 4, semantics).is_ok());  //grouped with previous
+    assert!(delete::document_impl(&mut doc, 1, semantics).is_ok());
+    assert!(backspace::document_impl(&mut doc, 1, false, 4, semantics).is_ok());
+    for change_set in &doc.undo_stack(){
+        assert_eq!(Some(change_set.clone()), round_trip(change_set));
+    }
+    assert!(doc.undo_stack()[2].is_grouped_with_previous());
+}
+
+#[test] fn operations_round_trip_through_json(){
+    for operation in [
+        Operation::Insert{inserted_text: "idk\n".to_string()},
+        Operation::Delete,
+        Operation::Replace{replacement_text: String::new()},
+        Operation::NoOp,
+    ]{
+        assert_eq!(Some(operation.clone()), Operation::from_json(&operation.to_json()));
+    }
+}
+
+#[test] fn from_json_rejects_invalid_json(){
+    assert_eq!(None, Operation::from_json(&Json::parse(r#"{"operation": "idk", "text": null}"#).unwrap()));
+    assert_eq!(None, Operation::from_json(&Json::parse(r#"{"operation": "insert", "text": null}"#).unwrap()));
+    assert_eq!(None, Selection::from_json(&Json::parse(r#"{"start": 5, "end": 4, "direction": "forward", "stored_line_position": null}"#).unwrap()));
+    assert_eq!(None, Selections::from_json(&Json::parse(r#"{"selections": [], "primary": 0}"#).unwrap()));
+}
@@ -0,0 +1,79 @@
+use ropey::Rope;
+use crate::document::Document;
+use crate::history::{ChangeSet, UndoTree};
+use crate::json::Json;
+use crate::selection::CursorSemantics;
+use crate::utilities::{insert_string, undo, redo};
+
+/// Returns a document where "idk\n" then "some\n" were inserted, "some\n" was undone, and "shit\n" was inserted on a new branch.
+fn branched_document(semantics: CursorSemantics) -> Document{
+    let mut doc = Document::new(semantics);
+    assert!(insert_string::document_impl(&mut doc, "idk\n", false, 4, semantics).is_ok());
+    assert!(insert_string::document_impl(&mut doc, "some\n", false, 4, semantics).is_ok());
+    assert!(undo::document_impl(&mut doc, semantics).is_ok());
+    assert!(insert_string::document_impl(&mut doc, "shit\n", false, 4, semantics).is_ok());
+    doc
+}
+
+#[test] fn undone_changes_are_kept_as_a_branch(){
+    let doc = branched_document(CursorSemantics::Bar);
+    assert_eq!(4, doc.undo_tree.len());
+    assert_eq!(3, doc.undo_tree.current());
+    assert_eq!(&[2, 3], doc.undo_tree.node(1).unwrap().children());
+    assert_eq!(Some(3), doc.undo_tree.node(1).unwrap().active_child());
+    assert_eq!(2, doc.undo_stack().len());
+    assert!(doc.redo_stack().is_empty());
+    assert_eq!(doc.undo_tree.node(3).unwrap().change_set(), doc.undo_tree.next_undo());
+    assert_eq!(None, doc.undo_tree.next_redo());
+}
+
+#[test] fn undo_and_redo_move_along_the_active_branch(){
+    let semantics = CursorSemantics::Block;
+    let mut doc = branched_document(semantics);
+    assert!(undo::document_impl(&mut doc, semantics).is_ok());
+    assert_eq!(1, doc.undo_tree.current());
+    assert_eq!(doc.undo_tree.node(3).unwrap().change_set(), doc.undo_tree.next_redo());
+    assert!(redo::document_impl(&mut doc, semantics).is_ok());
+    assert_eq!(3, doc.undo_tree.current());
+    assert_eq!(Rope::from("idk\nshit\n"), doc.text);
+}
+
+#[test] fn path_passes_through_common_ancestor(){
+    let doc = branched_document(CursorSemantics::Bar);
+    assert_eq!((vec![3], vec![2]), doc.undo_tree.path(3, 2));
+    assert_eq!((vec![3, 1], Vec::<usize>::new()), doc.undo_tree.path(3, 0));
+    assert_eq!((Vec::<usize>::new(), vec![1, 2]), doc.undo_tree.path(0, 2));
+    assert_eq!((Vec::<usize>::new(), Vec::<usize>::new()), doc.undo_tree.path(2, 2));
+}
+
+#[test] fn from_stacks_builds_single_branch(){
+    let doc = branched_document(CursorSemantics::Bar);
+    let undo_stack = doc.undo_stack();
+    let redo_stack = vec![undo_stack[1].clone()];
+    let tree = UndoTree::from_stacks(&undo_stack[..1], &redo_stack);
+    assert_eq!(3, tree.len());
+    assert_eq!(1, tree.current());
+    assert_eq!(undo_stack[..1].to_vec(), tree.undo_stack());
+    assert_eq!(redo_stack, tree.redo_stack());
+}
+
+#[test] fn mid_group_states_are_detected(){
+    let semantics = CursorSemantics::Bar;
+    let mut doc = Document::new(semantics);
+    for char in ["i", "d", "k"]{
+        assert!(insert_string::document_impl(&mut doc, char, false, 4, semantics).is_ok());
+    }
+    assert!(doc.undo_tree.node(2).and_then(|node| node.change_set()).is_some_and(ChangeSet::is_grouped_with_previous));
+    assert!(!doc.undo_tree.is_mid_group(0));
+    assert!(doc.undo_tree.is_mid_group(1));
+    assert!(doc.undo_tree.is_mid_group(2));
+    assert!(!doc.undo_tree.is_mid_group(3));
+}
+
+#[test] fn undo_tree_round_trips_through_json(){
+    let doc = branched_document(CursorSemantics::Block);
+    let json = Json::parse(&doc.undo_tree.to_json().to_string()).unwrap();
+    assert_eq!(Some(doc.undo_tree.clone()), UndoTree::from_json(&json));
+    assert_eq!(None, UndoTree::from_json(&Json::parse(r#"{"nodes":[],"current":0}"#).unwrap()));
+    assert_eq!(None, UndoTree::from_json(&Json::parse(r#"{"nodes":[{"parent":null,"change_set":null,"active_child":1}],"current":0}"#).unwrap()));
+}
@@ -0,0 +1,266 @@
+//! A minimal JSON value, parser, and serializer, so the client/server protocol can be line-delimited JSON without extra dependencies.
+//! Only integer numbers are supported, because nothing in the protocol needs fractional values.
+
+use std::fmt::{self, Display, Write};
+
+
+
+#[derive(Debug, PartialEq)]
+pub enum JsonError{
+    UnexpectedEnd,
+    UnexpectedChar(usize),  //byte index of unexpected char
+    InvalidNumber(usize),   //byte index of number start
+    InvalidEscape(usize),   //byte index of escape start
+    TrailingCharacters(usize),
+    TooDeeplyNested(usize), //byte index of the array or object opening past MAX_DEPTH
+}
+
+/// The most arrays and objects that can be nested inside each other. Parsing is recursive, so this bounds stack use on hostile input.
+pub const MAX_DEPTH: usize = 128;
+
+/// A parsed JSON value. Object entries keep their source order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json{
+    Null,
+    Bool(bool),
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+impl Json{
+    /// Parses a single JSON value from `input`. Surrounding whitespace is allowed, anything else is an error.
+    /// ```
+    /// # use edit_core::json::Json;
+    ///
+    /// let json = Json::parse(r#"{"a": [1, "b", null]}"#).unwrap();
+    /// assert_eq!(json, Json::Object(vec![("a".to_string(), Json::Array(vec![Json::Number(1), Json::String("b".to_string()), Json::Null]))]));
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, JsonError>{
+        let mut parser = Parser{input, index: 0, depth: 0};
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.index < input.len(){return Err(JsonError::TrailingCharacters(parser.index));}
+        Ok(value)
+    }
+
+    /// Returns the value associated with `key`, if `self` is an object containing `key`.
+    #[must_use] pub fn get(&self, key: &str) -> Option<&Json>{
+        match self{
+            Json::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None
+        }
+    }
+    #[must_use] pub fn as_str(&self) -> Option<&str>{
+        if let Json::String(string) = self{Some(string)}else{None}
+    }
+    #[must_use] pub fn as_bool(&self) -> Option<bool>{
+        if let Json::Bool(bool) = self{Some(*bool)}else{None}
+    }
+    #[must_use] pub fn as_usize(&self) -> Option<usize>{
+        if let Json::Number(number) = self{usize::try_from(*number).ok()}else{None}
+    }
+}
+impl From<&str> for Json{
+    fn from(value: &str) -> Self{Json::String(value.to_string())}
+}
+impl From<String> for Json{
+    fn from(value: String) -> Self{Json::String(value)}
+}
+impl From<bool> for Json{
+    fn from(value: bool) -> Self{Json::Bool(value)}
+}
+impl From<usize> for Json{
+    fn from(value: usize) -> Self{Json::Number(i64::try_from(value).unwrap_or(i64::MAX))}
+}
+/// Serializes as compact JSON, with no newlines, so each value fits on a single protocol line.
+impl Display for Json{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            Json::Null => f.write_str("null"),
+            Json::Bool(bool) => write!(f, "{bool}"),
+            Json::Number(number) => write!(f, "{number}"),
+            Json::String(string) => write_string(f, string),
+            Json::Array(values) => {
+                f.write_char('[')?;
+                for (i, value) in values.iter().enumerate(){
+                    if i > 0{f.write_char(',')?;}
+                    write!(f, "{value}")?;
+                }
+                f.write_char(']')
+            }
+            Json::Object(entries) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in entries.iter().enumerate(){
+                    if i > 0{f.write_char(',')?;}
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result{
+    f.write_char('"')?;
+    for char in string.chars(){
+        match char{
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+struct Parser<'a>{
+    input: &'a str,
+    index: usize,   //byte index into input
+    depth: usize,   //number of arrays and objects currently open
+}
+impl Parser<'_>{
+    fn peek(&self) -> Option<u8>{
+        self.input.as_bytes().get(self.index).copied()
+    }
+    fn skip_whitespace(&mut self){
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')){
+            self.index += 1;
+        }
+    }
+    fn expect(&mut self, byte: u8) -> Result<(), JsonError>{
+        match self.peek(){
+            Some(b) if b == byte => {self.index += 1; Ok(())}
+            Some(_) => Err(JsonError::UnexpectedChar(self.index)),
+            None => Err(JsonError::UnexpectedEnd)
+        }
+    }
+    fn literal(&mut self, literal: &str, value: Json) -> Result<Json, JsonError>{
+        if self.input[self.index..].starts_with(literal){
+            self.index += literal.len();
+            Ok(value)
+        }else{Err(JsonError::UnexpectedChar(self.index))}
+    }
+    fn value(&mut self) -> Result<Json, JsonError>{
+        self.skip_whitespace();
+        match self.peek(){
+            None => Err(JsonError::UnexpectedEnd),
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'"') => Ok(Json::String(self.string()?)),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'{') => self.nested(Self::object),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(JsonError::UnexpectedChar(self.index))
+        }
+    }
+    /// Parses an array or object with `parse`, erroring instead if that would nest deeper than [`MAX_DEPTH`].
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Json, JsonError>) -> Result<Json, JsonError>{
+        if self.depth >= MAX_DEPTH{return Err(JsonError::TooDeeplyNested(self.index));}
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+    fn number(&mut self) -> Result<Json, JsonError>{
+        let start = self.index;
+        if self.peek() == Some(b'-'){self.index += 1;}
+        while matches!(self.peek(), Some(b'0'..=b'9')){
+            self.index += 1;
+        }
+        if matches!(self.peek(), Some(b'.' | b'e' | b'E')){return Err(JsonError::InvalidNumber(start));}
+        self.input[start..self.index].parse().map(Json::Number).map_err(|_| JsonError::InvalidNumber(start))
+    }
+    fn string(&mut self) -> Result<String, JsonError>{
+        self.expect(b'"')?;
+        let mut string = String::new();
+        loop{
+            let Some(char) = self.input[self.index..].chars().next() else{return Err(JsonError::UnexpectedEnd);};
+            match char{
+                '"' => {self.index += 1; return Ok(string);}
+                '\\' => {
+                    let escape_start = self.index;
+                    self.index += 1;
+                    let Some(escaped) = self.peek() else{return Err(JsonError::UnexpectedEnd);};
+                    self.index += 1;
+                    match escaped{
+                        b'"' => string.push('"'),
+                        b'\\' => string.push('\\'),
+                        b'/' => string.push('/'),
+                        b'b' => string.push('\u{8}'),
+                        b'f' => string.push('\u{c}'),
+                        b'n' => string.push('\n'),
+                        b'r' => string.push('\r'),
+                        b't' => string.push('\t'),
+                        b'u' => string.push(self.unicode_escape(escape_start)?),
+                        _ => return Err(JsonError::InvalidEscape(escape_start))
+                    }
+                }
+                c => {
+                    if (c as u32) < 0x20{return Err(JsonError::UnexpectedChar(self.index));}
+                    string.push(c);
+                    self.index += c.len_utf8();
+                }
+            }
+        }
+    }
+    /// Parses the hex digits following `\u`, including a following low surrogate escape, if needed.
+    fn unicode_escape(&mut self, escape_start: usize) -> Result<char, JsonError>{
+        let high = self.hex4(escape_start)?;
+        let code_point = if (0xD800..0xDC00).contains(&high){
+            if !self.input[self.index..].starts_with("\\u"){return Err(JsonError::InvalidEscape(escape_start));}
+            self.index += 2;
+            let low = self.hex4(escape_start)?;
+            if !(0xDC00..0xE000).contains(&low){return Err(JsonError::InvalidEscape(escape_start));}
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        }else{high};
+        char::from_u32(code_point).ok_or(JsonError::InvalidEscape(escape_start))
+    }
+    fn hex4(&mut self, escape_start: usize) -> Result<u32, JsonError>{
+        let hex = self.input.get(self.index..self.index + 4).ok_or(JsonError::UnexpectedEnd)?;
+        let value = u32::from_str_radix(hex, 16).map_err(|_| JsonError::InvalidEscape(escape_start))?;
+        self.index += 4;
+        Ok(value)
+    }
+    fn array(&mut self) -> Result<Json, JsonError>{
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']'){self.index += 1; return Ok(Json::Array(values));}
+        loop{
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek(){
+                Some(b',') => self.index += 1,
+                Some(b']') => {self.index += 1; return Ok(Json::Array(values));}
+                Some(_) => return Err(JsonError::UnexpectedChar(self.index)),
+                None => return Err(JsonError::UnexpectedEnd)
+            }
+        }
+    }
+    fn object(&mut self) -> Result<Json, JsonError>{
+        self.expect(b'{')?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}'){self.index += 1; return Ok(Json::Object(entries));}
+        loop{
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            entries.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.peek(){
+                Some(b',') => self.index += 1,
+                Some(b'}') => {self.index += 1; return Ok(Json::Object(entries));}
+                Some(_) => return Err(JsonError::UnexpectedChar(self.index)),
+                None => return Err(JsonError::UnexpectedEnd)
+            }
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use crate::json::Json;
+
+#[test] fn serializes_compactly_on_a_single_line(){
+    let json = Json::Object(vec![
+        ("ok".to_string(), Json::Bool(true)),
+        ("text".to_string(), Json::from("idk\nsome\t\"shit\"\u{1}")),
+        ("list".to_string(), Json::Array(vec![Json::from(1usize), Json::Null])),
+    ]);
+    assert_eq!(r#"{"ok":true,"text":"idk\nsome\t\"shit\"\u0001","list":[1,null]}"#, json.to_string());
+}
+
+#[test] fn round_trips_through_parse(){
+    let json = Json::Object(vec![("text".to_string(), Json::from("a\\b\r\n😀"))]);
+    assert_eq!(json, Json::parse(&json.to_string()).unwrap());
+}
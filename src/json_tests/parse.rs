@@ -0,0 +1,37 @@
+use crate::json::{Json, JsonError, MAX_DEPTH};
+
+#[test] fn parses_nested_values(){
+    let json = Json::parse(r#" {"request": "insert_string", "client": 0, "flags": [true, false, null], "nested": {"n": -12}} "#).unwrap();
+    assert_eq!(Some("insert_string"), json.get("request").and_then(Json::as_str));
+    assert_eq!(Some(0), json.get("client").and_then(Json::as_usize));
+    assert_eq!(Some(&Json::Array(vec![Json::Bool(true), Json::Bool(false), Json::Null])), json.get("flags"));
+    assert_eq!(Some(&Json::Number(-12)), json.get("nested").and_then(|nested| nested.get("n")));
+}
+
+#[test] fn parses_string_escapes(){
+    assert_eq!(Json::String("a\"b\\c\nd\te/".to_string()), Json::parse(r#""a\"b\\c\nd\te\/""#).unwrap());
+    assert_eq!(Json::String("é".to_string()), Json::parse(r#""\u00e9""#).unwrap());
+    assert_eq!(Json::String("😀".to_string()), Json::parse(r#""\ud83d\ude00""#).unwrap());
+    assert_eq!(Json::String("😀".to_string()), Json::parse("\"😀\"").unwrap());
+}
+
+#[test] fn negative_number_is_not_usize(){
+    assert_eq!(None, Json::parse("-1").unwrap().as_usize());
+}
+
+#[test] fn errors_on_invalid_input(){
+    assert_eq!(Err(JsonError::UnexpectedEnd), Json::parse(r#"{"a": 1"#));
+    assert_eq!(Err(JsonError::UnexpectedEnd), Json::parse(""));
+    assert_eq!(Err(JsonError::TrailingCharacters(3)), Json::parse("{} {}"));
+    assert_eq!(Err(JsonError::InvalidNumber(0)), Json::parse("1.5"));
+    assert_eq!(Err(JsonError::InvalidEscape(1)), Json::parse(r#""\x""#));
+    assert_eq!(Err(JsonError::UnexpectedChar(1)), Json::parse("[,]"));
+}
+
+#[test] fn errors_on_nesting_deeper_than_max_depth(){
+    let nested = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+    assert!(Json::parse(&nested).is_ok());
+    assert_eq!(Err(JsonError::TooDeeplyNested(MAX_DEPTH)), Json::parse(&format!("[{nested}]")));
+    // each `{"a":[` opens 2 levels, so the opening past MAX_DEPTH is the `{` of repetition MAX_DEPTH / 2
+    assert_eq!(Err(JsonError::TooDeeplyNested(MAX_DEPTH / 2 * 6)), Json::parse(&"{\"a\":[".repeat(100_000)));
+}
@@ -34,10 +34,30 @@
 
 
 pub mod position;
-//pub mod id;
-//pub mod editor;
+pub mod id;
+pub mod editor;
+#[cfg(test)] mod editor_tests;
+pub mod json;
+#[cfg(test)] mod json_tests;
+pub mod protocol;
+#[cfg(test)] mod protocol_tests;
+pub mod server;
+#[cfg(test)] mod server_tests;
 pub mod history;
+#[cfg(test)] mod history_tests;
+pub mod undo_file;
+#[cfg(test)] mod undo_file_tests;
 pub mod document;
+pub mod register;
+pub mod clipboard;
+#[cfg(test)] mod clipboard_tests;
+pub mod shell;
+#[cfg(test)] mod shell_tests;
+pub mod completion;
+#[cfg(test)] mod completion_tests;
+pub mod search;
+#[cfg(test)] mod search_tests;
+#[cfg(test)] mod register_tests;
 pub mod range;
 #[cfg(test)] mod range_tests;
 pub mod selection;
@@ -46,6 +66,8 @@ pub mod selection2d;
 #[cfg(test)] mod selection2d_tests;
 pub mod selections;
 #[cfg(test)] mod selections_tests;
+pub mod selection_history;
+#[cfg(test)] mod selection_history_tests;
 pub mod view;
 #[cfg(test)] mod view_tests;
 pub mod text_util;
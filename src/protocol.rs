@@ -0,0 +1,631 @@
+//! Message types for driving an [`Editor`](crate::editor::Editor) from a separate frontend process.
+//!
+//! Messages are line-delimited JSON. Each request is a single JSON object on its own line, naming the request
+//! in its `"request"` field, and each request gets exactly one response line back.
+//! ```text
+//! -> {"request": "connect", "semantics": "block"}
+//! <- {"ok":true,"client":0}
+//! -> {"request": "open_document", "client": 0, "path": "/tmp/idk.txt"}
+//! <- {"ok":true}
+//! -> {"request": "insert_string", "client": 0, "string": "idk", "use_hard_tab": false, "tab_width": 4}
+//! <- {"ok":true}
+//! -> {"request": "view_text", "client": 0}
+//! <- {"ok":true,"text":"idk\n"}
+//! -> {"request": "undo", "client": 5}
+//! <- {"ok":false,"error":"InvalidClient"}
+//! ```
+//! Every `utilities::*::document_impl` action is exposed as a request with the same name as its module, taking
+//! that action's arguments as fields. `copy`, `cut` and `paste` take an optional `"register"` field, holding a register's vim style char.
+//! Repeatable movements and edits take an optional `"count"` field, defaulting to 1.
+//! Text object selections take a `"scope"` field, either `"inside"` or `"around"`. `select_surrounding_pair` takes an optional `"pairs"`
+//! field, holding alternating open/close chars(like `"()[]"`), and defaults to [`DEFAULT_PAIRS`](crate::text_util::DEFAULT_PAIRS).
+//! `pipe_selections` takes a `"command"` field, and an optional `"mode"` field, either `"replace"`(the default), `"insert"` or `"keep"`.
+//! `search_next`, `search_previous`, `add_selection_next_match` and `select_all_matches` take a `"pattern"` field, holding a regex
+//! that is matched against the whole document.
+//! Cursor semantics are not passed per request, they are set once per client on `connect`.
+
+use std::path::PathBuf;
+use crate::clipboard::ClipboardProvider;
+use crate::document::{Document, DocumentError, IndentStyle};
+use crate::history::{ChangeSet, UndoTree};
+use crate::id::ClientID;
+use crate::json::{Json, JsonError};
+use crate::position::Position;
+use crate::register::RegisterName;
+use crate::selection::{CursorSemantics, TextObjectScope};
+use crate::selection2d::Selection2d;
+use crate::selections::Selections;
+use crate::shell::PipeMode;
+use crate::text_util::DEFAULT_PAIRS;
+use crate::utilities::{
+    move_to_line_number,
+    move_cursor_up,
+    move_cursor_down,
+    move_cursor_left,
+    move_cursor_right,
+    move_cursor_word_boundary_forward,
+    move_cursor_word_boundary_backward,
+    move_cursor_line_end,
+    move_cursor_line_start,
+    move_cursor_line_text_start,
+    move_cursor_home,
+    move_cursor_matching_pair,
+    move_cursor_paragraph_start,
+    move_cursor_paragraph_end,
+    move_cursor_document_start,
+    move_cursor_document_end,
+    move_cursor_page_up,
+    move_cursor_page_down,
+    extend_selection_up,
+    extend_selection_down,
+    extend_selection_left,
+    extend_selection_right,
+    extend_selection_word_boundary_backward,
+    extend_selection_word_boundary_forward,
+    extend_selection_line_end,
+    extend_selection_line_start,
+    extend_selection_line_text_start,
+    extend_selection_home,
+    extend_selection_matching_pair,
+    extend_selection_paragraph_start,
+    extend_selection_paragraph_end,
+    select_line,
+    select_all,
+    select_word,
+    select_long_word,
+    select_sentence,
+    select_paragraph,
+    flip_direction,
+    surround,
+    nearest_surrounding_pair,
+    select_surrounding_pair,
+    incremental_search_in_selection,
+    incremental_split_in_selection,
+    search_next,
+    search_previous,
+    add_selection_next_match,
+    select_all_matches,
+    collapse_selections_to_cursor,
+    clear_non_primary_selections,
+    add_selection_above,
+    add_selection_below,
+    remove_primary_selection,
+    increment_primary_selection,
+    decrement_primary_selection,
+    jump_back,
+    jump_forward,
+    scroll_view_up,
+    scroll_view_down,
+    scroll_view_left,
+    scroll_view_right,
+    center_view_vertically_around_cursor,
+    delete,
+    backspace,
+    cut,
+    paste,
+    insert_string,
+    add_surrounding_pair,
+    undo,
+    redo,
+    undo_earlier,
+    undo_later,
+    next_undo_branch,
+    previous_undo_branch,
+    move_lines_up,
+    move_lines_down,
+    indent,
+    dedent,
+    convert_indentation,
+    pipe_selections,
+    complete_word,
+    align_selections,
+    rotate_selection_contents_forward,
+    rotate_selection_contents_backward,
+    reverse_selection_contents,
+    sort_selection_contents,
+    shuffle_selection_contents,
+    copy,
+};
+
+
+
+#[derive(Debug, PartialEq)]
+pub enum ProtocolError{
+    InvalidJson(JsonError),
+    MissingField(&'static str),
+    InvalidField(&'static str),
+    UnknownRequest(String),
+}
+
+/// A document action, corresponding to one of the `utilities::*::document_impl` fns.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action{
+    MoveToLineNumber{line_number: usize},
+    MoveCursorUp{count: usize},
+    MoveCursorDown{count: usize},
+    MoveCursorLeft{count: usize},
+    MoveCursorRight{count: usize},
+    MoveCursorWordBoundaryForward{count: usize},
+    MoveCursorWordBoundaryBackward{count: usize},
+    MoveCursorLineEnd,
+    MoveCursorLineStart,
+    MoveCursorLineTextStart,
+    MoveCursorHome,
+    MoveCursorMatchingPair,
+    MoveCursorParagraphStart{count: usize},
+    MoveCursorParagraphEnd{count: usize},
+    MoveCursorDocumentStart,
+    MoveCursorDocumentEnd,
+    MoveCursorPageUp{count: usize},
+    MoveCursorPageDown{count: usize},
+    ExtendSelectionUp{count: usize},
+    ExtendSelectionDown{count: usize},
+    ExtendSelectionLeft{count: usize},
+    ExtendSelectionRight{count: usize},
+    ExtendSelectionWordBoundaryBackward{count: usize},
+    ExtendSelectionWordBoundaryForward{count: usize},
+    ExtendSelectionLineEnd,
+    ExtendSelectionLineStart,
+    ExtendSelectionLineTextStart,
+    ExtendSelectionHome,
+    ExtendSelectionMatchingPair,
+    ExtendSelectionParagraphStart{count: usize},
+    ExtendSelectionParagraphEnd{count: usize},
+    SelectLine,
+    SelectAll,
+    SelectWord{scope: TextObjectScope},
+    SelectLongWord{scope: TextObjectScope},
+    SelectSentence{scope: TextObjectScope},
+    SelectParagraph{scope: TextObjectScope},
+    FlipDirection,
+    Surround,
+    NearestSurroundingPair,
+    SelectSurroundingPair{scope: TextObjectScope, pairs: Vec<(char, char)>},
+    /// Searches within the selections from before the first search of a run of consecutive incremental searches.
+    IncrementalSearchInSelection{search_text: String},
+    /// Splits within the selections from before the first split of a run of consecutive incremental splits.
+    IncrementalSplitInSelection{search_text: String},
+    SearchNext{pattern: String},
+    SearchPrevious{pattern: String},
+    AddSelectionNextMatch{pattern: String},
+    SelectAllMatches{pattern: String},
+    CollapseSelectionsToCursor,
+    ClearNonPrimarySelections,
+    AddSelectionAbove{count: usize},
+    AddSelectionBelow{count: usize},
+    RemovePrimarySelection,
+    IncrementPrimarySelection,
+    DecrementPrimarySelection,
+    JumpBack,
+    JumpForward,
+    ScrollViewUp{amount: usize},
+    ScrollViewDown{amount: usize},
+    ScrollViewLeft{amount: usize},
+    ScrollViewRight{amount: usize},
+    CenterViewVerticallyAroundCursor,
+    Delete{count: usize},
+    Backspace{count: usize, use_hard_tab: bool, tab_width: usize},
+    Cut{register: RegisterName},
+    Paste{register: RegisterName, use_hard_tab: bool, tab_width: usize},
+    InsertString{string: String, use_hard_tab: bool, tab_width: usize},
+    AddSurroundingPair{leading_char: char, trailing_char: char},
+    Undo,
+    Redo,
+    UndoEarlier,
+    UndoLater,
+    NextUndoBranch,
+    PreviousUndoBranch,
+    MoveLinesUp,
+    MoveLinesDown,
+    Indent{use_hard_tab: bool, tab_width: usize},
+    Dedent{tab_width: usize},
+    ConvertIndentation{use_hard_tab: bool, tab_width: usize},
+    PipeSelections{command: String, mode: PipeMode},
+    CompleteWord{completion: String},
+    AlignSelections{use_hard_tab: bool, tab_width: usize},
+    RotateSelectionContentsForward,
+    RotateSelectionContentsBackward,
+    ReverseSelectionContents,
+    SortSelectionContents,
+    ShuffleSelectionContents,
+    Copy{register: RegisterName},
+}
+impl Action{
+    /// Returns the [`Action`] named `name`, with arguments taken from the fields of `request`, or `None` if `name` is not an action.
+    pub fn parse(name: &str, request: &Json) -> Result<Option<Self>, ProtocolError>{
+        Ok(Some(match name{
+            "move_to_line_number" => Action::MoveToLineNumber{line_number: usize_field(request, "line_number")?},
+            "move_cursor_up" => Action::MoveCursorUp{count: count_field(request)?},
+            "move_cursor_down" => Action::MoveCursorDown{count: count_field(request)?},
+            "move_cursor_left" => Action::MoveCursorLeft{count: count_field(request)?},
+            "move_cursor_right" => Action::MoveCursorRight{count: count_field(request)?},
+            "move_cursor_word_boundary_forward" => Action::MoveCursorWordBoundaryForward{count: count_field(request)?},
+            "move_cursor_word_boundary_backward" => Action::MoveCursorWordBoundaryBackward{count: count_field(request)?},
+            "move_cursor_line_end" => Action::MoveCursorLineEnd,
+            "move_cursor_line_start" => Action::MoveCursorLineStart,
+            "move_cursor_line_text_start" => Action::MoveCursorLineTextStart,
+            "move_cursor_home" => Action::MoveCursorHome,
+            "move_cursor_matching_pair" => Action::MoveCursorMatchingPair,
+            "move_cursor_paragraph_start" => Action::MoveCursorParagraphStart{count: count_field(request)?},
+            "move_cursor_paragraph_end" => Action::MoveCursorParagraphEnd{count: count_field(request)?},
+            "move_cursor_document_start" => Action::MoveCursorDocumentStart,
+            "move_cursor_document_end" => Action::MoveCursorDocumentEnd,
+            "move_cursor_page_up" => Action::MoveCursorPageUp{count: count_field(request)?},
+            "move_cursor_page_down" => Action::MoveCursorPageDown{count: count_field(request)?},
+            "extend_selection_up" => Action::ExtendSelectionUp{count: count_field(request)?},
+            "extend_selection_down" => Action::ExtendSelectionDown{count: count_field(request)?},
+            "extend_selection_left" => Action::ExtendSelectionLeft{count: count_field(request)?},
+            "extend_selection_right" => Action::ExtendSelectionRight{count: count_field(request)?},
+            "extend_selection_word_boundary_backward" => Action::ExtendSelectionWordBoundaryBackward{count: count_field(request)?},
+            "extend_selection_word_boundary_forward" => Action::ExtendSelectionWordBoundaryForward{count: count_field(request)?},
+            "extend_selection_line_end" => Action::ExtendSelectionLineEnd,
+            "extend_selection_line_start" => Action::ExtendSelectionLineStart,
+            "extend_selection_line_text_start" => Action::ExtendSelectionLineTextStart,
+            "extend_selection_home" => Action::ExtendSelectionHome,
+            "extend_selection_matching_pair" => Action::ExtendSelectionMatchingPair,
+            "extend_selection_paragraph_start" => Action::ExtendSelectionParagraphStart{count: count_field(request)?},
+            "extend_selection_paragraph_end" => Action::ExtendSelectionParagraphEnd{count: count_field(request)?},
+            "select_line" => Action::SelectLine,
+            "select_all" => Action::SelectAll,
+            "select_word" => Action::SelectWord{scope: scope_field(request)?},
+            "select_long_word" => Action::SelectLongWord{scope: scope_field(request)?},
+            "select_sentence" => Action::SelectSentence{scope: scope_field(request)?},
+            "select_paragraph" => Action::SelectParagraph{scope: scope_field(request)?},
+            "flip_direction" => Action::FlipDirection,
+            "surround" => Action::Surround,
+            "nearest_surrounding_pair" => Action::NearestSurroundingPair,
+            "select_surrounding_pair" => Action::SelectSurroundingPair{scope: scope_field(request)?, pairs: pairs_field(request)?},
+            "incremental_search_in_selection" => Action::IncrementalSearchInSelection{search_text: string_field(request, "search_text")?},
+            "incremental_split_in_selection" => Action::IncrementalSplitInSelection{search_text: string_field(request, "search_text")?},
+            "search_next" => Action::SearchNext{pattern: string_field(request, "pattern")?},
+            "search_previous" => Action::SearchPrevious{pattern: string_field(request, "pattern")?},
+            "add_selection_next_match" => Action::AddSelectionNextMatch{pattern: string_field(request, "pattern")?},
+            "select_all_matches" => Action::SelectAllMatches{pattern: string_field(request, "pattern")?},
+            "collapse_selections_to_cursor" => Action::CollapseSelectionsToCursor,
+            "clear_non_primary_selections" => Action::ClearNonPrimarySelections,
+            "add_selection_above" => Action::AddSelectionAbove{count: count_field(request)?},
+            "add_selection_below" => Action::AddSelectionBelow{count: count_field(request)?},
+            "remove_primary_selection" => Action::RemovePrimarySelection,
+            "increment_primary_selection" => Action::IncrementPrimarySelection,
+            "decrement_primary_selection" => Action::DecrementPrimarySelection,
+            "jump_back" => Action::JumpBack,
+            "jump_forward" => Action::JumpForward,
+            "scroll_view_up" => Action::ScrollViewUp{amount: usize_field(request, "amount")?},
+            "scroll_view_down" => Action::ScrollViewDown{amount: usize_field(request, "amount")?},
+            "scroll_view_left" => Action::ScrollViewLeft{amount: usize_field(request, "amount")?},
+            "scroll_view_right" => Action::ScrollViewRight{amount: usize_field(request, "amount")?},
+            "center_view_vertically_around_cursor" => Action::CenterViewVerticallyAroundCursor,
+            "delete" => Action::Delete{count: count_field(request)?},
+            "backspace" => Action::Backspace{count: count_field(request)?, use_hard_tab: bool_field(request, "use_hard_tab")?, tab_width: tab_width_field(request)?},
+            "cut" => Action::Cut{register: register_field(request)?},
+            "paste" => Action::Paste{register: register_field(request)?, use_hard_tab: bool_field(request, "use_hard_tab")?, tab_width: tab_width_field(request)?},
+            "insert_string" => Action::InsertString{string: string_field(request, "string")?, use_hard_tab: bool_field(request, "use_hard_tab")?, tab_width: tab_width_field(request)?},
+            "add_surrounding_pair" => Action::AddSurroundingPair{leading_char: char_field(request, "leading_char")?, trailing_char: char_field(request, "trailing_char")?},
+            "undo" => Action::Undo,
+            "redo" => Action::Redo,
+            "undo_earlier" => Action::UndoEarlier,
+            "undo_later" => Action::UndoLater,
+            "next_undo_branch" => Action::NextUndoBranch,
+            "previous_undo_branch" => Action::PreviousUndoBranch,
+            "move_lines_up" => Action::MoveLinesUp,
+            "move_lines_down" => Action::MoveLinesDown,
+            "indent" => Action::Indent{use_hard_tab: bool_field(request, "use_hard_tab")?, tab_width: tab_width_field(request)?},
+            "dedent" => Action::Dedent{tab_width: tab_width_field(request)?},
+            "convert_indentation" => Action::ConvertIndentation{use_hard_tab: bool_field(request, "use_hard_tab")?, tab_width: tab_width_field(request)?},
+            "pipe_selections" => Action::PipeSelections{command: string_field(request, "command")?, mode: mode_field(request)?},
+            "complete_word" => Action::CompleteWord{completion: string_field(request, "completion")?},
+            "align_selections" => Action::AlignSelections{use_hard_tab: bool_field(request, "use_hard_tab")?, tab_width: tab_width_field(request)?},
+            "rotate_selection_contents_forward" => Action::RotateSelectionContentsForward,
+            "rotate_selection_contents_backward" => Action::RotateSelectionContentsBackward,
+            "reverse_selection_contents" => Action::ReverseSelectionContents,
+            "sort_selection_contents" => Action::SortSelectionContents,
+            "shuffle_selection_contents" => Action::ShuffleSelectionContents,
+            "copy" => Action::Copy{register: register_field(request)?},
+            _ => return Ok(None)
+        }))
+    }
+
+    /// Performs this [`Action`] on `document`. `clipboard` is the system clipboard, used by copy/cut/paste with the clipboard register.
+    /// `selections_before_search` is only used by incremental search/split, and should hold the selections from before the first
+    /// search of the current run of incremental searches.
+    pub fn apply(&self, document: &mut Document, clipboard: &mut dyn ClipboardProvider, selections_before_search: &Selections, semantics: CursorSemantics) -> Result<(), DocumentError>{
+        match self{
+            Action::MoveToLineNumber{line_number} => move_to_line_number::document_impl(document, *line_number, semantics),
+            Action::MoveCursorUp{count} => move_cursor_up::document_impl(document, *count, semantics),
+            Action::MoveCursorDown{count} => move_cursor_down::document_impl(document, *count, semantics),
+            Action::MoveCursorLeft{count} => move_cursor_left::document_impl(document, *count, semantics),
+            Action::MoveCursorRight{count} => move_cursor_right::document_impl(document, *count, semantics),
+            Action::MoveCursorWordBoundaryForward{count} => move_cursor_word_boundary_forward::document_impl(document, *count, semantics),
+            Action::MoveCursorWordBoundaryBackward{count} => move_cursor_word_boundary_backward::document_impl(document, *count, semantics),
+            Action::MoveCursorLineEnd => move_cursor_line_end::document_impl(document, semantics),
+            Action::MoveCursorLineStart => move_cursor_line_start::document_impl(document, semantics),
+            Action::MoveCursorLineTextStart => move_cursor_line_text_start::document_impl(document, semantics),
+            Action::MoveCursorHome => move_cursor_home::document_impl(document, semantics),
+            Action::MoveCursorMatchingPair => move_cursor_matching_pair::document_impl(document, semantics),
+            Action::MoveCursorParagraphStart{count} => move_cursor_paragraph_start::document_impl(document, *count, semantics),
+            Action::MoveCursorParagraphEnd{count} => move_cursor_paragraph_end::document_impl(document, *count, semantics),
+            Action::MoveCursorDocumentStart => move_cursor_document_start::document_impl(document, semantics),
+            Action::MoveCursorDocumentEnd => move_cursor_document_end::document_impl(document, semantics),
+            Action::MoveCursorPageUp{count} => move_cursor_page_up::document_impl(document, *count, semantics),
+            Action::MoveCursorPageDown{count} => move_cursor_page_down::document_impl(document, *count, semantics),
+            Action::ExtendSelectionUp{count} => extend_selection_up::document_impl(document, *count, semantics),
+            Action::ExtendSelectionDown{count} => extend_selection_down::document_impl(document, *count, semantics),
+            Action::ExtendSelectionLeft{count} => extend_selection_left::document_impl(document, *count, semantics),
+            Action::ExtendSelectionRight{count} => extend_selection_right::document_impl(document, *count, semantics),
+            Action::ExtendSelectionWordBoundaryBackward{count} => extend_selection_word_boundary_backward::document_impl(document, *count, semantics),
+            Action::ExtendSelectionWordBoundaryForward{count} => extend_selection_word_boundary_forward::document_impl(document, *count, semantics),
+            Action::ExtendSelectionLineEnd => extend_selection_line_end::document_impl(document, semantics),
+            Action::ExtendSelectionLineStart => extend_selection_line_start::document_impl(document, semantics),
+            Action::ExtendSelectionLineTextStart => extend_selection_line_text_start::document_impl(document, semantics),
+            Action::ExtendSelectionHome => extend_selection_home::document_impl(document, semantics),
+            Action::ExtendSelectionMatchingPair => extend_selection_matching_pair::document_impl(document, semantics),
+            Action::ExtendSelectionParagraphStart{count} => extend_selection_paragraph_start::document_impl(document, *count, semantics),
+            Action::ExtendSelectionParagraphEnd{count} => extend_selection_paragraph_end::document_impl(document, *count, semantics),
+            Action::SelectLine => select_line::document_impl(document, semantics),
+            Action::SelectAll => select_all::document_impl(document, semantics),
+            Action::SelectWord{scope} => select_word::document_impl(document, *scope, semantics),
+            Action::SelectLongWord{scope} => select_long_word::document_impl(document, *scope, semantics),
+            Action::SelectSentence{scope} => select_sentence::document_impl(document, *scope, semantics),
+            Action::SelectParagraph{scope} => select_paragraph::document_impl(document, *scope, semantics),
+            Action::FlipDirection => flip_direction::document_impl(document, semantics),
+            Action::Surround => surround::document_impl(document, semantics),
+            Action::NearestSurroundingPair => nearest_surrounding_pair::document_impl(document, semantics),
+            Action::SelectSurroundingPair{scope, pairs} => select_surrounding_pair::document_impl(document, *scope, pairs, semantics),
+            Action::IncrementalSearchInSelection{search_text} => incremental_search_in_selection::document_impl(document, search_text, selections_before_search, semantics),
+            Action::IncrementalSplitInSelection{search_text} => incremental_split_in_selection::document_impl(document, search_text, selections_before_search, semantics),
+            Action::SearchNext{pattern} => search_next::document_impl(document, pattern, semantics),
+            Action::SearchPrevious{pattern} => search_previous::document_impl(document, pattern, semantics),
+            Action::AddSelectionNextMatch{pattern} => add_selection_next_match::document_impl(document, pattern, semantics),
+            Action::SelectAllMatches{pattern} => select_all_matches::document_impl(document, pattern, semantics),
+            Action::CollapseSelectionsToCursor => collapse_selections_to_cursor::document_impl(document, semantics),
+            Action::ClearNonPrimarySelections => clear_non_primary_selections::document_impl(document),
+            Action::AddSelectionAbove{count} => add_selection_above::document_impl(document, *count, semantics),
+            Action::AddSelectionBelow{count} => add_selection_below::document_impl(document, *count, semantics),
+            Action::RemovePrimarySelection => remove_primary_selection::document_impl(document),
+            Action::IncrementPrimarySelection => increment_primary_selection::document_impl(document),
+            Action::DecrementPrimarySelection => decrement_primary_selection::document_impl(document),
+            Action::JumpBack => jump_back::document_impl(document),
+            Action::JumpForward => jump_forward::document_impl(document),
+            Action::ScrollViewUp{amount} => scroll_view_up::document_impl(document, *amount),
+            Action::ScrollViewDown{amount} => scroll_view_down::document_impl(document, *amount),
+            Action::ScrollViewLeft{amount} => scroll_view_left::document_impl(document, *amount),
+            Action::ScrollViewRight{amount} => scroll_view_right::document_impl(document, *amount),
+            Action::CenterViewVerticallyAroundCursor => center_view_vertically_around_cursor::document_impl(document, semantics),
+            Action::Delete{count} => delete::document_impl(document, *count, semantics),
+            Action::Backspace{count, use_hard_tab, tab_width} => backspace::document_impl(document, *count, *use_hard_tab, *tab_width, semantics),
+            Action::Cut{register} => cut::document_impl(document, *register, clipboard, semantics),
+            Action::Paste{register, use_hard_tab, tab_width} => paste::document_impl(document, *register, clipboard, *use_hard_tab, *tab_width, semantics),
+            Action::InsertString{string, use_hard_tab, tab_width} => insert_string::document_impl(document, string, *use_hard_tab, *tab_width, semantics),
+            Action::AddSurroundingPair{leading_char, trailing_char} => add_surrounding_pair::document_impl(document, *leading_char, *trailing_char, semantics),
+            Action::Undo => undo::document_impl(document, semantics),
+            Action::Redo => redo::document_impl(document, semantics),
+            Action::UndoEarlier => undo_earlier::document_impl(document, semantics),
+            Action::UndoLater => undo_later::document_impl(document, semantics),
+            Action::NextUndoBranch => next_undo_branch::document_impl(document),
+            Action::PreviousUndoBranch => previous_undo_branch::document_impl(document),
+            Action::MoveLinesUp => move_lines_up::document_impl(document, semantics),
+            Action::MoveLinesDown => move_lines_down::document_impl(document, semantics),
+            Action::Indent{use_hard_tab, tab_width} => indent::document_impl(document, *use_hard_tab, *tab_width, semantics),
+            Action::Dedent{tab_width} => dedent::document_impl(document, *tab_width, semantics),
+            Action::ConvertIndentation{use_hard_tab, tab_width} => convert_indentation::document_impl(document, *use_hard_tab, *tab_width, semantics),
+            Action::PipeSelections{command, mode} => pipe_selections::document_impl(document, command, *mode, semantics),
+            Action::CompleteWord{completion} => complete_word::document_impl(document, completion, semantics),
+            Action::AlignSelections{use_hard_tab, tab_width} => align_selections::document_impl(document, *use_hard_tab, *tab_width, semantics),
+            Action::RotateSelectionContentsForward => rotate_selection_contents_forward::document_impl(document, semantics),
+            Action::RotateSelectionContentsBackward => rotate_selection_contents_backward::document_impl(document, semantics),
+            Action::ReverseSelectionContents => reverse_selection_contents::document_impl(document, semantics),
+            Action::SortSelectionContents => sort_selection_contents::document_impl(document, semantics),
+            Action::ShuffleSelectionContents => shuffle_selection_contents::document_impl(document, semantics),
+            Action::Copy{register} => copy::document_impl(document, *register, clipboard),
+        }
+    }
+
+    /// Returns `true` if this [`Action`] continues a run of incremental searches/splits.
+    #[must_use] pub fn is_incremental(&self) -> bool{
+        matches!(self, Action::IncrementalSearchInSelection{..} | Action::IncrementalSplitInSelection{..})
+    }
+}
+
+/// A message sent from a frontend to the server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request{
+    Connect{semantics: CursorSemantics},
+    Disconnect{client: ClientID},
+    OpenDocument{client: ClientID, path: PathBuf},
+    CloseDocument{client: ClientID},
+    Save{client: ClientID},
+    SetViewSize{client: ClientID, width: usize, height: usize},
+    Action{client: ClientID, action: Action},
+    /// Queries [`View::text`](crate::view::View::text)
+    ViewText{client: ClientID},
+    /// Queries [`View::selections`](crate::view::View::selections)
+    ViewSelections{client: ClientID},
+    /// Queries [`View::line_numbers`](crate::view::View::line_numbers)
+    ViewLineNumbers{client: ClientID},
+    /// Queries [`View::primary_cursor_position`](crate::view::View::primary_cursor_position)
+    ViewPrimaryCursorPosition{client: ClientID},
+    /// Queries the shape of the undo tree of the client's document, for display by a frontend
+    ViewUndoTree{client: ClientID},
+    /// Queries [`Document::indent_style`] of the client's document
+    ViewIndentStyle{client: ClientID},
+    /// Queries [`WordIndex::candidates_at`](crate::completion::WordIndex::candidates_at) the client's selections
+    ViewCompletions{client: ClientID},
+}
+impl Request{
+    /// Parses a [`Request`] from a single line of JSON.
+    /// ```
+    /// # use edit_core::protocol::{Request, Action};
+    ///
+    /// let request = Request::parse(r#"{"request": "move_cursor_right", "client": 1}"#).unwrap();
+    /// assert_eq!(request, Request::Action{client: 1, action: Action::MoveCursorRight{count: 1}});
+    /// ```
+    pub fn parse(line: &str) -> Result<Self, ProtocolError>{
+        let json = Json::parse(line).map_err(ProtocolError::InvalidJson)?;
+        let name = string_field(&json, "request")?;
+        Ok(match name.as_str(){
+            "connect" => {
+                let semantics = match json.get("semantics").map(|value| value.as_str()){
+                    None | Some(Some("bar")) => CursorSemantics::Bar,
+                    Some(Some("block")) => CursorSemantics::Block,
+                    Some(_) => return Err(ProtocolError::InvalidField("semantics"))
+                };
+                Request::Connect{semantics}
+            }
+            "disconnect" => Request::Disconnect{client: usize_field(&json, "client")?},
+            "open_document" => Request::OpenDocument{client: usize_field(&json, "client")?, path: PathBuf::from(string_field(&json, "path")?)},
+            "close_document" => Request::CloseDocument{client: usize_field(&json, "client")?},
+            "save" => Request::Save{client: usize_field(&json, "client")?},
+            "set_view_size" => Request::SetViewSize{client: usize_field(&json, "client")?, width: usize_field(&json, "width")?, height: usize_field(&json, "height")?},
+            "view_text" => Request::ViewText{client: usize_field(&json, "client")?},
+            "view_selections" => Request::ViewSelections{client: usize_field(&json, "client")?},
+            "view_line_numbers" => Request::ViewLineNumbers{client: usize_field(&json, "client")?},
+            "view_primary_cursor_position" => Request::ViewPrimaryCursorPosition{client: usize_field(&json, "client")?},
+            "view_undo_tree" => Request::ViewUndoTree{client: usize_field(&json, "client")?},
+            "view_indent_style" => Request::ViewIndentStyle{client: usize_field(&json, "client")?},
+            "view_completions" => Request::ViewCompletions{client: usize_field(&json, "client")?},
+            name => {
+                match Action::parse(name, &json)?{
+                    Some(action) => Request::Action{client: usize_field(&json, "client")?, action},
+                    None => return Err(ProtocolError::UnknownRequest(name.to_string()))
+                }
+            }
+        })
+    }
+}
+
+/// A message sent from the server to a frontend, in reply to a [`Request`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Response{
+    Ok,
+    Client(ClientID),
+    Text(String),
+    Selections(Vec<Selection2d>),
+    Position(Option<Position>),
+    UndoTree(UndoTree),
+    IndentStyle(Option<IndentStyle>),
+    /// completion candidates for each selection, in selection order
+    Completions(Vec<Vec<String>>),
+    /// the request succeeded, but with a problem the frontend may want to show. ex. a file saved without its undo history
+    Warning(String),
+    Error(String),
+}
+impl Response{
+    #[must_use] pub fn to_json(&self) -> Json{
+        let mut entries = vec![("ok".to_string(), Json::Bool(!matches!(self, Response::Error(_))))];
+        match self{
+            Response::Ok => {}
+            Response::Client(client) => entries.push(("client".to_string(), Json::from(*client))),
+            Response::Text(text) => entries.push(("text".to_string(), Json::from(text.as_str()))),
+            Response::Selections(selections) => {
+                let selections = selections.iter()
+                    .map(|selection| Json::Object(vec![
+                        ("anchor".to_string(), position_to_json(selection.anchor())),
+                        ("head".to_string(), position_to_json(selection.head())),
+                    ]))
+                    .collect();
+                entries.push(("selections".to_string(), Json::Array(selections)));
+            }
+            Response::Position(position) => entries.push(("position".to_string(), position.as_ref().map_or(Json::Null, position_to_json))),
+            Response::UndoTree(undo_tree) => entries.push(("undo_tree".to_string(), undo_tree_to_json(undo_tree))),
+            Response::IndentStyle(indent_style) => entries.push(("indent_style".to_string(), indent_style.map_or(Json::Null, indent_style_to_json))),
+            Response::Completions(completions) => {
+                let completions = completions.iter()
+                    .map(|candidates| Json::Array(candidates.iter().map(|candidate| Json::from(candidate.as_str())).collect()))
+                    .collect();
+                entries.push(("completions".to_string(), Json::Array(completions)));
+            }
+            Response::Warning(warning) => entries.push(("warning".to_string(), Json::from(warning.as_str()))),
+            Response::Error(error) => entries.push(("error".to_string(), Json::from(error.as_str()))),
+        }
+        Json::Object(entries)
+    }
+}
+
+fn position_to_json(position: &Position) -> Json{
+    Json::Object(vec![("x".to_string(), Json::from(position.x)), ("y".to_string(), Json::from(position.y))])
+}
+/// Describes `indent_style` with the same fields an indenting action takes.
+fn indent_style_to_json(indent_style: IndentStyle) -> Json{
+    match indent_style{
+        IndentStyle::HardTab => Json::Object(vec![("use_hard_tab".to_string(), Json::Bool(true))]),
+        IndentStyle::Spaces(tab_width) => Json::Object(vec![("use_hard_tab".to_string(), Json::Bool(false)), ("tab_width".to_string(), Json::from(tab_width))]),
+    }
+}
+/// Describes the shape of `undo_tree` without its change sets. Node ids are indices into `"nodes"`, in creation order.
+fn undo_tree_to_json(undo_tree: &UndoTree) -> Json{
+    let nodes = (0..undo_tree.len())
+        .filter_map(|id| undo_tree.node(id))
+        .map(|node| Json::Object(vec![
+            ("parent".to_string(), node.parent().map_or(Json::Null, Json::from)),
+            ("children".to_string(), Json::Array(node.children().iter().copied().map(Json::from).collect())),
+            ("active_child".to_string(), node.active_child().map_or(Json::Null, Json::from)),
+            ("grouped_with_previous".to_string(), Json::Bool(node.change_set().is_some_and(ChangeSet::is_grouped_with_previous))),
+        ]))
+        .collect();
+    Json::Object(vec![("current".to_string(), Json::from(undo_tree.current())), ("nodes".to_string(), Json::Array(nodes))])
+}
+fn string_field(json: &Json, field: &'static str) -> Result<String, ProtocolError>{
+    json.get(field).ok_or(ProtocolError::MissingField(field))?
+        .as_str().map(str::to_string).ok_or(ProtocolError::InvalidField(field))
+}
+fn usize_field(json: &Json, field: &'static str) -> Result<usize, ProtocolError>{
+    json.get(field).ok_or(ProtocolError::MissingField(field))?
+        .as_usize().ok_or(ProtocolError::InvalidField(field))
+}
+fn bool_field(json: &Json, field: &'static str) -> Result<bool, ProtocolError>{
+    json.get(field).ok_or(ProtocolError::MissingField(field))?
+        .as_bool().ok_or(ProtocolError::InvalidField(field))
+}
+/// Returns the register named by the optional `"register"` field, or the default register if the field is absent.
+fn register_field(json: &Json) -> Result<RegisterName, ProtocolError>{
+    if json.get("register").is_none(){return Ok(RegisterName::default());}
+    RegisterName::from_char(char_field(json, "register")?).ok_or(ProtocolError::InvalidField("register"))
+}
+/// Returns the repeat count held by the optional `"count"` field, or 1 if the field is absent. A count of 0 is invalid.
+fn count_field(json: &Json) -> Result<usize, ProtocolError>{
+    if json.get("count").is_none(){return Ok(1);}
+    match usize_field(json, "count")?{
+        0 => Err(ProtocolError::InvalidField("count")),
+        count => Ok(count)
+    }
+}
+/// Returns the soft tab width held by the `"tab_width"` field. A width of 0 is invalid, because indentation code divides by it.
+fn tab_width_field(json: &Json) -> Result<usize, ProtocolError>{
+    match usize_field(json, "tab_width")?{
+        0 => Err(ProtocolError::InvalidField("tab_width")),
+        tab_width => Ok(tab_width)
+    }
+}
+/// Returns the text object scope named by the `"scope"` field, either `"inside"` or `"around"`.
+fn scope_field(json: &Json) -> Result<TextObjectScope, ProtocolError>{
+    match string_field(json, "scope")?.as_str(){
+        "inside" => Ok(TextObjectScope::Inside),
+        "around" => Ok(TextObjectScope::Around),
+        _ => Err(ProtocolError::InvalidField("scope"))
+    }
+}
+/// Returns the pipe mode named by the optional `"mode"` field, either `"replace"`, `"insert"` or `"keep"`, or replace if the field is absent.
+fn mode_field(json: &Json) -> Result<PipeMode, ProtocolError>{
+    if json.get("mode").is_none(){return Ok(PipeMode::Replace);}
+    match string_field(json, "mode")?.as_str(){
+        "replace" => Ok(PipeMode::Replace),
+        "insert" => Ok(PipeMode::Insert),
+        "keep" => Ok(PipeMode::Keep),
+        _ => Err(ProtocolError::InvalidField("mode"))
+    }
+}
+/// Returns the pairs held by the optional `"pairs"` field, as alternating open/close chars, or the default pairs if the field is absent.
+fn pairs_field(json: &Json) -> Result<Vec<(char, char)>, ProtocolError>{
+    if json.get("pairs").is_none(){return Ok(DEFAULT_PAIRS.to_vec());}
+    let chars: Vec<char> = string_field(json, "pairs")?.chars().collect();
+    if chars.is_empty() || !chars.len().is_multiple_of(2){return Err(ProtocolError::InvalidField("pairs"));}
+    Ok(chars.chunks_exact(2).map(|pair| (pair[0], pair[1])).collect())
+}
+fn char_field(json: &Json, field: &'static str) -> Result<char, ProtocolError>{
+    let string = string_field(json, field)?;
+    let mut chars = string.chars();
+    match (chars.next(), chars.next()){
+        (Some(char), None) => Ok(char),
+        _ => Err(ProtocolError::InvalidField(field))
+    }
+}
@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+use crate::json::JsonError;
+use crate::protocol::{Request, Action, ProtocolError};
+use crate::selection::{CursorSemantics, TextObjectScope};
+use crate::register::RegisterName;
+use crate::shell::PipeMode;
+
+#[test] fn parses_session_requests(){
+    assert_eq!(Ok(Request::Connect{semantics: CursorSemantics::Bar}), Request::parse(r#"{"request": "connect"}"#));
+    assert_eq!(Ok(Request::Connect{semantics: CursorSemantics::Block}), Request::parse(r#"{"request": "connect", "semantics": "block"}"#));
+    assert_eq!(Ok(Request::OpenDocument{client: 2, path: PathBuf::from("/tmp/idk")}), Request::parse(r#"{"request": "open_document", "client": 2, "path": "/tmp/idk"}"#));
+    assert_eq!(Ok(Request::SetViewSize{client: 0, width: 80, height: 24}), Request::parse(r#"{"request": "set_view_size", "client": 0, "width": 80, "height": 24}"#));
+    assert_eq!(Ok(Request::ViewText{client: 0}), Request::parse(r#"{"request": "view_text", "client": 0}"#));
+}
+
+#[test] fn parses_actions_with_arguments(){
+    assert_eq!(
+        Ok(Request::Action{client: 0, action: Action::InsertString{string: "idk\n".to_string(), use_hard_tab: false, tab_width: 4}}),
+        Request::parse(r#"{"request": "insert_string", "client": 0, "string": "idk\n", "use_hard_tab": false, "tab_width": 4}"#)
+    );
+    assert_eq!(
+        Ok(Request::Action{client: 1, action: Action::AddSurroundingPair{leading_char: '{', trailing_char: '}'}}),
+        Request::parse(r#"{"request": "add_surrounding_pair", "client": 1, "leading_char": "{", "trailing_char": "}"}"#)
+    );
+    assert_eq!(Ok(Request::Action{client: 0, action: Action::Copy{register: RegisterName::default()}}), Request::parse(r#"{"request": "copy", "client": 0}"#));
+    assert_eq!(Ok(Request::Action{client: 0, action: Action::Cut{register: RegisterName::Named('q')}}), Request::parse(r#"{"request": "cut", "client": 0, "register": "q"}"#));
+    assert_eq!(Ok(Request::Action{client: 0, action: Action::ScrollViewDown{amount: 3}}), Request::parse(r#"{"request": "scroll_view_down", "client": 0, "amount": 3}"#));
+    assert_eq!(Ok(Request::Action{client: 0, action: Action::SelectWord{scope: TextObjectScope::Around}}), Request::parse(r#"{"request": "select_word", "client": 0, "scope": "around"}"#));
+    assert_eq!(Err(ProtocolError::InvalidField("scope")), Request::parse(r#"{"request": "select_paragraph", "client": 0, "scope": "idk"}"#));
+    assert_eq!(
+        Ok(Request::Action{client: 0, action: Action::SelectSurroundingPair{scope: TextObjectScope::Inside, pairs: vec![('(', ')'), ('|', '|')]}}),
+        Request::parse(r#"{"request": "select_surrounding_pair", "client": 0, "scope": "inside", "pairs": "()||"}"#)
+    );
+    assert_eq!(Err(ProtocolError::InvalidField("pairs")), Request::parse(r#"{"request": "select_surrounding_pair", "client": 0, "scope": "inside", "pairs": "(){"}"#));
+    assert_eq!(Ok(Request::Action{client: 0, action: Action::MoveCursorDown{count: 1}}), Request::parse(r#"{"request": "move_cursor_down", "client": 0}"#));
+    assert_eq!(Ok(Request::Action{client: 0, action: Action::Delete{count: 3}}), Request::parse(r#"{"request": "delete", "client": 0, "count": 3}"#));
+    assert_eq!(Err(ProtocolError::InvalidField("count")), Request::parse(r#"{"request": "move_cursor_up", "client": 0, "count": 0}"#));
+    assert_eq!(Ok(Request::Action{client: 0, action: Action::Dedent{tab_width: 4}}), Request::parse(r#"{"request": "dedent", "client": 0, "tab_width": 4}"#));
+    assert_eq!(
+        Ok(Request::Action{client: 0, action: Action::PipeSelections{command: "sort".to_string(), mode: PipeMode::Keep}}),
+        Request::parse(r#"{"request": "pipe_selections", "client": 0, "command": "sort", "mode": "keep"}"#)
+    );
+    assert_eq!(Err(ProtocolError::InvalidField("mode")), Request::parse(r#"{"request": "pipe_selections", "client": 0, "command": "sort", "mode": "idk"}"#));
+    assert_eq!(
+        Ok(Request::Action{client: 0, action: Action::SelectAllMatches{pattern: r"s\w+".to_string()}}),
+        Request::parse(r#"{"request": "select_all_matches", "client": 0, "pattern": "s\\w+"}"#)
+    );
+    assert_eq!(Err(ProtocolError::MissingField("pattern")), Request::parse(r#"{"request": "search_next", "client": 0}"#));
+}
+
+#[test] fn errors_on_invalid_requests(){
+    assert_eq!(Err(ProtocolError::InvalidJson(JsonError::UnexpectedEnd)), Request::parse(r#"{"request": "undo""#));
+    assert_eq!(Err(ProtocolError::MissingField("request")), Request::parse(r#"{"client": 0}"#));
+    assert_eq!(Err(ProtocolError::UnknownRequest("idk".to_string())), Request::parse(r#"{"request": "idk", "client": 0}"#));
+    assert_eq!(Err(ProtocolError::MissingField("client")), Request::parse(r#"{"request": "undo"}"#));
+    assert_eq!(Err(ProtocolError::InvalidField("amount")), Request::parse(r#"{"request": "scroll_view_up", "client": 0, "amount": -1}"#));
+    assert_eq!(Err(ProtocolError::InvalidField("leading_char")), Request::parse(r#"{"request": "add_surrounding_pair", "client": 0, "leading_char": "ab", "trailing_char": "}"}"#));
+    assert_eq!(Err(ProtocolError::InvalidField("register")), Request::parse(r#"{"request": "copy", "client": 0, "register": "A"}"#));
+    assert_eq!(Err(ProtocolError::InvalidField("tab_width")), Request::parse(r#"{"request": "insert_string", "client": 0, "string": "\t", "use_hard_tab": false, "tab_width": 0}"#));
+    assert_eq!(Err(ProtocolError::InvalidField("tab_width")), Request::parse(r#"{"request": "backspace", "client": 0, "use_hard_tab": false, "tab_width": 0}"#));
+    assert_eq!(Err(ProtocolError::InvalidField("semantics")), Request::parse(r#"{"request": "connect", "semantics": "idk"}"#));
+}
@@ -0,0 +1,107 @@
+//! Vim style registers, used by copy/cut/paste in place of a single clipboard string.
+//!
+//! Each register holds one text fragment per selection it was written from, so copying from N selections and pasting into
+//! N selections puts each fragment back into its own selection.
+
+use std::collections::{HashMap, VecDeque};
+
+
+
+/// Number of previous yanks retained in the yank history.
+pub const YANK_HISTORY_LEN: usize = 10;
+
+/// Identifies a register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RegisterName{
+    /// Named registers `a` through `z`.
+    Named(char),
+    /// Previous yanks, with 0 being the most recent. Writing to any yank history register pushes a new most recent yank.
+    YankHistory(usize),
+    /// Discards anything written to it, and is always empty.
+    BlackHole,
+    /// The system clipboard, accessed through the editor's [`ClipboardProvider`](crate::clipboard::ClipboardProvider).
+    /// [`Registers`] only holds the fragments most recently copied to the clipboard, so they can be pasted back one per selection.
+    Clipboard,
+}
+impl RegisterName{
+    /// Returns the register name for its vim style char. `a`-`z` are named registers, `0`-`9` are yank history,
+    /// `_` is the black hole register, `+` and `*` are the system clipboard, and `"` is the default register.
+    /// ```
+    /// # use edit_core::register::RegisterName;
+    ///
+    /// assert_eq!(Some(RegisterName::Named('a')), RegisterName::from_char('a'));
+    /// assert_eq!(Some(RegisterName::YankHistory(3)), RegisterName::from_char('3'));
+    /// assert_eq!(Some(RegisterName::BlackHole), RegisterName::from_char('_'));
+    /// assert_eq!(Some(RegisterName::Clipboard), RegisterName::from_char('+'));
+    /// assert_eq!(Some(RegisterName::default()), RegisterName::from_char('"'));
+    /// assert_eq!(None, RegisterName::from_char('A'));
+    /// ```
+    #[must_use] pub fn from_char(char: char) -> Option<Self>{
+        match char{
+            'a'..='z' => Some(RegisterName::Named(char)),
+            '0'..='9' => char.to_digit(10).map(|digit| RegisterName::YankHistory(digit as usize)),
+            '_' => Some(RegisterName::BlackHole),
+            '+' | '*' => Some(RegisterName::Clipboard),
+            '"' => Some(RegisterName::default()),
+            _ => None
+        }
+    }
+}
+/// The default register is the most recent yank.
+impl Default for RegisterName{
+    fn default() -> Self{
+        RegisterName::YankHistory(0)
+    }
+}
+
+/// Holds the contents of every register.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Registers{
+    named: HashMap<char, Vec<String>>,
+    yank_history: VecDeque<Vec<String>>,   //front is most recent
+    clipboard: Option<Vec<String>>,
+}
+impl Registers{
+    /// Returns the fragments held in register `name`, if any.
+    #[must_use] pub fn get(&self, name: RegisterName) -> Option<&[String]>{
+        match name{
+            RegisterName::Named(char) => self.named.get(&char).map(Vec::as_slice),
+            RegisterName::YankHistory(index) => self.yank_history.get(index).map(Vec::as_slice),
+            RegisterName::BlackHole => None,
+            RegisterName::Clipboard => self.clipboard.as_deref()
+        }
+    }
+
+    /// Stores `fragments` in register `name`, one fragment per selection.
+    pub fn set(&mut self, name: RegisterName, fragments: Vec<String>){
+        match name{
+            RegisterName::Named(char) => {self.named.insert(char, fragments);}
+            RegisterName::YankHistory(_) => {
+                self.yank_history.push_front(fragments);
+                self.yank_history.truncate(YANK_HISTORY_LEN);
+            }
+            RegisterName::BlackHole => {}
+            RegisterName::Clipboard => {self.clipboard = Some(fragments);}
+        }
+    }
+
+    /// Returns the contents of register `name` distributed over `selection_count` selections, if the register holds anything.
+    /// If the register holds one fragment per selection, each selection gets its own fragment.
+    /// Otherwise, every selection gets all fragments joined with a newline.
+    /// ```
+    /// # use edit_core::register::{Registers, RegisterName};
+    ///
+    /// let mut registers = Registers::default();
+    /// registers.set(RegisterName::default(), vec!["idk".to_string(), "some".to_string()]);
+    /// assert_eq!(Some(vec!["idk".to_string(), "some".to_string()]), registers.fragments_for(RegisterName::default(), 2));
+    /// assert_eq!(Some(vec!["idk\nsome".to_string(); 3]), registers.fragments_for(RegisterName::default(), 3));
+    /// ```
+    #[must_use] pub fn fragments_for(&self, name: RegisterName, selection_count: usize) -> Option<Vec<String>>{
+        let fragments = self.get(name)?;
+        if fragments.len() == selection_count{
+            Some(fragments.to_vec())
+        }else{
+            Some(vec![fragments.join("\n"); selection_count])
+        }
+    }
+}
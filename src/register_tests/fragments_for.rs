@@ -0,0 +1,26 @@
+use crate::register::{Registers, RegisterName};
+
+#[test] fn distributes_one_fragment_per_selection_when_counts_match(){
+    let mut registers = Registers::default();
+    registers.set(RegisterName::Named('a'), vec!["idk".to_string(), "some".to_string(), "shit".to_string()]);
+    assert_eq!(Some(vec!["idk".to_string(), "some".to_string(), "shit".to_string()]), registers.fragments_for(RegisterName::Named('a'), 3));
+}
+
+#[test] fn joins_fragments_with_newline_for_every_selection_when_counts_differ(){
+    let mut registers = Registers::default();
+    registers.set(RegisterName::Named('a'), vec!["idk".to_string(), "some".to_string(), "shit".to_string()]);
+    assert_eq!(Some(vec!["idk\nsome\nshit".to_string(); 2]), registers.fragments_for(RegisterName::Named('a'), 2));
+    assert_eq!(Some(vec!["idk\nsome\nshit".to_string()]), registers.fragments_for(RegisterName::Named('a'), 1));
+}
+
+#[test] fn single_fragment_is_pasted_at_every_selection(){
+    let mut registers = Registers::default();
+    registers.set(RegisterName::default(), vec!["idk".to_string()]);
+    assert_eq!(Some(vec!["idk".to_string(); 3]), registers.fragments_for(RegisterName::default(), 3));
+}
+
+#[test] fn returns_none_for_empty_register(){
+    let registers = Registers::default();
+    assert_eq!(None, registers.fragments_for(RegisterName::Named('z'), 1));
+    assert_eq!(None, registers.fragments_for(RegisterName::BlackHole, 1));
+}
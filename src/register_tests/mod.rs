@@ -0,0 +1,2 @@
+mod set;
+mod fragments_for;
@@ -0,0 +1,44 @@
+use crate::register::{Registers, RegisterName, YANK_HISTORY_LEN};
+
+fn fragments(fragments: &[&str]) -> Vec<String>{
+    fragments.iter().map(|fragment| fragment.to_string()).collect()
+}
+
+#[test] fn named_registers_are_independent(){
+    let mut registers = Registers::default();
+    registers.set(RegisterName::Named('a'), fragments(&["idk"]));
+    registers.set(RegisterName::Named('b'), fragments(&["some", "shit"]));
+    registers.set(RegisterName::Named('a'), fragments(&["other"]));
+    assert_eq!(Some(fragments(&["other"]).as_slice()), registers.get(RegisterName::Named('a')));
+    assert_eq!(Some(fragments(&["some", "shit"]).as_slice()), registers.get(RegisterName::Named('b')));
+    assert_eq!(None, registers.get(RegisterName::Named('c')));
+    assert_eq!(None, registers.get(RegisterName::default()));
+}
+
+#[test] fn writing_yank_history_shifts_previous_yanks(){
+    let mut registers = Registers::default();
+    registers.set(RegisterName::default(), fragments(&["idk"]));
+    registers.set(RegisterName::default(), fragments(&["some"]));
+    registers.set(RegisterName::YankHistory(5), fragments(&["shit"]));
+    assert_eq!(Some(fragments(&["shit"]).as_slice()), registers.get(RegisterName::YankHistory(0)));
+    assert_eq!(Some(fragments(&["some"]).as_slice()), registers.get(RegisterName::YankHistory(1)));
+    assert_eq!(Some(fragments(&["idk"]).as_slice()), registers.get(RegisterName::YankHistory(2)));
+    assert_eq!(None, registers.get(RegisterName::YankHistory(3)));
+}
+
+#[test] fn yank_history_drops_oldest_yank_when_full(){
+    let mut registers = Registers::default();
+    for i in 0..=YANK_HISTORY_LEN{
+        registers.set(RegisterName::default(), vec![i.to_string()]);
+    }
+    assert_eq!(Some([YANK_HISTORY_LEN.to_string()].as_slice()), registers.get(RegisterName::YankHistory(0)));
+    assert_eq!(Some(["1".to_string()].as_slice()), registers.get(RegisterName::YankHistory(YANK_HISTORY_LEN - 1)));
+    assert_eq!(None, registers.get(RegisterName::YankHistory(YANK_HISTORY_LEN)));
+}
+
+#[test] fn black_hole_register_discards_writes(){
+    let mut registers = Registers::default();
+    registers.set(RegisterName::BlackHole, fragments(&["idk"]));
+    assert_eq!(None, registers.get(RegisterName::BlackHole));
+    assert_eq!(Registers::default(), registers);
+}
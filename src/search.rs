@@ -0,0 +1,257 @@
+//! Regex search over a [`Rope`], without collecting the searched text into a [`String`].
+//!
+//! A [`Search`] runs `regex-automata`'s lazy DFAs directly over the rope's chunks, one byte at a time. The forward DFA finds
+//! where each match ends, and the reverse DFA walks back from there to find where it starts. Matches have the same leftmost-first
+//! semantics as the `regex` crate, and are returned as char indices.
+//!
+//! Some patterns can't be searched this way(a Unicode aware `\b` in non ASCII text, for example). Those searches fall back to the
+//! `regex` crate, over the searched slice of text only.
+
+use ropey::{Rope, str_utils};
+use regex::Regex;
+use regex_automata::{
+    hybrid::{self, dfa::{Cache, DFA}, LazyStateID},
+    util::{prefilter::Prefilter, start, syntax},
+    Anchored, MatchKind, Span,
+};
+use crate::range::Range;
+
+
+
+#[derive(Debug, PartialEq)]
+pub enum SearchError{
+    /// the pattern is not a valid regex
+    InvalidPattern,
+}
+
+/// The lazy DFA could not continue a search. The search is redone with the `regex` crate instead.
+struct GaveUp;
+
+/// A compiled regex pattern, that can be searched for in a [`Rope`].
+#[derive(Debug)]
+pub struct Search{
+    regex: Regex,
+    lazy: Option<hybrid::regex::Regex>,    //None if the pattern can't be compiled to a lazy DFA
+}
+impl Search{
+    /// Compiles `pattern`, using the `regex` crate's syntax.
+    pub fn new(pattern: &str) -> Result<Self, SearchError>{
+        let Ok(regex) = Regex::new(pattern) else{return Err(SearchError::InvalidPattern);};
+        // a literal prefix every match starts with lets the forward search skip straight to the next place a match could start
+        let prefilter = syntax::parse(pattern).ok()
+            .and_then(|hir| Prefilter::from_hir_prefix(MatchKind::LeftmostFirst, &hir))
+            .filter(Prefilter::is_fast);
+        let lazy = hybrid::regex::Regex::builder()
+            // a Unicode aware `\b` can't be compiled otherwise. the lazy DFA gives up on non ASCII text, instead of failing to build
+            .dfa(DFA::config().unicode_word_boundary(true).prefilter(prefilter))
+            .build(pattern)
+            .ok();
+        Ok(Self{regex, lazy})
+    }
+
+    /// Returns the char [`Range`] of every match in `text`, in text order.
+    /// ```
+    /// # use ropey::Rope;
+    /// # use edit_core::search::Search;
+    /// # use edit_core::range::Range;
+    ///
+    /// let text = Rope::from("idk\nsome\nshit\n");
+    /// assert_eq!(vec![Range::new(4, 6), Range::new(9, 11)], Search::new(r"s\w").unwrap().find_all(&text));
+    /// ```
+    #[must_use] pub fn find_all(&self, text: &Rope) -> Vec<Range>{
+        self.find_in(&Range::new(0, text.len_chars()), text)
+    }
+
+    /// Returns the char [`Range`] of every match within `range` of `text`, in text order.
+    /// The start and end of `range` are treated as the start and end of the text, like searching a slice of a [`String`].
+    #[must_use] pub fn find_in(&self, range: &Range, text: &Rope) -> Vec<Range>{
+        let mut haystack = Haystack::new(text, range);
+        let matches = match &self.lazy{
+            Some(lazy) => haystack.find_iter(lazy).unwrap_or_else(|_| self.find_iter_materialized(&haystack)),
+            None => self.find_iter_materialized(&haystack)
+        };
+        matches.into_iter()
+            .map(|(start, end)| Range::new(haystack.char_index(start), haystack.char_index(end)))
+            .collect()
+    }
+
+    /// Returns the byte span of every match in `haystack`, by collecting its text into a [`String`] and searching with the `regex` crate.
+    fn find_iter_materialized(&self, haystack: &Haystack) -> Vec<(usize, usize)>{
+        let slice = haystack.text.byte_slice(haystack.start..haystack.end).to_string();
+        self.regex.find_iter(&slice)
+            .map(|search_match| (haystack.start + search_match.start(), haystack.start + search_match.end()))
+            .collect()
+    }
+}
+
+/// The bytes of `text` from `start` to `end`. Bytes outside of these bounds are not visible to a search.
+/// The chunk last read from is kept, because most reads are close to the one before.
+struct Haystack<'a>{
+    text: &'a Rope,
+    start: usize,
+    end: usize,
+    chunk: &'a str,
+    chunk_start: usize,         //byte index of the start of `chunk`
+    chunk_char_start: usize,    //char index of the start of `chunk`
+}
+impl<'a> Haystack<'a>{
+    /// Returns the haystack of the chars within `range` of `text`. `range` is clamped to the text.
+    fn new(text: &'a Rope, range: &Range) -> Self{
+        let (chunk, chunk_start, chunk_char_start, _) = text.chunk_at_byte(0);
+        Self{
+            text,
+            start: text.char_to_byte(range.start.min(text.len_chars())),
+            end: text.char_to_byte(range.end.min(text.len_chars())),
+            chunk,
+            chunk_start,
+            chunk_char_start
+        }
+    }
+
+    /// Makes the chunk containing byte `at` the current chunk, and returns it with its start byte index.
+    /// The text end is treated as part of the last chunk.
+    fn chunk_at(&mut self, at: usize) -> (&'a str, usize){
+        let chunk_end = self.chunk_start + self.chunk.len();
+        if at < self.chunk_start || at > chunk_end || (at == chunk_end && at < self.text.len_bytes()){
+            let (chunk, chunk_start, chunk_char_start, _) = self.text.chunk_at_byte(at);
+            self.chunk = chunk;
+            self.chunk_start = chunk_start;
+            self.chunk_char_start = chunk_char_start;
+        }
+        (self.chunk, self.chunk_start)
+    }
+    /// Returns the char index of byte `at`.
+    fn char_index(&mut self, at: usize) -> usize{
+        let (chunk, chunk_start) = self.chunk_at(at);
+        self.chunk_char_start + str_utils::byte_to_char_idx(chunk, at - chunk_start)
+    }
+    /// Returns the byte before `at`, if it is in the haystack.
+    fn byte_before(&mut self, at: usize) -> Option<u8>{
+        if at <= self.start{return None;}
+        let (chunk, chunk_start) = self.chunk_at(at - 1);
+        Some(chunk.as_bytes()[at - 1 - chunk_start])
+    }
+    /// Returns the byte at `at`, if it is in the haystack.
+    fn byte_at(&mut self, at: usize) -> Option<u8>{
+        if at >= self.end{return None;}
+        let (chunk, chunk_start) = self.chunk_at(at);
+        Some(chunk.as_bytes()[at - chunk_start])
+    }
+    /// Returns `true` if `at` is not in the middle of a UTF-8 encoded char.
+    fn is_char_boundary(&mut self, at: usize) -> bool{
+        self.byte_at(at).is_none_or(|byte| byte & 0b1100_0000 != 0b1000_0000)
+    }
+
+    /// Returns the byte span of every match, with the same handling of empty matches as [`Regex::find_iter`].
+    fn find_iter(&mut self, lazy: &hybrid::regex::Regex) -> Result<Vec<(usize, usize)>, GaveUp>{
+        let mut cache = lazy.create_cache();
+        let (forward_cache, reverse_cache) = cache.as_parts_mut();
+        let mut matches = Vec::new();
+        let mut last_end = None;
+        let mut at = self.start;
+        while at <= self.end{
+            let Some(end) = self.find_end(lazy.forward(), forward_cache, at)? else{break;};
+            let start = self.find_start(lazy.reverse(), reverse_cache, at, end)?;
+            // an empty match is skipped if it touches the previous match, or splits a char
+            if start == end && (last_end == Some(end) || !self.is_char_boundary(end)){
+                at = end + 1;
+                continue;
+            }
+            matches.push((start, end));
+            last_end = Some(end);
+            at = end;
+        }
+        Ok(matches)
+    }
+
+    /// Returns the end of the leftmost-first match starting at or after `at`, by walking the chunks after `at` with the forward DFA.
+    fn find_end(&mut self, dfa: &DFA, cache: &mut Cache, at: usize) -> Result<Option<usize>, GaveUp>{
+        let prefilter = dfa.get_config().get_prefilter();
+        let mut state = self.forward_start_state(dfa, cache, at)?;
+        let mut end = None;
+        let mut position = at;
+        if let (Some(prefilter), true) = (prefilter, state.is_start()){
+            position = self.find_candidate(prefilter, position);
+            state = self.forward_start_state(dfa, cache, position)?;
+        }
+        'chunks: while position < self.end{
+            let (chunk, chunk_start) = self.chunk_at(position);
+            let chunk_end = (chunk_start + chunk.len()).min(self.end);
+            let bytes = chunk.as_bytes();
+            while position < chunk_end{
+                state = dfa.next_state(cache, state, bytes[position - chunk_start]).map_err(|_| GaveUp)?;
+                // matches are reported one byte late, so a match state here means a match ended before this byte
+                if state.is_tagged(){
+                    if state.is_match(){end = Some(position);}
+                    else if state.is_dead(){return Ok(end);}
+                    else if state.is_quit(){return Err(GaveUp);}
+                    else if let (Some(prefilter), true) = (prefilter, state.is_start()){
+                        let candidate = self.find_candidate(prefilter, position);
+                        if candidate > position{
+                            position = candidate;
+                            state = self.forward_start_state(dfa, cache, position)?;
+                            continue 'chunks;
+                        }
+                    }
+                }
+                position += 1;
+            }
+        }
+        state = dfa.next_eoi_state(cache, state).map_err(|_| GaveUp)?;
+        if state.is_match(){end = Some(self.end);}
+        Ok(end)
+    }
+
+    /// Returns the forward DFA's state for an unanchored search starting at `at`.
+    fn forward_start_state(&mut self, dfa: &DFA, cache: &mut Cache, at: usize) -> Result<LazyStateID, GaveUp>{
+        let config = start::Config::new().anchored(Anchored::No).look_behind(self.byte_before(at));
+        dfa.start_state(cache, &config).map_err(|_| GaveUp)
+    }
+
+    /// Returns the first position at or after `from` where a match could start, according to `prefilter`, or the haystack end if there is none.
+    fn find_candidate(&mut self, prefilter: &Prefilter, from: usize) -> usize{
+        let mut position = from;
+        while position < self.end{
+            let (chunk, chunk_start) = self.chunk_at(position);
+            let chunk_end = (chunk_start + chunk.len()).min(self.end);
+            let bytes = &chunk.as_bytes()[..chunk_end - chunk_start];
+            if let Some(span) = prefilter.find(bytes, Span::from(position - chunk_start..bytes.len())){
+                return chunk_start + span.start;
+            }
+            // a literal starting near the end of this chunk could continue into the next one. the DFA checks those bytes itself
+            let straddle_start = chunk_end.saturating_sub(prefilter.max_needle_len().saturating_sub(1)).max(position);
+            if straddle_start < chunk_end && chunk_end < self.end{return straddle_start;}
+            position = chunk_end;
+        }
+        self.end
+    }
+
+    /// Returns the start of the match ending at `end`, by walking the chunks before `end`, back to `at`, with the reverse DFA.
+    fn find_start(&mut self, dfa: &DFA, cache: &mut Cache, at: usize, end: usize) -> Result<usize, GaveUp>{
+        let config = start::Config::new().anchored(Anchored::Yes).look_behind(self.byte_at(end));
+        let mut state = dfa.start_state(cache, &config).map_err(|_| GaveUp)?;
+        let mut start = None;
+        let mut position = end;
+        while position > at{
+            let (chunk, chunk_start) = self.chunk_at(position - 1);
+            let chunk_start_in_haystack = chunk_start.max(at);
+            for &byte in chunk.as_bytes()[chunk_start_in_haystack - chunk_start..position - chunk_start].iter().rev(){
+                position -= 1;
+                state = dfa.next_state(cache, state, byte).map_err(|_| GaveUp)?;
+                // matches are reported one byte late, so a match state here means a match started after this byte
+                if state.is_tagged(){
+                    if state.is_match(){start = Some(position + 1);}
+                    else if state.is_dead(){return start.ok_or(GaveUp);}
+                    else if state.is_quit(){return Err(GaveUp);}
+                }
+            }
+        }
+        state = match self.byte_before(at){
+            Some(byte) => dfa.next_state(cache, state, byte).map_err(|_| GaveUp)?,
+            None => dfa.next_eoi_state(cache, state).map_err(|_| GaveUp)?
+        };
+        if state.is_quit(){return Err(GaveUp);}
+        if state.is_match(){start = Some(at);}
+        start.ok_or(GaveUp)
+    }
+}
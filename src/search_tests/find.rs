@@ -0,0 +1,57 @@
+use ropey::Rope;
+use regex::Regex;
+use crate::range::Range;
+use crate::search::{Search, SearchError};
+
+/// Asserts that searching `text` as a [`Rope`] finds the same matches as the `regex` crate finds in it as a [`String`].
+fn assert_same_as_regex(pattern: &str, text: &str){
+    let rope = Rope::from(text);
+    let expected: Vec<Range> = Regex::new(pattern).unwrap().find_iter(text)
+        .map(|search_match| Range::new(rope.byte_to_char(search_match.start()), rope.byte_to_char(search_match.end())))
+        .collect();
+    assert_eq!(expected, Search::new(pattern).unwrap().find_all(&rope), "pattern: {pattern:?}");
+}
+
+#[test] fn finds_matches_as_char_indices(){
+    let text = Rope::from("é😀\nidk\nsome\nidk\n");
+    assert_eq!(vec![Range::new(3, 6), Range::new(12, 15)], Search::new("idk").unwrap().find_all(&text));
+}
+
+#[test] fn finds_same_matches_as_regex(){
+    for text in ["idk\nsome\nshit\n\nsomething else\n  tab\tidk\n", "idk\nsome\nshit\n\nsomething else\néa😀b   tab\tidk\n"]{
+        for pattern in ["idk", r"s\w+", r"\w+", "^", "$", "(?m)^", "(?m)$", r"\s*", "", "a|ab", "some|something", "[^\n]*", r"(?m)^\w", r"\d*", "😀", r"\bs", r"(?-u)\bs"]{
+            assert_same_as_regex(pattern, text);
+        }
+    }
+}
+
+#[test] fn finds_matches_across_chunk_boundaries(){
+    // ropey chunks are at most a few kilobytes, so this text spans many chunks
+    let text = "idk some shit\nsomething é else😀\n".repeat(2_000);
+    for pattern in ["some", r"shit\nsome", r"else😀\nidk", r"(?m)^\w+$", r"(?m)\w+$"]{
+        assert_same_as_regex(pattern, &text);
+    }
+}
+
+#[test] fn falls_back_to_regex_for_unicode_word_boundary_in_non_ascii_text(){
+    assert_same_as_regex(r"\bé\w*", "idk éa\nsome é\n");
+}
+
+#[test] fn treats_range_bounds_as_text_bounds(){
+    //                     1
+    // 0 1 2 3 4 5 6 7 8 9 0 1 2 3
+    // i d k \n s o m e \n s h i t \n
+    let text = Rope::from("idk\nsome\nshit\n");
+    let search = Search::new(r"^\w").unwrap();
+    assert_eq!(vec![Range::new(5, 6)], search.find_in(&Range::new(5, 13), &text));
+    assert_eq!(vec![Range::new(5, 7)], Search::new(r"\w\w$").unwrap().find_in(&Range::new(4, 7), &text));
+}
+
+#[test] fn find_in_clamps_range_to_text(){
+    let text = Rope::from("idk");
+    assert_eq!(vec![Range::new(2, 3)], Search::new("k").unwrap().find_in(&Range::new(1, 4), &text));
+}
+
+#[test] fn errors_on_invalid_pattern(){
+    assert_eq!(SearchError::InvalidPattern, Search::new("(").unwrap_err());
+}
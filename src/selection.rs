@@ -1,7 +1,7 @@
 // follow documentation style from https://std-dev-guide.rust-lang.org/development/how-to-write-documentation.html
 use ropey::Rope;
 use crate::{
-    text_util, position::Position, selection2d::Selection2d, range::Range
+    text_util::{self, Coordinates}, position::Position, selection2d::Selection2d, range::Range, json::Json
 };
 
 
@@ -23,6 +23,13 @@ pub enum Movement{
     Move,
 }
 
+/// Whether selecting a text object selects only the object itself, or also what surrounds it(like adjacent whitespace).
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum TextObjectScope{
+    Inside,
+    Around,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum SelectionError{        //or should each fallible fn have its own fn specific Error? this would prevent the calling fn from having to match unused variants in the fallible fn...
     ResultsInSameState,
@@ -547,26 +554,70 @@ impl Selection{
     
     //TODO: make pub fn select_until    //extend selection until provided character/string is selected (should have one for forwards and one for backwards)
 
+    /// Moves the cursor back by `amount` chars, then extends the [`Selection`] forward over those chars.
+    /// `amount` is a char count, not a byte count, so it can be taken directly from the `chars().count()` of edited text.
     //TODO: should this be made purely functional?
     //TODO: should this pass up possible errors from move/extend calls?
     pub fn shift_and_extend(&mut self, amount: usize, text: &Rope, semantics: CursorSemantics){ //-> Result<(), SelectionError>{
-        for _ in 0..amount{
-            if let Ok(new_selection) = crate::utilities::move_cursor_left::selection_impl(self, text, semantics){
-                *self = new_selection;
-            }
+        if amount < 1{return;}
+        let cursor = self.cursor(text, semantics);
+        if let Ok(new_selection) = self.put_cursor(cursor.saturating_sub(amount), text, Movement::Move, semantics, true){
+            *self = new_selection;
         }
         if amount > 1{
-            for _ in match semantics{   //match semantics to determine our iter range
-                CursorSemantics::Bar => 0..amount,
-                CursorSemantics::Block => 0..amount.saturating_sub(1)
-            }{
-                if let Ok(new_selection) = crate::utilities::extend_selection_right::selection_impl(self, text, semantics){
-                    *self = new_selection;
-                }
+            let to = match semantics{
+                CursorSemantics::Bar => cursor,
+                CursorSemantics::Block => text_util::previous_grapheme_index(cursor, text)
+            };
+            if let Ok(new_selection) = self.put_cursor(to, text, Movement::Extend, semantics, true){
+                *self = new_selection;
             }
         }
     }
 
+    /// Returns a new instance of [`Selection`] from an `anchor` and `head` expressed in the specified [`Coordinates`].
+    pub fn new_from_coordinates(anchor: usize, head: usize, coordinates: Coordinates, text: &Rope, semantics: CursorSemantics) -> Self{
+        Self::new_from_components(
+            text_util::char_index_from(anchor, coordinates, text), 
+            text_util::char_index_from(head, coordinates, text), 
+            None, 
+            text, 
+            semantics
+        )
+    }
+    /// Returns a new instance of [`Selection`] from an `anchor` and `head` line/column [Position], with columns counted in UTF-16 code units.
+    /// Intended for positions received from an LSP server.
+    pub fn new_from_utf16_positions(anchor: &Position, head: &Position, text: &Rope, semantics: CursorSemantics) -> Self{
+        Self::new_from_components(
+            text_util::char_index_from_utf16_position(anchor, text), 
+            text_util::char_index_from_utf16_position(head, text), 
+            None, 
+            text, 
+            semantics
+        )
+    }
+
+    /// Returns the index of [`Selection`] anchor in the specified [`Coordinates`].
+    #[must_use] pub fn anchor_as(&self, coordinates: Coordinates, text: &Rope) -> usize{
+        text_util::char_index_to(self.anchor(), coordinates, text)
+    }
+    /// Returns the index of [`Selection`] head in the specified [`Coordinates`].
+    #[must_use] pub fn head_as(&self, coordinates: Coordinates, text: &Rope) -> usize{
+        text_util::char_index_to(self.head(), coordinates, text)
+    }
+    /// Returns the index of [`Selection`] cursor in the specified [`Coordinates`].
+    #[must_use] pub fn cursor_as(&self, coordinates: Coordinates, text: &Rope, semantics: CursorSemantics) -> usize{
+        text_util::char_index_to(self.cursor(text, semantics), coordinates, text)
+    }
+
+    /// Translates a [`Selection`] to a [Selection2d], with columns counted in UTF-16 code units instead of chars.
+    #[must_use] pub fn selection_to_selection2d_utf16(&self, text: &Rope, semantics: CursorSemantics) -> Selection2d{
+        Selection2d::new(
+            text_util::char_index_to_utf16_position(self.anchor(), text),
+            text_util::char_index_to_utf16_position(self.cursor(text, semantics), text)
+        )
+    }
+
     /// Translates a [`Selection`] to a [Selection2d].
     #[must_use] pub fn selection_to_selection2d(&self, text: &Rope, semantics: CursorSemantics) -> Selection2d{
         let line_number_head = text.char_to_line(self.cursor(text, semantics));
@@ -604,4 +655,29 @@ impl Selection{
             ) 
         )
     }
+
+    /// Returns a [`Json`] representation of [`Selection`], as stored in an undo file.
+    #[must_use] pub fn to_json(&self) -> Json{
+        Json::Object(vec![
+            ("start".to_string(), Json::from(self.range.start)),
+            ("end".to_string(), Json::from(self.range.end)),
+            ("direction".to_string(), Json::from(match self.direction{Direction::Forward => "forward", Direction::Backward => "backward"})),
+            ("stored_line_position".to_string(), self.stored_line_position.map_or(Json::Null, Json::from)),
+        ])
+    }
+    /// Returns the [`Selection`] represented by `json`, if `json` is valid output of [`Selection::to_json`].
+    #[must_use] pub fn from_json(json: &Json) -> Option<Self>{
+        let direction = match json.get("direction")?.as_str()?{
+            "forward" => Direction::Forward,
+            "backward" => Direction::Backward,
+            _ => return None
+        };
+        let stored_line_position = match json.get("stored_line_position")?{
+            Json::Null => None,
+            value => Some(value.as_usize()?)
+        };
+        let (start, end) = (json.get("start")?.as_usize()?, json.get("end")?.as_usize()?);
+        if start > end{return None;}
+        Some(Self{range: Range::new(start, end), direction, stored_line_position})
+    }
 }
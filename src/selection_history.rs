@@ -0,0 +1,79 @@
+//! Selection history: like undo/redo, but for selection changes instead of text changes.
+//!
+//! Selection actions record the [`Selections`] they replace, so a frontend can jump back to where the selections were,
+//! and forward again. Runs of small steps(like repeated cursor movement by one char/line) are recorded as a single entry,
+//! so jumping back returns to where the run started, instead of retracing every step.
+
+use ropey::Rope;
+use crate::history::Edit;
+use crate::selection::CursorSemantics;
+use crate::selections::Selections;
+
+
+
+/// Maximum number of entries retained for jumping back. The oldest entries are discarded first.
+pub const SELECTION_HISTORY_LEN: usize = 100;
+
+/// How far a selection action moves selections, which determines how it is recorded in [`SelectionHistory`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionChange{
+    /// A small movement, within a line or to an adjacent line. Consecutive steps are coalesced into a single entry.
+    Step,
+    /// Any other change, like page movement, search, or adding/removing selections. Always recorded as its own entry.
+    Jump,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SelectionHistory{
+    back: Vec<Selections>,      //last is most recent
+    forward: Vec<Selections>,   //last is next to jump forward to
+    in_step_run: bool,          //whether the most recent change was a step, whose starting selections are already recorded
+}
+impl SelectionHistory{
+    /// Records `previous`, the [`Selections`] replaced by a selection action, and clears any entries to jump forward to.
+    /// `previous` is not recorded if it continues a run of steps, or matches the most recent entry.
+    pub fn record(&mut self, previous: Selections, change: SelectionChange){
+        self.forward.clear();
+        let continues_step_run = change == SelectionChange::Step && self.in_step_run;
+        self.in_step_run = change == SelectionChange::Step;
+        if continues_step_run || self.back.last() == Some(&previous){return;}
+        self.back.push(previous);
+        if self.back.len() > SELECTION_HISTORY_LEN{
+            self.back.remove(0);
+        }
+    }
+
+    /// Returns the most recently recorded [`Selections`], if any, and remembers `current` so it can be jumped forward to.
+    pub fn jump_back(&mut self, current: Selections) -> Option<Selections>{
+        let selections = self.back.pop()?;
+        self.forward.push(current);
+        self.in_step_run = false;
+        Some(selections)
+    }
+
+    /// Returns the [`Selections`] most recently jumped back from, if any, and remembers `current` so it can be jumped back to.
+    pub fn jump_forward(&mut self, current: Selections) -> Option<Selections>{
+        let selections = self.forward.pop()?;
+        self.back.push(current);
+        self.in_step_run = false;
+        Some(selections)
+    }
+
+    /// Moves every entry to account for `edits` made to the text. `text` is the text after `edits`.
+    /// A text edit also ends any run of steps, so the next step is recorded as a new entry.
+    pub fn map_edits(&mut self, edits: &[Edit], text: &Rope, semantics: CursorSemantics){
+        for selections in self.back.iter_mut().chain(self.forward.iter_mut()){
+            *selections = selections.map_edits(edits, text, semantics);
+        }
+        self.in_step_run = false;
+    }
+
+    /// Returns the entries available to jump back to, oldest first.
+    #[must_use] pub fn back(&self) -> &[Selections]{
+        &self.back
+    }
+    /// Returns the entries available to jump forward to, furthest first.
+    #[must_use] pub fn forward(&self) -> &[Selections]{
+        &self.forward
+    }
+}
@@ -0,0 +1,44 @@
+use ropey::Rope;
+use crate::selection::{Selection, CursorSemantics};
+use crate::selection_history::{SelectionHistory, SelectionChange, SELECTION_HISTORY_LEN};
+use crate::selections::Selections;
+
+fn cursor(index: usize) -> Selections{
+    let text = Rope::from("idk\nsome\nshit\n".repeat(20));
+    Selections::new(vec![Selection::new_from_components(index, index, None, &text, CursorSemantics::Bar)], 0, &text, CursorSemantics::Bar)
+}
+
+#[test] fn consecutive_steps_are_coalesced(){
+    let mut history = SelectionHistory::default();
+    history.record(cursor(0), SelectionChange::Step);
+    history.record(cursor(1), SelectionChange::Step);
+    history.record(cursor(2), SelectionChange::Jump);
+    history.record(cursor(10), SelectionChange::Step);
+    history.record(cursor(11), SelectionChange::Step);
+    assert_eq!(&[cursor(0), cursor(2), cursor(10)], history.back());
+}
+
+#[test] fn repeated_selections_are_recorded_once(){
+    let mut history = SelectionHistory::default();
+    history.record(cursor(0), SelectionChange::Jump);
+    history.record(cursor(0), SelectionChange::Jump);
+    assert_eq!(&[cursor(0)], history.back());
+}
+
+#[test] fn recording_clears_forward_history(){
+    let mut history = SelectionHistory::default();
+    history.record(cursor(0), SelectionChange::Jump);
+    assert_eq!(Some(cursor(0)), history.jump_back(cursor(1)));
+    assert_eq!(&[cursor(1)], history.forward());
+    history.record(cursor(0), SelectionChange::Jump);
+    assert!(history.forward().is_empty());
+}
+
+#[test] fn oldest_entries_are_discarded_past_limit(){
+    let mut history = SelectionHistory::default();
+    for index in 0..=SELECTION_HISTORY_LEN{
+        history.record(cursor(index), SelectionChange::Jump);
+    }
+    assert_eq!(SELECTION_HISTORY_LEN, history.back().len());
+    assert_eq!(Some(&cursor(1)), history.back().first());
+}
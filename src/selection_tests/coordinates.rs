@@ -0,0 +1,34 @@
+use ropey::Rope;
+use crate::selection::{Selection, CursorSemantics};
+use crate::text_util::Coordinates;
+
+// a 🦀 e◌́ \n
+//chars:    0 1 2 3 4 (5)
+//graphemes 0 1 2 . 3 (4)
+//bytes:    0 1 5 6 8 (9)
+//utf16:    0 1 3 4 5 (6)
+#[test] fn anchor_and_head_as(){
+    let text = Rope::from("a\u{1f980}e\u{301}\n");
+    let selection = Selection::new_from_components(1, 4, None, &text, CursorSemantics::Bar);
+    assert_eq!(1, selection.anchor_as(Coordinates::Grapheme, &text));
+    assert_eq!(3, selection.head_as(Coordinates::Grapheme, &text));
+    assert_eq!(1, selection.anchor_as(Coordinates::Utf8Byte, &text));
+    assert_eq!(8, selection.head_as(Coordinates::Utf8Byte, &text));
+    assert_eq!(1, selection.anchor_as(Coordinates::Utf16CodeUnit, &text));
+    assert_eq!(5, selection.head_as(Coordinates::Utf16CodeUnit, &text));
+}
+
+#[test] fn cursor_as_block_semantics(){
+    let text = Rope::from("a\u{1f980}e\u{301}\n");
+    let selection = Selection::new_from_components(0, 3, None, &text, CursorSemantics::Block);
+    assert_eq!(2, selection.cursor_as(Coordinates::Char, &text, CursorSemantics::Block));
+    assert_eq!(5, selection.cursor_as(Coordinates::Utf8Byte, &text, CursorSemantics::Block));
+    assert_eq!(3, selection.cursor_as(Coordinates::Utf16CodeUnit, &text, CursorSemantics::Block));
+}
+
+#[test] fn new_from_coordinates(){
+    let text = Rope::from("a\u{1f980}e\u{301}\n");
+    assert_eq!(Selection::new_from_components(1, 4, None, &text, CursorSemantics::Bar), Selection::new_from_coordinates(1, 3, Coordinates::Grapheme, &text, CursorSemantics::Bar));
+    assert_eq!(Selection::new_from_components(1, 4, None, &text, CursorSemantics::Bar), Selection::new_from_coordinates(1, 8, Coordinates::Utf8Byte, &text, CursorSemantics::Bar));
+    assert_eq!(Selection::new_from_components(1, 4, None, &text, CursorSemantics::Bar), Selection::new_from_coordinates(1, 5, Coordinates::Utf16CodeUnit, &text, CursorSemantics::Bar));
+}
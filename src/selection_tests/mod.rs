@@ -18,3 +18,6 @@ mod extend_doc_end;
 mod select_inside;
 mod shift_and_extend;
 mod selection_to_selection2d;
+mod selection_to_selection2d_utf16;
+mod new_from_utf16_positions;
+mod coordinates;
@@ -0,0 +1,21 @@
+use ropey::Rope;
+use crate::position::Position;
+use crate::selection::{Selection, CursorSemantics};
+
+#[test] fn bar_semantics(){
+    let text = Rope::from("a\u{1f980}bc\nx\u{1f980}y");
+    assert_eq!(Selection::new_from_components(1, 3, None, &text, CursorSemantics::Bar), Selection::new_from_utf16_positions(&Position::new(1, 0), &Position::new(4, 0), &text, CursorSemantics::Bar));
+    assert_eq!(Selection::new_from_components(8, 3, None, &text, CursorSemantics::Bar), Selection::new_from_utf16_positions(&Position::new(4, 1), &Position::new(4, 0), &text, CursorSemantics::Bar));
+}
+
+#[test] fn block_semantics(){
+    let text = Rope::from("a\u{1f980}bc\nx\u{1f980}y");
+    assert_eq!(Selection::new_from_components(2, 3, None, &text, CursorSemantics::Block), Selection::new_from_utf16_positions(&Position::new(3, 0), &Position::new(4, 0), &text, CursorSemantics::Block));
+}
+
+#[test] fn round_trips_with_selection_to_selection2d_utf16(){
+    let text = Rope::from("a\u{1f980}bc\nx\u{1f980}y");
+    let selection = Selection::new_from_components(1, 7, None, &text, CursorSemantics::Bar);
+    let selection2d = selection.selection_to_selection2d_utf16(&text, CursorSemantics::Bar);
+    assert_eq!(selection, Selection::new_from_utf16_positions(selection2d.anchor(), selection2d.head(), &text, CursorSemantics::Bar));
+}
@@ -0,0 +1,21 @@
+use ropey::Rope;
+use crate::position::Position;
+use crate::range::Range;
+use crate::selection::{Selection, CursorSemantics, Direction};
+use crate::selection2d::Selection2d;
+
+#[test] fn bar_semantics(){
+    let text = Rope::from("a\u{1f980}bc\nx\u{1f980}y");
+    //a[🦀b]c\nx🦀y
+    assert_eq!(Selection::new(Range::new(1, 3), Direction::Forward).selection_to_selection2d_utf16(&text, CursorSemantics::Bar), Selection2d::new(Position::new(1, 0), Position::new(4, 0)));
+    //a🦀b[c\nx🦀]y
+    assert_eq!(Selection::new(Range::new(3, 7), Direction::Forward).selection_to_selection2d_utf16(&text, CursorSemantics::Bar), Selection2d::new(Position::new(4, 0), Position::new(3, 1)));
+}
+
+#[test] fn block_semantics(){
+    let text = Rope::from("a\u{1f980}bc\nx\u{1f980}y");
+    //a🦀[b]c\nx🦀y
+    assert_eq!(Selection::new(Range::new(2, 3), Direction::Forward).selection_to_selection2d_utf16(&text, CursorSemantics::Block), Selection2d::new(Position::new(3, 0), Position::new(3, 0)));
+    //a[🦀b]c\nx🦀y
+    assert_eq!(Selection::new(Range::new(1, 3), Direction::Forward).selection_to_selection2d_utf16(&text, CursorSemantics::Block), Selection2d::new(Position::new(1, 0), Position::new(3, 0)));
+}
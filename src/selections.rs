@@ -1,7 +1,9 @@
 use ropey::Rope;
 use crate::range::Range;
-use crate::selection::{Selection, CursorSemantics, Direction, SelectionError};
+use crate::selection::{Selection, CursorSemantics, Direction, Movement, SelectionError};
+use crate::history::Edit;
 use crate::view::View;
+use crate::json::Json;
 
 
 
@@ -218,8 +220,6 @@ impl Selections{
         }
     }
 
-                //TODO: pub fn search_whole_text
-
     /// Intended to ease the use of Selection functions, when used over multiple selections, where the returned selections could be overlapping.
     pub fn move_cursor_potentially_overlapping<F>(&self, text: &Rope, semantics: CursorSemantics, move_fn: F) -> Result<Self, SelectionsError>
         where F: Fn(&Selection, &Rope, CursorSemantics) -> Result<Selection, SelectionError>
@@ -341,4 +341,45 @@ impl Selections{
         }
         Ok(new_selections)
     }
+
+    /// Returns [`Selections`] moved to account for `edits` made to the text since these [`Selections`] were current,
+    /// with each [`Selection`] mapped across `edits`, in order.
+    /// Extended selections keep their extent, and selections collapsed by a deletion become cursors at the deletion point.
+    /// `text` is the text after `edits`.
+    #[must_use] pub fn map_edits(&self, edits: &[Edit], text: &Rope, semantics: CursorSemantics) -> Self{
+        let mut new_selections = Vec::with_capacity(self.count());
+        for selection in self.iter(){
+            let (mut start, mut end) = (selection.range.start, selection.range.end);
+            for edit in edits{
+                start = edit.map(start);
+                end = edit.map(end);
+            }
+            let new_selection = if selection.is_extended(semantics) && end.saturating_sub(start) > usize::from(semantics == CursorSemantics::Block){
+                let mut new_selection = selection.clone();
+                new_selection.range = Range::new(start, end);
+                new_selection
+            }else{
+                // non extended, or collapsed by a deletion. keep as a cursor at start
+                selection.put_cursor(start, text, Movement::Move, semantics, true).unwrap_or_else(|_| selection.clone())
+            };
+            new_selections.push(new_selection);
+        }
+        Selections::new(new_selections, self.primary_selection_index, text, semantics)
+    }
+
+    /// Returns a [`Json`] representation of [`Selections`], as stored in an undo file.
+    #[must_use] pub fn to_json(&self) -> Json{
+        Json::Object(vec![
+            ("selections".to_string(), Json::Array(self.selections.iter().map(Selection::to_json).collect())),
+            ("primary".to_string(), Json::from(self.primary_selection_index)),
+        ])
+    }
+    /// Returns the [`Selections`] represented by `json`, if `json` is valid output of [`Selections::to_json`].
+    #[must_use] pub fn from_json(json: &Json) -> Option<Self>{
+        let Json::Array(selections) = json.get("selections")? else{return None;};
+        let selections = selections.iter().map(Selection::from_json).collect::<Option<Vec<Selection>>>()?;
+        let primary_selection_index = json.get("primary")?.as_usize()?;
+        if primary_selection_index >= selections.len(){return None;}
+        Some(Self{selections, primary_selection_index})
+    }
 }
@@ -0,0 +1,199 @@
+//! A server loop that serves [`protocol`](crate::protocol) requests from frontends over stdin/stdout, or a Unix socket.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+#[cfg(unix)] use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)] use std::path::Path;
+#[cfg(unix)] use std::sync::{Arc, Mutex};
+#[cfg(unix)] use std::thread;
+use crate::document::Document;
+use crate::editor::{Client, Editor};
+use crate::id::ClientID;
+use crate::protocol::{Request, Response};
+use crate::selections::Selections;
+use crate::utilities::save::SaveOutcome;
+
+
+
+/// Owns an [`Editor`], and translates [`Request`]s into [`Editor`] calls.
+#[derive(Default)]
+pub struct Server{
+    editor: Editor,
+    /// each client's selections from before its current run of incremental searches, if any
+    selections_before_search: HashMap<ClientID, Selections>,
+}
+impl Server{
+    #[must_use] pub fn new(editor: Editor) -> Self{
+        Self{editor, selections_before_search: HashMap::new()}
+    }
+    #[must_use] pub fn editor(&self) -> &Editor{
+        &self.editor
+    }
+
+    /// Handles a single [`Request`].
+    pub fn handle(&mut self, request: Request) -> Response{
+        // any request other than another incremental search ends the current run of incremental searches
+        match &request{
+            Request::Action{action, ..} if action.is_incremental() => {}
+            Request::Action{client, ..}
+            | Request::Disconnect{client}
+            | Request::OpenDocument{client, ..}
+            | Request::CloseDocument{client} => {self.selections_before_search.remove(client);}
+            _ => {}
+        }
+
+        match request{
+            Request::Connect{semantics} => Response::Client(self.editor.connect_client(semantics)),
+            Request::Disconnect{client} => {
+                if self.editor.client(client).is_none(){return Response::Error("InvalidClient".to_string());}
+                self.editor.disconnect_client(client);
+                Response::Ok
+            }
+            Request::OpenDocument{client, path} => {
+                match self.editor.open_document(&path, client){
+                    Ok(()) => Response::Ok,
+                    Err(e) => Response::Error(e.to_string())
+                }
+            }
+            Request::CloseDocument{client} => {
+                if self.editor.client(client).is_none(){return Response::Error("InvalidClient".to_string());}
+                self.editor.close_document(client);
+                Response::Ok
+            }
+            Request::Save{client} => {
+                match self.editor.save(client){
+                    Ok(SaveOutcome::Saved) => Response::Ok,
+                    Ok(SaveOutcome::UndoHistoryNotSaved(e)) => Response::Warning(format!("could not save undo history: {e}")),
+                    Err(e) => Response::Error(e.to_string())
+                }
+            }
+            Request::SetViewSize{client, width, height} => {
+                match self.editor.client_mut(client){
+                    Some(client) => {client.view.set_size(width, height); Response::Ok}
+                    None => Response::Error("InvalidClient".to_string())
+                }
+            }
+            Request::Action{client, action} => {
+                let Some(client_state) = self.editor.client(client) else{return Response::Error("InvalidClient".to_string());};
+                let semantics = client_state.semantics;
+                let current_selections = client_state.selections.clone();
+                let selections_before_search = if action.is_incremental(){
+                    self.selections_before_search.entry(client).or_insert(current_selections).clone()
+                }else{current_selections};
+                match self.editor.apply_with_clipboard(client, |document, clipboard| action.apply(document, clipboard, &selections_before_search, semantics)){
+                    Ok(()) => Response::Ok,
+                    Err(e) => Response::Error(format!("{e:?}"))
+                }
+            }
+            Request::ViewText{client} => self.query(client, |client, document| Response::Text(client.view.text(&document.text))),
+            Request::ViewSelections{client} => self.query(client, |client, document| Response::Selections(client.view.selections(&client.selections, &document.text))),
+            Request::ViewLineNumbers{client} => self.query(client, |client, document| Response::Text(client.view.line_numbers(&document.text))),
+            Request::ViewPrimaryCursorPosition{client} => self.query(client, |client, document| Response::Position(client.view.primary_cursor_position(&document.text, &client.selections, client.semantics))),
+            Request::ViewUndoTree{client} => self.query(client, |_, document| Response::UndoTree(document.undo_tree.clone())),
+            Request::ViewIndentStyle{client} => self.query(client, |_, document| Response::IndentStyle(document.indent_style())),
+            Request::ViewCompletions{client} => self.query(client, |client, document| Response::Completions(document.word_index.candidates_at(&client.selections, &document.text, client.semantics))),
+        }
+    }
+
+    fn query<F>(&self, client_id: ClientID, query: F) -> Response
+        where F: FnOnce(&Client, &Document) -> Response
+    {
+        let Some(client) = self.editor.client(client_id) else{return Response::Error("InvalidClient".to_string());};
+        let Some(document) = self.editor.document(client_id) else{return Response::Error("NoOpenDocument".to_string());};
+        query(client, document)
+    }
+
+    /// Handles a single line of JSON, and returns the single line of JSON to respond with(without trailing newline).
+    pub fn handle_line(&mut self, line: &str) -> String{
+        let response = match Request::parse(line){
+            Ok(request) => self.handle(request),
+            Err(e) => Response::Error(format!("{e:?}"))
+        };
+        response.to_json().to_string()
+    }
+
+    /// Handles a single line of JSON as [`Server::handle_line`] does, keeping `clients` to the clients connected, and not since disconnected, by it.
+    #[cfg(unix)]
+    fn handle_connection_line(&mut self, line: &str, clients: &mut Vec<ClientID>) -> String{
+        let response = match Request::parse(line){
+            Ok(request) => {
+                let disconnecting = if let Request::Disconnect{client} = &request{Some(*client)}else{None};
+                let response = self.handle(request);
+                match (&response, disconnecting){
+                    (Response::Client(client), _) => clients.push(*client),
+                    (Response::Ok, Some(disconnected)) => clients.retain(|client| *client != disconnected),
+                    _ => {}
+                }
+                response
+            }
+            Err(e) => Response::Error(format!("{e:?}"))
+        };
+        response.to_json().to_string()
+    }
+
+    /// Serves requests read line by line from `reader`, writing one response line per request to `writer`, until `reader` is exhausted.
+    /// Blank lines are ignored.
+    pub fn run<R: BufRead, W: Write>(&mut self, reader: R, mut writer: W) -> io::Result<()>{
+        for line in reader.lines(){
+            let line = line?;
+            if line.trim().is_empty(){continue;}
+            writeln!(writer, "{}", self.handle_line(&line))?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Serves requests over stdin/stdout.
+    pub fn run_stdio(&mut self) -> io::Result<()>{
+        self.run(io::stdin().lock(), io::stdout().lock())
+    }
+
+    /// Listens on a Unix socket at `path`, serving each connection on its own thread. Connections share this [`Server`],
+    /// so clients connected through different sockets can work on the same documents.
+    /// An I/O error on one connection only ends that connection, and is passed to `on_connection_error`.
+    #[cfg(unix)]
+    pub fn run_unix_socket<F>(self, path: &Path, on_connection_error: F) -> io::Result<()>
+        where F: Fn(io::Error) + Send + Sync + 'static
+    {
+        let listener = UnixListener::bind(path)?;
+        let server = Arc::new(Mutex::new(self));
+        let on_connection_error = Arc::new(on_connection_error);
+        for stream in listener.incoming(){
+            let stream = stream?;
+            let server = Arc::clone(&server);
+            let on_connection_error = Arc::clone(&on_connection_error);
+            thread::spawn(move || {
+                if let Err(e) = serve_connection(&server, stream){
+                    on_connection_error(e);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Serves requests read line by line from `stream`, writing each response back to it, until the other end closes it.
+/// Clients connected through `stream` and still connected when it ends are disconnected, so their documents are closed.
+#[cfg(unix)]
+pub(crate) fn serve_connection(server: &Mutex<Server>, stream: UnixStream) -> io::Result<()>{
+    let mut clients = Vec::new();
+    let result = serve_lines(server, stream, &mut clients);
+    let mut server = server.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    for client in clients{
+        server.selections_before_search.remove(&client);
+        server.editor.disconnect_client(client);
+    }
+    result
+}
+#[cfg(unix)]
+fn serve_lines(server: &Mutex<Server>, stream: UnixStream, clients: &mut Vec<ClientID>) -> io::Result<()>{
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines(){
+        let line = line?;
+        if line.trim().is_empty(){continue;}
+        let response = server.lock().unwrap_or_else(std::sync::PoisonError::into_inner).handle_connection_line(&line, clients);
+        writeln!(writer, "{response}")?;
+        writer.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use crate::server::Server;
+
+#[test] fn responds_with_error_for_unparseable_request(){
+    let mut server = Server::default();
+    assert_eq!(r#"{"ok":false,"error":"UnknownRequest(\"idk\")"}"#, server.handle_line(r#"{"request": "idk"}"#));
+    assert_eq!(r#"{"ok":false,"error":"InvalidJson(UnexpectedEnd)"}"#, server.handle_line("{"));
+    assert_eq!(r#"{"ok":false,"error":"InvalidJson(TooDeeplyNested(128))"}"#, server.handle_line(&"[".repeat(200_000)));
+}
+
+#[test] fn responds_with_error_for_invalid_client(){
+    let mut server = Server::default();
+    assert_eq!(r#"{"ok":false,"error":"InvalidClient"}"#, server.handle_line(r#"{"request": "move_cursor_right", "client": 0}"#));
+    assert_eq!(r#"{"ok":false,"error":"InvalidClient"}"#, server.handle_line(r#"{"request": "view_text", "client": 0}"#));
+}
+
+#[test] fn responds_with_error_without_open_document(){
+    let mut server = Server::default();
+    assert_eq!(r#"{"ok":true,"client":0}"#, server.handle_line(r#"{"request": "connect"}"#));
+    assert_eq!(r#"{"ok":false,"error":"NoOpenDocument"}"#, server.handle_line(r#"{"request": "move_cursor_right", "client": 0}"#));
+    assert_eq!(r#"{"ok":false,"error":"NoOpenDocument"}"#, server.handle_line(r#"{"request": "view_line_numbers", "client": 0}"#));
+}
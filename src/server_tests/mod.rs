@@ -0,0 +1,22 @@
+mod run;
+mod handle_line;
+#[cfg(unix)] mod socket;
+
+use crate::server::Server;
+
+/// Drives a [`Server`] in-process: each input line is served through [`Server::run`], exactly as if it had been read from a frontend,
+/// and the response lines written back are returned.
+fn serve(server: &mut Server, lines: &[&str]) -> Vec<String>{
+    let mut input = lines.join("\n");
+    input.push('\n');
+    let mut output = Vec::new();
+    server.run(input.as_bytes(), &mut output).unwrap();
+    String::from_utf8(output).unwrap().lines().map(str::to_string).collect()
+}
+
+/// Writes `contents` to a file in the system temp dir, unique to the calling test, and returns its path.
+fn temp_file(name: &str, contents: &str) -> std::path::PathBuf{
+    let path = std::env::temp_dir().join(format!("edit_core_server_tests_{}_{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
@@ -0,0 +1,203 @@
+use crate::server::Server;
+use super::{serve, temp_file};
+
+#[test] fn edits_and_queries_a_document(){
+    let path = temp_file("edits_and_queries_a_document", "idk\nsome\nshit\n");
+    let mut server = Server::default();
+    let responses = serve(&mut server, &[
+        r#"{"request": "connect", "semantics": "bar"}"#,
+        &format!(r#"{{"request": "open_document", "client": 0, "path": "{}"}}"#, path.display()),
+        r#"{"request": "set_view_size", "client": 0, "width": 3, "height": 2}"#,
+        r#"{"request": "insert_string", "client": 0, "string": "a", "use_hard_tab": false, "tab_width": 4}"#,
+        "",
+        r#"{"request": "extend_selection_right", "client": 0}"#,
+        r#"{"request": "view_text", "client": 0}"#,
+        r#"{"request": "view_selections", "client": 0}"#,
+        r#"{"request": "view_line_numbers", "client": 0}"#,
+        r#"{"request": "view_primary_cursor_position", "client": 0}"#,
+        r#"{"request": "undo", "client": 0}"#,
+        r#"{"request": "view_text", "client": 0}"#,
+        r#"{"request": "undo", "client": 0}"#,
+    ]);
+    assert_eq!(vec![
+        r#"{"ok":true,"client":0}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true,"text":"aid\nsom\n"}"#,
+        r#"{"ok":true,"selections":[{"anchor":{"x":1,"y":0},"head":{"x":2,"y":0}}]}"#,
+        r#"{"ok":true,"text":"1\n2"}"#,
+        r#"{"ok":true,"position":{"x":2,"y":0}}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true,"text":"idk\nsom\n"}"#,
+        r#"{"ok":false,"error":"DocumentError(NoChangesToUndo)"}"#,
+    ], responses);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn clients_share_documents_opened_at_the_same_path(){
+    let path = temp_file("clients_share_documents_opened_at_the_same_path", "idk\n");
+    let mut server = Server::default();
+    let open = |client: usize| format!(r#"{{"request": "open_document", "client": {client}, "path": "{}"}}"#, path.display());
+    let responses = serve(&mut server, &[
+        r#"{"request": "connect"}"#,
+        r#"{"request": "connect", "semantics": "block"}"#,
+        &open(0),
+        &open(1),
+        r#"{"request": "set_view_size", "client": 1, "width": 10, "height": 10}"#,
+        r#"{"request": "move_cursor_right", "client": 1}"#,
+        r#"{"request": "insert_string", "client": 0, "string": "so", "use_hard_tab": false, "tab_width": 4}"#,
+        r#"{"request": "view_text", "client": 1}"#,
+        r#"{"request": "view_selections", "client": 1}"#,
+        r#"{"request": "disconnect", "client": 0}"#,
+        r#"{"request": "view_text", "client": 0}"#,
+        r#"{"request": "view_text", "client": 1}"#,
+    ]);
+    assert_eq!(vec![
+        r#"{"ok":true,"client":0}"#,
+        r#"{"ok":true,"client":1}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true,"text":"soidk\n\n"}"#,
+        r#"{"ok":true,"selections":[{"anchor":{"x":3,"y":0},"head":{"x":4,"y":0}}]}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":false,"error":"InvalidClient"}"#,
+        r#"{"ok":true,"text":"soidk\n\n"}"#,
+    ], responses);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn incremental_search_searches_selections_from_before_the_run(){
+    let path = temp_file("incremental_search_searches_selections_from_before_the_run", "idk some idk\n");
+    let mut server = Server::default();
+    let responses = serve(&mut server, &[
+        r#"{"request": "connect"}"#,
+        &format!(r#"{{"request": "open_document", "client": 0, "path": "{}"}}"#, path.display()),
+        r#"{"request": "set_view_size", "client": 0, "width": 20, "height": 1}"#,
+        r#"{"request": "select_all", "client": 0}"#,
+        r#"{"request": "incremental_search_in_selection", "client": 0, "search_text": "i"}"#,
+        r#"{"request": "incremental_search_in_selection", "client": 0, "search_text": "idk"}"#,
+        r#"{"request": "view_selections", "client": 0}"#,
+    ]);
+    assert_eq!(r#"{"ok":true,"selections":[{"anchor":{"x":0,"y":0},"head":{"x":3,"y":0}},{"anchor":{"x":9,"y":0},"head":{"x":12,"y":0}}]}"#, responses[6]);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn saves_document(){
+    let path = temp_file("saves_document", "idk\n");
+    let mut server = Server::default();
+    let responses = serve(&mut server, &[
+        r#"{"request": "connect"}"#,
+        &format!(r#"{{"request": "open_document", "client": 0, "path": "{}"}}"#, path.display()),
+        r#"{"request": "insert_string", "client": 0, "string": "some ", "use_hard_tab": false, "tab_width": 4}"#,
+        r#"{"request": "save", "client": 0}"#,
+    ]);
+    assert_eq!(r#"{"ok":true}"#, responses[3]);
+    assert_eq!("some idk\n", std::fs::read_to_string(&path).unwrap());
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn warns_if_undo_history_is_not_saved(){
+    let path = temp_file("warns_if_undo_history_is_not_saved", "idk\n");
+    // a directory in place of the undo file makes writing it fail
+    std::fs::create_dir(crate::undo_file::path_for(&path)).unwrap();
+    let mut server = Server::default();
+    let responses = serve(&mut server, &[
+        r#"{"request": "connect"}"#,
+        &format!(r#"{{"request": "open_document", "client": 0, "path": "{}"}}"#, path.display()),
+        r#"{"request": "insert_string", "client": 0, "string": "some ", "use_hard_tab": false, "tab_width": 4}"#,
+        r#"{"request": "save", "client": 0}"#,
+    ]);
+    assert!(responses[3].starts_with(r#"{"ok":true,"warning":"could not save undo history: "#));
+    assert_eq!("some idk\n", std::fs::read_to_string(&path).unwrap());
+    let _ = std::fs::remove_dir(crate::undo_file::path_for(&path));
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn navigates_and_views_undo_tree(){
+    let path = temp_file("navigates_and_views_undo_tree", "idk\n");
+    let mut server = Server::default();
+    let responses = serve(&mut server, &[
+        r#"{"request": "connect"}"#,
+        &format!(r#"{{"request": "open_document", "client": 0, "path": "{}"}}"#, path.display()),
+        r#"{"request": "set_view_size", "client": 0, "width": 5, "height": 1}"#,
+        r#"{"request": "insert_string", "client": 0, "string": "a", "use_hard_tab": false, "tab_width": 4}"#,
+        r#"{"request": "undo", "client": 0}"#,
+        r#"{"request": "insert_string", "client": 0, "string": "b", "use_hard_tab": false, "tab_width": 4}"#,
+        r#"{"request": "view_undo_tree", "client": 0}"#,
+        r#"{"request": "undo_earlier", "client": 0}"#,
+        r#"{"request": "view_text", "client": 0}"#,
+        r#"{"request": "undo_later", "client": 0}"#,
+        r#"{"request": "view_text", "client": 0}"#,
+        r#"{"request": "next_undo_branch", "client": 0}"#,
+    ]);
+    assert_eq!(vec![
+        r#"{"ok":true,"client":0}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true,"undo_tree":{"current":2,"nodes":[{"parent":null,"children":[1,2],"active_child":2,"grouped_with_previous":false},{"parent":0,"children":[],"active_child":null,"grouped_with_previous":false},{"parent":0,"children":[],"active_child":null,"grouped_with_previous":false}]}}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true,"text":"aidk\n"}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true,"text":"bidk\n"}"#,
+        r#"{"ok":false,"error":"DocumentError(NoOtherUndoBranch)"}"#,
+    ], responses);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn views_and_converts_indent_style(){
+    let path = temp_file("views_and_converts_indent_style", "{\n\tidk\n}\n");
+    let mut server = Server::default();
+    let responses = serve(&mut server, &[
+        r#"{"request": "connect"}"#,
+        &format!(r#"{{"request": "open_document", "client": 0, "path": "{}"}}"#, path.display()),
+        r#"{"request": "set_view_size", "client": 0, "width": 10, "height": 3}"#,
+        r#"{"request": "view_indent_style", "client": 0}"#,
+        r#"{"request": "convert_indentation", "client": 0, "use_hard_tab": false, "tab_width": 2}"#,
+        r#"{"request": "view_text", "client": 0}"#,
+        r#"{"request": "view_indent_style", "client": 0}"#,
+    ]);
+    assert_eq!(vec![
+        r#"{"ok":true,"client":0}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true,"indent_style":{"use_hard_tab":true}}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true,"text":"{\n  idk\n}\n"}"#,
+        r#"{"ok":true,"indent_style":{"use_hard_tab":false,"tab_width":2}}"#,
+    ], responses);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test] fn views_and_applies_completions(){
+    let path = temp_file("views_and_applies_completions", "something\n");
+    let mut server = Server::default();
+    let responses = serve(&mut server, &[
+        r#"{"request": "connect"}"#,
+        &format!(r#"{{"request": "open_document", "client": 0, "path": "{}"}}"#, path.display()),
+        r#"{"request": "set_view_size", "client": 0, "width": 20, "height": 2}"#,
+        r#"{"request": "insert_string", "client": 0, "string": "so ", "use_hard_tab": false, "tab_width": 4}"#,
+        r#"{"request": "move_cursor_left", "client": 0}"#,
+        r#"{"request": "view_completions", "client": 0}"#,
+        r#"{"request": "complete_word", "client": 0, "completion": "something"}"#,
+        r#"{"request": "view_text", "client": 0}"#,
+    ]);
+    assert_eq!(vec![
+        r#"{"ok":true,"client":0}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true,"completions":[["something"]]}"#,
+        r#"{"ok":true}"#,
+        r#"{"ok":true,"text":"something something\n\n"}"#,
+    ], responses);
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,41 @@
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::sync::Mutex;
+use std::thread;
+use crate::server::{self, Server};
+
+#[test] fn serves_a_connection_until_it_closes(){
+    let server = Mutex::new(Server::default());
+    let (mut client, connection) = UnixStream::pair().unwrap();
+    let responses = thread::scope(|scope| {
+        let serving = scope.spawn(|| server::serve_connection(&server, connection));
+        client.write_all(b"{\"request\": \"connect\"}\n\n{\"request\": \"idk\"}\n").unwrap();
+        client.shutdown(std::net::Shutdown::Write).unwrap();
+        let responses: Vec<String> = BufReader::new(&client).lines().map(Result::unwrap).collect();
+        assert!(serving.join().unwrap().is_ok());
+        responses
+    });
+    assert_eq!(vec![
+        r#"{"ok":true,"client":0}"#,
+        r#"{"ok":false,"error":"UnknownRequest(\"idk\")"}"#,
+    ], responses);
+}
+
+#[test] fn disconnects_clients_of_a_closed_connection(){
+    let server = Mutex::new(Server::default());
+    let (mut client, connection) = UnixStream::pair().unwrap();
+    assert_eq!(r#"{"ok":true,"client":0}"#, server.lock().unwrap().handle_line(r#"{"request": "connect"}"#));
+    thread::scope(|scope| {
+        let serving = scope.spawn(|| server::serve_connection(&server, connection));
+        client.write_all(b"{\"request\": \"connect\"}\n{\"request\": \"connect\"}\n{\"request\": \"disconnect\", \"client\": 1}\n").unwrap();
+        client.shutdown(std::net::Shutdown::Write).unwrap();
+        let responses: Vec<String> = BufReader::new(&client).lines().map(Result::unwrap).collect();
+        assert_eq!(3, responses.len());
+        assert!(serving.join().unwrap().is_ok());
+    });
+    let server = server.into_inner().unwrap();
+    // client 0 was connected outside the closed connection, so it stays
+    assert!(server.editor().client(0).is_some());
+    assert!(server.editor().client(1).is_none());
+    assert!(server.editor().client(2).is_none());
+}
@@ -0,0 +1,63 @@
+//! Running shell commands over selected text.
+//!
+//! Commands are run by `sh -c`, with text passed on stdin. See [`utilities::pipe_selections`](crate::utilities::pipe_selections).
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+
+
+#[derive(Debug)]
+pub enum ShellError{
+    /// the command could not be run
+    Io(io::Error),
+    /// the command ran, but exited unsuccessfully. holds the command's stderr
+    CommandFailed(String),
+    /// the command's output was not valid UTF-8
+    InvalidOutput,
+}
+
+/// What is done with each selection's command output.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PipeMode{
+    /// replace the selected text with the command's output(like kakoune's `|`)
+    Replace,
+    /// insert the command's output before the selected text(like kakoune's `!`)
+    Insert,
+    /// keep only the selections the command exits successfully for(like kakoune's `$`)
+    Keep,
+}
+
+/// The result of a command that ran to completion.
+#[derive(Debug, PartialEq)]
+pub struct ShellOutput{
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Runs `command` with `sh -c`, writing `input` to its stdin, and waits for it to exit.
+/// Errors only if the command could not be run, or its output is not valid UTF-8. An unsuccessful exit is reported by [`ShellOutput::success`].
+pub fn run(command: &str, input: &str) -> Result<ShellOutput, ShellError>{
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(ShellError::Io)?;
+    // write stdin from another thread, so a command writing a lot of output before reading all input can't deadlock
+    let mut stdin = child.stdin.take().ok_or(ShellError::Io(io::Error::other("stdin not captured")))?;
+    let input = input.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));   // stdin dropped at thread end, so the command sees end of input
+    let output = child.wait_with_output().map_err(ShellError::Io)?;
+    // a command exiting without reading all of its input breaks the pipe. that is not a failure to run it
+    if let Ok(Err(e)) = writer.join(){
+        if e.kind() != io::ErrorKind::BrokenPipe{return Err(ShellError::Io(e));}
+    }
+    Ok(ShellOutput{
+        success: output.status.success(),
+        stdout: String::from_utf8(output.stdout).map_err(|_| ShellError::InvalidOutput)?,
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    })
+}
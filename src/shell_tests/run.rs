@@ -0,0 +1,19 @@
+use crate::shell::{self, ShellOutput};
+
+#[test] fn passes_input_on_stdin(){
+    assert_eq!(
+        ShellOutput{success: true, stdout: "IDK\nSOME😀\n".to_string(), stderr: String::new()},
+        shell::run("tr a-z A-Z", "idk\nsome😀\n").unwrap()
+    );
+}
+
+#[test] fn reports_unsuccessful_exit(){
+    let output = shell::run("echo idk >&2; exit 1", "").unwrap();
+    assert!(!output.success);
+    assert_eq!("idk\n", output.stderr);
+}
+
+#[test] fn does_not_fail_if_command_ignores_input(){
+    let input = "idk\n".repeat(100_000);
+    assert_eq!("some\n", shell::run("echo some", &input).unwrap().stdout);
+}
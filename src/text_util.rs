@@ -1,6 +1,24 @@
 use ropey::{Rope, RopeSlice};
-use unicode_segmentation::UnicodeSegmentation;
+use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete, UnicodeSegmentation};
 use crate::selection::{CursorSemantics, Selection};
+use crate::position::Position;
+use crate::document::IndentStyle;
+
+
+
+/// Pairs selected around by default: brackets, and quotes. Pairs whose chars are the same are treated as quotes, which do not nest.
+pub const DEFAULT_PAIRS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}'), ('<', '>'), ('"', '"'), ('\'', '\''), ('`', '`')];
+
+/// Coordinate systems an index into a text can be expressed in.
+/// [`Selection`]s store char indices, and can be converted to/from any of these.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Coordinates{
+    Char,
+    Grapheme,
+    Utf8Byte,
+    /// UTF-16 code units, as used by LSP
+    Utf16CodeUnit,
+}
 
 
 
@@ -27,7 +45,7 @@ use crate::selection::{CursorSemantics, Selection};
     //for (index, char) in line.chars().enumerate(){
     for (index, grapheme) in line.graphemes(true).enumerate(){
         //if char != ' '{return index;}
-        if grapheme != " "{return index;}
+        if grapheme != " " && grapheme != "\t"{return index;}
     }
 
     0
@@ -42,7 +60,8 @@ use crate::selection::{CursorSemantics, Selection};
     current_index.saturating_sub(1) //placeholder to handle ascii text. code will need to change to handle UTF-8
 }
 
-fn is_word_char(char: char) -> bool{
+/// Returns true if `char` is part of a word. (as opposed to whitespace or punctuation)
+#[must_use] pub fn is_word_char(char: char) -> bool{
     if char.is_alphabetic() || char.is_numeric()/* || char == '_'*/{
         return true;
     }
@@ -50,7 +69,8 @@ fn is_word_char(char: char) -> bool{
     false
 }
 
-fn is_whitespace(char: char) -> bool{
+/// Returns true if `char` is a space, tab, or newline.
+#[must_use] pub fn is_whitespace(char: char) -> bool{
     char == ' ' || char == '\t' || char == '\n'
 }
 
@@ -139,6 +159,167 @@ fn is_whitespace(char: char) -> bool{
     true
 }
 
+/// Returns true if `line` contains only whitespace. Blank lines separate paragraphs.
+#[must_use] pub fn is_blank_line(line: RopeSlice) -> bool{
+    line.chars().all(is_whitespace)
+}
+
+/// Returns the indentation style of `text`, or `None` if no line is indented.
+/// Hard tabs are detected if more lines start with a tab than with a space. Otherwise, the indent width is the most common
+/// change in indentation between consecutive non blank lines.
+#[must_use] pub fn indent_style(text: &Rope) -> Option<IndentStyle>{
+    let (mut tab_lines, mut space_lines) = (0, 0);
+    let mut width_counts = [0usize; 9];    //index is the width, up to 8
+    let mut previous_spaces = 0;
+    for line in text.lines().filter(|&line| !is_blank_line(line)){
+        if line.char(0) == '\t'{
+            tab_lines += 1;
+            continue;
+        }
+        let spaces = line.chars().take_while(|&char| char == ' ').count();
+        if spaces > 0{space_lines += 1;}
+        if let Some(count) = width_counts.get_mut(spaces.abs_diff(previous_spaces)){*count += 1;}
+        previous_spaces = spaces;
+    }
+
+    if tab_lines == 0 && space_lines == 0{return None;}
+    if tab_lines > space_lines{return Some(IndentStyle::HardTab);}
+    // ties go to the smaller width
+    let width = (1..width_counts.len()).rev().max_by_key(|&width| width_counts[width]).filter(|&width| width_counts[width] > 0);
+    Some(IndentStyle::Spaces(width.unwrap_or(previous_spaces.max(1))))
+}
+
+/// Returns the first and last line of the paragraph containing `line`.
+/// A paragraph is a block of non blank lines. If `line` is blank, returns the first and last line of the block of blank lines containing it.
+/// ```
+/// # use ropey::Rope;
+/// # use edit_core::text_util;
+///
+/// let text = Rope::from("idk\nsome\nshit\n\nsomething\nelse\n");
+/// assert_eq!((0, 2), text_util::paragraph_lines(1, &text));
+/// assert_eq!((3, 3), text_util::paragraph_lines(3, &text));
+/// assert_eq!((4, 5), text_util::paragraph_lines(5, &text));
+/// ```
+#[must_use] pub fn paragraph_lines(line: usize, text: &Rope) -> (usize, usize){
+    let blank = is_blank_line(text.line(line));
+    let mut first = line;
+    while first > 0 && is_blank_line(text.line(first - 1)) == blank{
+        first -= 1;
+    }
+    let mut last = line;
+    while last + 1 < text.len_lines() && is_blank_line(text.line(last + 1)) == blank{
+        last += 1;
+    }
+    (first, last)
+}
+
+/// Returns `start` and `end` extended over the spaces and tabs following `end`, or if there are none, the spaces and tabs preceding `start`.
+/// Used to select around text objects like words and sentences.
+#[must_use] pub fn include_adjacent_whitespace(start: usize, end: usize, text: &Rope) -> (usize, usize){
+    let is_space = |char: char| char == ' ' || char == '\t';
+    let mut new_end = end;
+    while new_end < text.len_chars() && is_space(text.char(new_end)){
+        new_end += 1;
+    }
+    if new_end != end{return (start, new_end);}
+    let mut new_start = start;
+    while new_start > 0 && is_space(text.char(new_start - 1)){
+        new_start -= 1;
+    }
+    (new_start, end)
+}
+
+/// Returns true if the char at `index` is escaped, by being preceded by an odd number of backslashes.
+/// ```
+/// # use ropey::Rope;
+/// # use edit_core::text_util;
+///
+/// let text = Rope::from(r#"\" \\" \\\""#);
+/// assert!(text_util::is_escaped(1, &text));
+/// assert!(!text_util::is_escaped(5, &text));
+/// assert!(text_util::is_escaped(10, &text));
+/// ```
+#[must_use] pub fn is_escaped(index: usize, text: &Rope) -> bool{
+    let mut backslashes = 0;
+    while backslashes < index && text.char(index - backslashes - 1) == '\\'{
+        backslashes += 1;
+    }
+    backslashes % 2 == 1
+}
+
+/// Returns the index of the `close` char matching the `open` char at `open_index`, skipping nested and escaped pairs.
+/// ```
+/// # use ropey::Rope;
+/// # use edit_core::text_util;
+///
+/// let text = Rope::from("(idk (some) \\) shit)");
+/// assert_eq!(Some(19), text_util::matching_close(0, '(', ')', &text));
+/// assert_eq!(Some(10), text_util::matching_close(5, '(', ')', &text));
+/// assert_eq!(None, text_util::matching_close(0, '(', ')', &Rope::from("(idk")));
+/// ```
+#[must_use] pub fn matching_close(open_index: usize, open: char, close: char, text: &Rope) -> Option<usize>{
+    let mut depth = 0;
+    for index in open_index + 1..text.len_chars(){
+        let char = text.char(index);
+        if char != open && char != close || is_escaped(index, text){continue;}
+        if char == close{
+            if depth == 0{return Some(index);}
+            depth -= 1;
+        }else{
+            depth += 1;
+        }
+    }
+    None
+}
+
+/// Returns the index of the `open` char matching the `close` char at `close_index`, skipping nested and escaped pairs.
+#[must_use] pub fn matching_open(close_index: usize, open: char, close: char, text: &Rope) -> Option<usize>{
+    let mut depth = 0;
+    for index in (0..close_index).rev(){
+        let char = text.char(index);
+        if char != open && char != close || is_escaped(index, text){continue;}
+        if char == open{
+            if depth == 0{return Some(index);}
+            depth -= 1;
+        }else{
+            depth += 1;
+        }
+    }
+    None
+}
+
+/// Returns the index of the char matching the pair char at `index`, if the char at `index` is part of one of `pairs`.
+/// Brackets skip nested and escaped pairs. Pairs whose chars are the same(quotes) are matched in order within the line.
+/// ```
+/// # use ropey::Rope;
+/// # use edit_core::text_util;
+///
+/// let text = Rope::from("(idk [some]) \"shit\"");
+/// assert_eq!(Some(11), text_util::matching_pair(0, text_util::DEFAULT_PAIRS, &text));
+/// assert_eq!(Some(5), text_util::matching_pair(10, text_util::DEFAULT_PAIRS, &text));
+/// assert_eq!(Some(13), text_util::matching_pair(18, text_util::DEFAULT_PAIRS, &text));
+/// assert_eq!(None, text_util::matching_pair(1, text_util::DEFAULT_PAIRS, &text));
+/// ```
+#[must_use] pub fn matching_pair(index: usize, pairs: &[(char, char)], text: &Rope) -> Option<usize>{
+    if index >= text.len_chars() || is_escaped(index, text){return None;}
+    let char = text.char(index);
+    for &(open, close) in pairs{
+        if open == close && char == open{
+            let line = text.char_to_line(index);
+            let line_start = text.line_to_char(line);
+            let line_end = line_start + text.line(line).len_chars();
+            let quotes: Vec<usize> = (line_start..line_end)
+                .filter(|&quote| text.char(quote) == open && !is_escaped(quote, text))
+                .collect();
+            let position = quotes.iter().position(|&quote| quote == index)?;
+            return quotes.get(position ^ 1).copied();   //partner of an even position is the next quote, and of an odd position the previous
+        }
+        if char == open{return matching_close(index, open, close, text);}
+        if char == close{return matching_open(index, open, close, text);}
+    }
+    None
+}
+
 /// Returns the grapheme distance to next multiple of user defined tab width.
 #[must_use] pub fn distance_to_next_multiple_of_tab_width(selection: &Selection, text: &Rope, semantics: CursorSemantics, tab_width: usize) -> usize{
     let next_tab_distance = offset_from_line_start(selection.cursor(text, semantics), text) % tab_width;//TAB_WIDTH;
@@ -152,6 +333,88 @@ fn is_whitespace(char: char) -> bool{
     }
 }
 
+/// Returns a char index converted to the specified [`Coordinates`].
+/// `char_index` saturates at text end.
+#[must_use] pub fn char_index_to(char_index: usize, coordinates: Coordinates, text: &Rope) -> usize{
+    let char_index = char_index.min(text.len_chars());
+    match coordinates{
+        Coordinates::Char => char_index,
+        Coordinates::Grapheme => {
+            let end = text.char_to_byte(char_index);
+            let (mut grapheme_index, mut byte_index) = (0, 0);
+            while byte_index < end{
+                byte_index = next_grapheme_boundary(byte_index, text);
+                grapheme_index += 1;
+            }
+            grapheme_index
+        }
+        Coordinates::Utf8Byte => text.char_to_byte(char_index),
+        Coordinates::Utf16CodeUnit => text.char_to_utf16_cu(char_index),
+    }
+}
+
+/// Returns a char index from an index in the specified [`Coordinates`].
+/// `index` saturates at text end. An index inside a multi-byte/multi-code-unit char resolves to the start of that char.
+#[must_use] pub fn char_index_from(index: usize, coordinates: Coordinates, text: &Rope) -> usize{
+    match coordinates{
+        Coordinates::Char => index.min(text.len_chars()),
+        Coordinates::Grapheme => {
+            let mut byte_index = 0;
+            for _ in 0..index{
+                if byte_index == text.len_bytes(){break;}
+                byte_index = next_grapheme_boundary(byte_index, text);
+            }
+            text.byte_to_char(byte_index)
+        }
+        Coordinates::Utf8Byte => text.byte_to_char(index.min(text.len_bytes())),
+        Coordinates::Utf16CodeUnit => text.utf16_cu_to_char(index.min(text.len_utf16_cu())),
+    }
+}
+
+/// Returns the byte index of the grapheme boundary after `byte_index`, or text end if there is none.
+/// Walks the rope's chunks, so the text is never copied.
+fn next_grapheme_boundary(byte_index: usize, text: &Rope) -> usize{
+    let (mut chunk, mut chunk_start, _, _) = text.chunk_at_byte(byte_index);
+    let mut cursor = GraphemeCursor::new(byte_index, text.len_bytes(), true);
+    loop{
+        match cursor.next_boundary(chunk, chunk_start){
+            Ok(Some(boundary)) => return boundary,
+            Ok(None) => return text.len_bytes(),
+            Err(GraphemeIncomplete::NextChunk) => {
+                (chunk, chunk_start, _, _) = text.chunk_at_byte(chunk_start + chunk.len());
+            }
+            Err(GraphemeIncomplete::PreContext(context_end)) => {
+                let (context, context_start, _, _) = text.chunk_at_byte(context_end - 1);
+                cursor.provide_context(context, context_start);
+            }
+            // only returned by prev_boundary, or when given a chunk that doesn't hold the cursor
+            Err(_) => return text.len_bytes(),
+        }
+    }
+}
+
+/// Returns the line/column [`Position`] of a char index, with column counted in UTF-16 code units from line start.
+#[must_use] pub fn char_index_to_utf16_position(char_index: usize, text: &Rope) -> Position{
+    let char_index = char_index.min(text.len_chars());
+    let line = text.char_to_line(char_index);
+    let line_start = text.line_to_char(line);
+    Position::new(
+        text.char_to_utf16_cu(char_index).saturating_sub(text.char_to_utf16_cu(line_start)),
+        line
+    )
+}
+
+/// Returns the char index of a line/column [`Position`], with column counted in UTF-16 code units from line start.
+/// Line saturates at the last line, and column saturates at line end(excluding newline).
+#[must_use] pub fn char_index_from_utf16_position(position: &Position, text: &Rope) -> usize{
+    let line = position.y.min(text.len_lines().saturating_sub(1));
+    let line_start = text.line_to_char(line);
+    let line_end = line_start.saturating_add(line_width(text.line(line), false));
+    let line_start_utf16 = text.char_to_utf16_cu(line_start);
+    let line_end_utf16 = text.char_to_utf16_cu(line_end);
+    text.utf16_cu_to_char(line_start_utf16.saturating_add(position.x).min(line_end_utf16))
+}
+
 /// Returns the offset of cursor position from the start of a line of text.
 // TODO: maybe this really does belong in [Selection] in selection.rs?
 #[must_use] pub fn offset_from_line_start(point: usize, text: &Rope) -> usize{
@@ -159,6 +422,22 @@ fn is_whitespace(char: char) -> bool{
     point.saturating_sub(line_start)
 }
 
+/// Returns the display column of `index` within its line. Each grapheme takes one column, and tabs advance to the next multiple of `tab_width`.
+/// ```
+/// # use ropey::Rope;
+/// # use edit_core::text_util;
+///
+/// let text = Rope::from("idk\n\te\u{301}x\n");
+/// assert_eq!(2, text_util::display_column(2, 4, &text));
+/// assert_eq!(4, text_util::display_column(5, 4, &text));
+/// assert_eq!(5, text_util::display_column(7, 4, &text));
+/// ```
+#[must_use] pub fn display_column(index: usize, tab_width: usize, text: &Rope) -> usize{
+    let line_start = text.line_to_char(text.char_to_line(index));
+    text.slice(line_start..index).to_string().graphemes(true)
+        .fold(0, |column, grapheme| if grapheme == "\t"{(column / tab_width.max(1) + 1) * tab_width.max(1)}else{column + 1})
+}
+
 // /// Returns the start index of the first matching pattern inside a text if one exists, or None
 // // AI gen code. verify behavior and clarify code intent at some later point.
 // #[must_use] pub fn naive_search(text: &str, pattern: &str) -> Option<usize> {
@@ -0,0 +1,47 @@
+use ropey::Rope;
+use crate::text_util::{self, Coordinates};
+
+// a é 🦀 e◌́ \n x
+//chars:    0 1 2 3 4 5 6 (7)
+//graphemes 0 1 2 3 . 4 5 (6)
+//bytes:    0 1 3 7 8 10 11 (12)
+//utf16:    0 1 2 4 5 6 7 (8)
+#[test] fn char_index_from(){
+    let text = Rope::from("a\u{e9}\u{1f980}e\u{301}\nx");
+    assert_eq!(3, text_util::char_index_from(3, Coordinates::Char, &text));
+    assert_eq!(5, text_util::char_index_from(4, Coordinates::Grapheme, &text));
+    assert_eq!(7, text_util::char_index_from(6, Coordinates::Grapheme, &text));
+    assert_eq!(3, text_util::char_index_from(7, Coordinates::Utf8Byte, &text));
+    assert_eq!(4, text_util::char_index_from(8, Coordinates::Utf8Byte, &text));
+    assert_eq!(3, text_util::char_index_from(4, Coordinates::Utf16CodeUnit, &text));
+    assert_eq!(7, text_util::char_index_from(8, Coordinates::Utf16CodeUnit, &text));
+}
+
+#[test] fn index_inside_char_resolves_to_char_start(){
+    let text = Rope::from("a\u{e9}\u{1f980}e\u{301}\nx");
+    assert_eq!(1, text_util::char_index_from(2, Coordinates::Utf8Byte, &text));   //inside é
+    assert_eq!(2, text_util::char_index_from(3, Coordinates::Utf16CodeUnit, &text));  //inside 🦀 surrogate pair
+}
+
+#[test] fn saturates_at_text_end(){
+    let text = Rope::from("a\u{e9}\u{1f980}e\u{301}\nx");
+    assert_eq!(7, text_util::char_index_from(100, Coordinates::Char, &text));
+    assert_eq!(7, text_util::char_index_from(100, Coordinates::Grapheme, &text));
+    assert_eq!(7, text_util::char_index_from(100, Coordinates::Utf8Byte, &text));
+    assert_eq!(7, text_util::char_index_from(100, Coordinates::Utf16CodeUnit, &text));
+}
+
+#[test] fn walks_graphemes_across_chunk_boundaries(){
+    use unicode_segmentation::UnicodeSegmentation;
+    let string = "ab\u{1f1fa}\u{1f1f8}e\u{301}\r\n".repeat(200);
+    let text = Rope::from(string.as_str());
+    assert!(text.chunks().count() > 1);
+    let mut expected = 0;
+    for (grapheme_index, grapheme) in string.graphemes(true).enumerate(){
+        if grapheme_index % 7 == 0{
+            assert_eq!(expected, text_util::char_index_from(grapheme_index, Coordinates::Grapheme, &text));
+        }
+        expected += grapheme.chars().count();
+    }
+    assert_eq!(text.len_chars(), text_util::char_index_from(string.graphemes(true).count(), Coordinates::Grapheme, &text));
+}
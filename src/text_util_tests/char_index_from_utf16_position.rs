@@ -0,0 +1,22 @@
+use ropey::Rope;
+use crate::text_util;
+use crate::position::Position;
+
+#[test] fn char_index_from_utf16_position(){
+    let text = Rope::from("a\u{e9}\u{1f980}e\u{301}\nx\u{1f980}y");
+    assert_eq!(0, text_util::char_index_from_utf16_position(&Position::new(0, 0), &text));
+    assert_eq!(3, text_util::char_index_from_utf16_position(&Position::new(4, 0), &text));
+    assert_eq!(6, text_util::char_index_from_utf16_position(&Position::new(0, 1), &text));
+    assert_eq!(8, text_util::char_index_from_utf16_position(&Position::new(3, 1), &text));
+}
+
+#[test] fn column_saturates_at_line_end(){
+    let text = Rope::from("a\u{e9}\u{1f980}e\u{301}\nx\u{1f980}y");
+    assert_eq!(5, text_util::char_index_from_utf16_position(&Position::new(100, 0), &text));
+    assert_eq!(9, text_util::char_index_from_utf16_position(&Position::new(100, 1), &text));
+}
+
+#[test] fn line_saturates_at_last_line(){
+    let text = Rope::from("a\u{e9}\u{1f980}e\u{301}\nx\u{1f980}y");
+    assert_eq!(6, text_util::char_index_from_utf16_position(&Position::new(0, 100), &text));
+}
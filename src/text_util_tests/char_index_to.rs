@@ -0,0 +1,37 @@
+use ropey::Rope;
+use crate::text_util::{self, Coordinates};
+
+// a é 🦀 e◌́ \n x
+//chars:    0 1 2 3 4 5 6 (7)
+//graphemes 0 1 2 3 . 4 5 (6)
+//bytes:    0 1 3 7 8 10 11 (12)
+//utf16:    0 1 2 4 5 6 7 (8)
+#[test] fn char_index_to(){
+    let text = Rope::from("a\u{e9}\u{1f980}e\u{301}\nx");
+    assert_eq!(3, text_util::char_index_to(3, Coordinates::Char, &text));
+    assert_eq!(3, text_util::char_index_to(3, Coordinates::Grapheme, &text));
+    assert_eq!(4, text_util::char_index_to(5, Coordinates::Grapheme, &text));
+    assert_eq!(6, text_util::char_index_to(7, Coordinates::Grapheme, &text));
+    assert_eq!(3, text_util::char_index_to(2, Coordinates::Utf8Byte, &text));
+    assert_eq!(10, text_util::char_index_to(5, Coordinates::Utf8Byte, &text));
+    assert_eq!(4, text_util::char_index_to(3, Coordinates::Utf16CodeUnit, &text));
+    assert_eq!(8, text_util::char_index_to(7, Coordinates::Utf16CodeUnit, &text));
+}
+
+#[test] fn saturates_at_text_end(){
+    let text = Rope::from("a\u{e9}\u{1f980}e\u{301}\nx");
+    assert_eq!(7, text_util::char_index_to(100, Coordinates::Char, &text));
+    assert_eq!(12, text_util::char_index_to(100, Coordinates::Utf8Byte, &text));
+    assert_eq!(8, text_util::char_index_to(100, Coordinates::Utf16CodeUnit, &text));
+}
+
+#[test] fn counts_graphemes_across_chunk_boundaries(){
+    use unicode_segmentation::UnicodeSegmentation;
+    let string = "ab\u{1f1fa}\u{1f1f8}e\u{301}\r\n".repeat(200);
+    let text = Rope::from(string.as_str());
+    assert!(text.chunks().count() > 1);
+    for char_index in (0..=text.len_chars()).step_by(7){
+        let expected = text.slice(..char_index).to_string().graphemes(true).count();
+        assert_eq!(expected, text_util::char_index_to(char_index, Coordinates::Grapheme, &text));
+    }
+}
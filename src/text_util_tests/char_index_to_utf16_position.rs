@@ -0,0 +1,12 @@
+use ropey::Rope;
+use crate::text_util;
+use crate::position::Position;
+
+#[test] fn char_index_to_utf16_position(){
+    let text = Rope::from("a\u{e9}\u{1f980}e\u{301}\nx\u{1f980}y");
+    assert_eq!(Position::new(0, 0), text_util::char_index_to_utf16_position(0, &text));
+    assert_eq!(Position::new(4, 0), text_util::char_index_to_utf16_position(3, &text));
+    assert_eq!(Position::new(6, 0), text_util::char_index_to_utf16_position(5, &text));
+    assert_eq!(Position::new(0, 1), text_util::char_index_to_utf16_position(6, &text));
+    assert_eq!(Position::new(3, 1), text_util::char_index_to_utf16_position(8, &text));
+}
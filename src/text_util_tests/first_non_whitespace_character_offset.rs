@@ -15,3 +15,8 @@ use crate::text_util;
     let text = Rope::from("   ");
     assert!(text_util::first_non_whitespace_character_offset(text.slice(..)) == 0);
 }
+
+#[test] fn with_leading_tabs(){
+    let text = Rope::from("\t idk\n");
+    assert!(text_util::first_non_whitespace_character_offset(text.slice(..)) == 2);
+}
@@ -0,0 +1,13 @@
+use crate::text_util;
+use ropey::Rope;
+
+#[test] fn prefers_following_whitespace(){
+    let text = Rope::from("idk some \tshit\n");
+    assert_eq!((4, 10), text_util::include_adjacent_whitespace(4, 8, &text));
+}
+
+#[test] fn uses_preceding_whitespace_if_none_follows(){
+    let text = Rope::from("idk  some\n");
+    assert_eq!((3, 9), text_util::include_adjacent_whitespace(5, 9, &text));
+    assert_eq!((0, 3), text_util::include_adjacent_whitespace(0, 3, &Rope::from("idk\n")));
+}
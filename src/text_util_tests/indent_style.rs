@@ -0,0 +1,28 @@
+use ropey::Rope;
+use crate::text_util;
+use crate::document::IndentStyle;
+
+#[test] fn with_soft_tabs(){
+    let text = Rope::from("idk{\n    some{\n        shit\n    }\n}\n");
+    assert_eq!(Some(IndentStyle::Spaces(4)), text_util::indent_style(&text));
+}
+
+#[test] fn with_hard_tabs(){
+    let text = Rope::from("idk{\n\tsome{\n\t\tshit\n\t}\n}\n");
+    assert_eq!(Some(IndentStyle::HardTab), text_util::indent_style(&text));
+}
+
+#[test] fn with_mixed_indentation_goes_with_most_lines(){
+    let text = Rope::from("idk\n\tsome\n  shit\n  else\n");
+    assert_eq!(Some(IndentStyle::Spaces(2)), text_util::indent_style(&text));
+}
+
+#[test] fn ignores_blank_lines(){
+    let text = Rope::from("idk\n   \n  some\n\n  shit\n");
+    assert_eq!(Some(IndentStyle::Spaces(2)), text_util::indent_style(&text));
+}
+
+#[test] fn without_indentation(){
+    assert_eq!(None, text_util::indent_style(&Rope::from("idk\nsome\n")));
+    assert_eq!(None, text_util::indent_style(&Rope::from("")));
+}
@@ -0,0 +1,12 @@
+use crate::text_util;
+use ropey::RopeSlice;
+
+#[test] fn with_line_containing_only_whitespace(){
+    assert!(text_util::is_blank_line(RopeSlice::from("\n")));
+    assert!(text_util::is_blank_line(RopeSlice::from(" \t\n")));
+    assert!(text_util::is_blank_line(RopeSlice::from("")));
+}
+
+#[test] fn with_line_containing_non_whitespace_chars(){
+    assert!(!text_util::is_blank_line(RopeSlice::from(" idk\n")));
+}
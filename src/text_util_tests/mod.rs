@@ -7,3 +7,11 @@ mod previous_word_boundary;
 mod slice_is_all_spaces;
 mod distance_to_next_multiple_of_tab_width;
 mod offset_from_line_start;
+mod char_index_to;
+mod char_index_from;
+mod char_index_to_utf16_position;
+mod char_index_from_utf16_position;
+mod is_blank_line;
+mod paragraph_lines;
+mod include_adjacent_whitespace;
+mod indent_style;
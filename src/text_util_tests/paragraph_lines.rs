@@ -0,0 +1,10 @@
+use crate::text_util;
+use ropey::Rope;
+
+#[test] fn paragraph_lines(){
+    let text = Rope::from("idk\nsome\n\n  \nshit");
+    assert_eq!((0, 1), text_util::paragraph_lines(0, &text));
+    assert_eq!((0, 1), text_util::paragraph_lines(1, &text));
+    assert_eq!((2, 3), text_util::paragraph_lines(2, &text));   //blank lines include whitespace only lines
+    assert_eq!((4, 4), text_util::paragraph_lines(4, &text));
+}
@@ -0,0 +1,164 @@
+//! Persistent undo history, saved alongside the file it belongs to.
+//!
+//! The undo file for `dir/name` is `dir/.name.edit_core_undo`. It holds the undo tree as JSON, along with a format version,
+//! and a hash of the file contents at save time. History is only restored when both still match, so a file changed externally
+//! (or an undo file written by an incompatible version) is safely ignored. A restored tree's changes are also checked against
+//! the file contents, so a corrupt undo file can't leave undo/redo applying changes that don't fit the text.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use ropey::Rope;
+use crate::history::{ChangeSet, Operation, UndoTree};
+use crate::json::Json;
+use crate::selection::{CursorSemantics, Selection};
+
+
+
+#[derive(Debug, PartialEq)]
+pub enum UndoFileError{
+    /// the undo file could not be read(usually because there is none)
+    Unreadable,
+    /// the undo file does not hold a valid undo tree
+    Invalid,
+    /// the undo file was written for another [`UNDO_FILE_VERSION`]
+    OtherVersion,
+    /// the file was changed since its undo file was saved
+    ContentChanged,
+    /// the undo tree's changes do not fit the file contents
+    Inconsistent,
+}
+
+/// Version of the undo file format. Undo files with any other version are ignored.
+pub const UNDO_FILE_VERSION: usize = 3;
+
+/// Returns the path of the undo file for the file at `file_path`.
+/// ```
+/// # use std::path::{Path, PathBuf};
+/// # use edit_core::undo_file;
+///
+/// assert_eq!(PathBuf::from("/tmp/.idk.txt.edit_core_undo"), undo_file::path_for(Path::new("/tmp/idk.txt")));
+/// ```
+#[must_use] pub fn path_for(file_path: &Path) -> PathBuf{
+    let mut file_name = std::ffi::OsString::from(".");
+    file_name.push(file_path.file_name().unwrap_or_default());
+    file_name.push(".edit_core_undo");
+    file_path.with_file_name(file_name)
+}
+
+/// Returns a hash of `text`, stable across runs and platforms. (64 bit FNV-1a, as hex)
+#[must_use] pub fn content_hash(text: &Rope) -> String{
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+    let mut hash = FNV_OFFSET_BASIS;
+    for chunk in text.chunks(){
+        for byte in chunk.bytes(){
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+/// Writes `undo_tree` to the undo file for the file at `file_path`, keyed to `text`.
+pub fn save(file_path: &Path, text: &Rope, undo_tree: &UndoTree) -> io::Result<()>{
+    let json = Json::Object(vec![
+        ("version".to_string(), Json::from(UNDO_FILE_VERSION)),
+        ("content_hash".to_string(), Json::from(content_hash(text))),
+        ("undo_tree".to_string(), undo_tree.to_json()),
+    ]);
+    fs::write(path_for(file_path), json.to_string())
+}
+
+/// Returns the undo tree saved for the file at `file_path`, if an undo file exists, is valid,
+/// has the current [`UNDO_FILE_VERSION`], was saved for contents matching `text`, and every change in it fits `text`.
+pub fn load(file_path: &Path, text: &Rope) -> Result<UndoTree, UndoFileError>{
+    let contents = fs::read_to_string(path_for(file_path)).map_err(|_| UndoFileError::Unreadable)?;
+    let json = Json::parse(&contents).map_err(|_| UndoFileError::Invalid)?;
+    let version = json.get("version").and_then(Json::as_usize).ok_or(UndoFileError::Invalid)?;
+    if version != UNDO_FILE_VERSION{return Err(UndoFileError::OtherVersion);}
+    let hash = json.get("content_hash").and_then(Json::as_str).ok_or(UndoFileError::Invalid)?;
+    if hash != content_hash(text){return Err(UndoFileError::ContentChanged);}
+    let undo_tree = json.get("undo_tree").and_then(UndoTree::from_json).ok_or(UndoFileError::Invalid)?;
+    if !fits(&undo_tree, text){return Err(UndoFileError::Inconsistent);}
+    Ok(undo_tree)
+}
+
+/// Returns `true` if every change set in `undo_tree` can be undone/redone from `text`, which is the text at the current node.
+/// Each change set is replayed on a copy of the text, with the cursor semantics it was recorded with,
+/// checking its selections are in bounds, and the text it removes is there.
+fn fits(undo_tree: &UndoTree, text: &Rope) -> bool{
+    // walk back to the root, to get the text before any change
+    let mut root_text = text.clone();
+    let mut id = undo_tree.current();
+    while let Some(node) = undo_tree.node(id){
+        let (Some(change_set), Some(parent)) = (node.change_set(), node.parent()) else{break;};
+        if !revert(change_set, &mut root_text){return false;}
+        id = parent;
+    }
+    // then re-apply every branch from the root
+    let mut pending = vec![(0, root_text)];
+    while let Some((id, text)) = pending.pop(){
+        let Some(node) = undo_tree.node(id) else{return false;};
+        for &child in node.children(){
+            let mut child_text = text.clone();
+            let Some(change_set) = undo_tree.node(child).and_then(|child| child.change_set()) else{return false;};
+            if !apply(change_set, &mut child_text){return false;}
+            pending.push((child, child_text));
+        }
+    }
+    true
+}
+
+/// Applies `change_set` to `text`, as redo would. Returns `false` if it does not fit `text`.
+fn apply(change_set: &ChangeSet, text: &mut Rope) -> bool{
+    let semantics = change_set.semantics();
+    if !change_set.selections_before_changes().iter().all(|selection| in_bounds(selection, text, semantics)){return false;}
+    for change in change_set.changes(){
+        if !in_bounds(&change.selection_before_change(), text, semantics){return false;}
+        let Some((at, removed, inserted)) = text_edit(&change.operation(), &change.inverse(), &change.selection_before_change(), &change.selection_after_change()) else{return false;};
+        if !replace(text, at, &removed, &inserted){return false;}
+    }
+    change_set.clone().selections_after_changes().iter().all(|selection| in_bounds(selection, text, semantics))
+}
+/// Reverts `change_set` in `text`, as undo would. Returns `false` if it does not fit `text`.
+fn revert(change_set: &ChangeSet, text: &mut Rope) -> bool{
+    let semantics = change_set.semantics();
+    if !change_set.clone().selections_after_changes().iter().all(|selection| in_bounds(selection, text, semantics)){return false;}
+    for change in change_set.changes().iter().rev(){
+        if !in_bounds(&change.selection_after_change(), text, semantics){return false;}
+        let Some((at, removed, inserted)) = text_edit(&change.operation(), &change.inverse(), &change.selection_before_change(), &change.selection_after_change()) else{return false;};
+        if !replace(text, at, &inserted, &removed){return false;}
+    }
+    change_set.selections_before_changes().iter().all(|selection| in_bounds(selection, text, semantics))
+}
+
+/// Returns where a change's `operation` edits the text, with the text it removes and the text it inserts.
+/// Matches [`ChangeSet::edits`]. Returns `None` if `inverse` is not the inverse of `operation`.
+fn text_edit(operation: &Operation, inverse: &Operation, before: &Selection, after: &Selection) -> Option<(usize, String, String)>{
+    match (operation, inverse){
+        (Operation::Insert{inserted_text}, _) => Some((before.range.start, String::new(), inserted_text.clone())),
+        (Operation::Delete, Operation::Insert{inserted_text}) => Some((after.range.start, inserted_text.clone(), String::new())),
+        (Operation::Replace{replacement_text}, Operation::Replace{replacement_text: replaced_text}) => Some((before.range.start, replaced_text.clone(), replacement_text.clone())),
+        (Operation::NoOp, _) => Some((0, String::new(), String::new())),
+        _ => None
+    }
+}
+/// Replaces `removed` at `at` in `text` with `inserted`. Returns `false` if `text` does not hold `removed` at `at`.
+fn replace(text: &mut Rope, at: usize, removed: &str, inserted: &str) -> bool{
+    let Some(end) = at.checked_add(removed.chars().count()) else{return false;};
+    if end > text.len_chars() || text.slice(at..end) != removed{return false;}
+    text.remove(at..end);
+    text.insert(at, inserted);
+    true
+}
+/// Returns `true` if `selection` is within `text`, as [`Selection::assert_invariants`] requires.
+fn in_bounds(selection: &Selection, text: &Rope, semantics: CursorSemantics) -> bool{
+    let len = text.len_chars();
+    match semantics{
+        CursorSemantics::Bar => selection.range.end <= len,
+        // a block cursor can be one past the text end
+        CursorSemantics::Block => selection.range.start < selection.range.end
+            && selection.range.end <= if selection.is_extended(semantics){len}else{len.saturating_add(1)}
+    }
+}
@@ -0,0 +1,11 @@
+use ropey::Rope;
+use crate::undo_file::content_hash;
+
+#[test] fn hash_is_stable(){
+    assert_eq!("cbf29ce484222325", content_hash(&Rope::from("")));
+    assert_eq!("af63dc4c8601ec8c", content_hash(&Rope::from("a")));
+}
+
+#[test] fn hash_changes_with_content(){
+    assert_ne!(content_hash(&Rope::from("idk\nsome\nshit\n")), content_hash(&Rope::from("idk\nsome\nshit")));
+}
@@ -0,0 +1,109 @@
+use ropey::Rope;
+use crate::document::Document;
+use crate::history::UndoTree;
+use crate::selection::CursorSemantics;
+use crate::undo_file::{self, UndoFileError};
+use crate::utilities::{backspace, insert_string, save, undo, redo};
+use super::{temp_file, remove};
+
+#[test] fn open_restores_history_saved_with_file(){
+    let path = temp_file("open_restores_history_saved_with_file", "idk\n");
+    let semantics = CursorSemantics::Bar;
+    let mut doc = Document::open(&path, semantics).unwrap();
+    assert!(insert_string::document_impl(&mut doc, "some\n", false, 4, semantics).is_ok());
+    assert!(insert_string::document_impl(&mut doc, "shit\n", false, 4, semantics).is_ok());
+    assert!(undo::document_impl(&mut doc, semantics).is_ok());
+    assert!(save::document_impl(&mut doc).is_ok());
+
+    let mut reopened = Document::open(&path, semantics).unwrap();
+    assert_eq!(doc.undo_stack(), reopened.undo_stack());
+    assert_eq!(doc.redo_stack(), reopened.redo_stack());
+    assert_eq!(doc.undo_tree, reopened.undo_tree);
+    assert!(redo::document_impl(&mut reopened, semantics).is_ok());
+    assert_eq!(Rope::from("some\nshit\nidk\n"), reopened.text);
+    assert!(undo::document_impl(&mut reopened, semantics).is_ok());
+    assert!(undo::document_impl(&mut reopened, semantics).is_ok());
+    assert_eq!(Rope::from("idk\n"), reopened.text);
+    remove(&path);
+}
+
+#[test] fn open_restores_branched_history_with_block_semantics(){
+    let path = temp_file("open_restores_branched_history_with_block_semantics", "idk\n");
+    let semantics = CursorSemantics::Block;
+    let mut doc = Document::open(&path, semantics).unwrap();
+    assert!(insert_string::document_impl(&mut doc, "some\n", false, 4, semantics).is_ok());
+    assert!(undo::document_impl(&mut doc, semantics).is_ok());
+    assert!(insert_string::document_impl(&mut doc, "shit\n", false, 4, semantics).is_ok());
+    assert!(backspace::document_impl(&mut doc, 1, false, 4, semantics).is_ok());
+    assert!(save::document_impl(&mut doc).is_ok());
+
+    let reopened = Document::open(&path, semantics).unwrap();
+    assert_eq!(doc.undo_tree, reopened.undo_tree);
+    remove(&path);
+}
+
+#[test] fn history_is_discarded_if_undo_file_does_not_fit_file(){
+    let path = temp_file("history_is_discarded_if_undo_file_does_not_fit_file", "idk\n");
+    let semantics = CursorSemantics::Bar;
+    let mut doc = Document::open(&path, semantics).unwrap();
+    assert!(insert_string::document_impl(&mut doc, "some\n", false, 4, semantics).is_ok());
+    assert!(save::document_impl(&mut doc).is_ok());
+    // the content hash still matches, but the recorded insert no longer matches the text
+    let contents = std::fs::read_to_string(undo_file::path_for(&path)).unwrap();
+    std::fs::write(undo_file::path_for(&path), contents.replace(r#""some\n""#, r#""other\n""#)).unwrap();
+    assert_eq!(Err(UndoFileError::Inconsistent), undo_file::load(&path, &doc.text));
+
+    let mut reopened = Document::open(&path, semantics).unwrap();
+    assert_eq!(UndoTree::default(), reopened.undo_tree);
+    assert!(undo::document_impl(&mut reopened, semantics).is_err());
+    assert_eq!(Rope::from("some\nidk\n"), reopened.text);
+    remove(&path);
+}
+
+#[test] fn save_succeeds_if_undo_file_can_not_be_written(){
+    let path = temp_file("save_succeeds_if_undo_file_can_not_be_written", "idk\n");
+    let semantics = CursorSemantics::Bar;
+    let mut doc = Document::open(&path, semantics).unwrap();
+    assert!(insert_string::document_impl(&mut doc, "some\n", false, 4, semantics).is_ok());
+    // a directory in place of the undo file makes writing it fail
+    std::fs::create_dir(undo_file::path_for(&path)).unwrap();
+    assert!(matches!(save::document_impl(&mut doc), Ok(save::SaveOutcome::UndoHistoryNotSaved(_))));
+    assert_eq!("some\nidk\n", std::fs::read_to_string(&path).unwrap());
+    let _ = std::fs::remove_dir(undo_file::path_for(&path));
+    remove(&path);
+}
+
+#[test] fn history_is_discarded_if_file_changed_externally(){
+    let path = temp_file("history_is_discarded_if_file_changed_externally", "idk\n");
+    let semantics = CursorSemantics::Bar;
+    let mut doc = Document::open(&path, semantics).unwrap();
+    assert!(insert_string::document_impl(&mut doc, "some\n", false, 4, semantics).is_ok());
+    assert!(save::document_impl(&mut doc).is_ok());
+    std::fs::write(&path, "other\n").unwrap();
+
+    let reopened = Document::open(&path, semantics).unwrap();
+    assert!(reopened.undo_stack().is_empty());
+    assert!(reopened.redo_stack().is_empty());
+    remove(&path);
+}
+
+#[test] fn ignores_undo_file_with_other_version_or_invalid_contents(){
+    let path = temp_file("ignores_undo_file_with_other_version_or_invalid_contents", "idk\n");
+    let text = Rope::from("idk\n");
+    assert!(undo_file::save(&path, &text, &UndoTree::default()).is_ok());
+    assert_eq!(Ok(UndoTree::default()), undo_file::load(&path, &text));
+
+    let contents = std::fs::read_to_string(undo_file::path_for(&path)).unwrap();
+    std::fs::write(undo_file::path_for(&path), contents.replace(&format!(r#""version":{}"#, undo_file::UNDO_FILE_VERSION), r#""version":999"#)).unwrap();
+    assert_eq!(Err(UndoFileError::OtherVersion), undo_file::load(&path, &text));
+
+    std::fs::write(undo_file::path_for(&path), "idk").unwrap();
+    assert_eq!(Err(UndoFileError::Invalid), undo_file::load(&path, &text));
+    remove(&path);
+}
+
+#[test] fn errors_without_undo_file(){
+    let path = temp_file("errors_without_undo_file", "idk\n");
+    assert_eq!(Err(UndoFileError::Unreadable), undo_file::load(&path, &Rope::from("idk\n")));
+    remove(&path);
+}
@@ -0,0 +1,13 @@
+mod content_hash;
+mod load;
+
+/// Writes `contents` to a file in the system temp dir, unique to the calling test, and returns its path.
+fn temp_file(name: &str, contents: &str) -> std::path::PathBuf{
+    let path = std::env::temp_dir().join(format!("edit_core_undo_file_tests_{}_{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+fn remove(path: &std::path::Path){
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(crate::undo_file::path_for(path));
+}
@@ -1,24 +1,28 @@
 use crate::{
+    selection_history::SelectionChange,
     document::{Document, DocumentError},
     selections::{Selections, SelectionsError},
-    selection::{Selection, CursorSemantics, Direction},
+    selection::{Selection, CursorSemantics},
     range::Range,
     text_util
 };
 use ropey::Rope;
 
-pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
-    match selections_impl(&document.selections, &document.text, semantics){
-        Ok(new_selections) => {document.selections = new_selections;}
-        Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
-    }
+/// Adds `count` new selections above, or as many as there are lines for, if fewer.
+pub fn document_impl(document: &mut Document, count: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    if count == 0{return Err(DocumentError::InvalidInput);}
+    let new_selections = match selections_impl(&document.selections, count, &document.text, semantics){
+        Ok(new_selections) => new_selections,
+        Err(e) => {return Err(DocumentError::SelectionsError(e))}
+    };
+    document.set_selections(new_selections, SelectionChange::Jump);
     Ok(())
 }
 
 //TODO: add selection above/below fns don't work as expected when multiple selections on same line. only adds primary selection range above/below
 
-/// Adds a new [`Selection`] directly above the top-most [`Selection`], with the same start and end offsets from line start, if possible.
-fn selections_impl(selections: &Selections, text: &Rope, semantics: CursorSemantics) -> Result<Selections, SelectionsError>{
+/// Adds a new [`Selection`] on each of the `count` lines above the top-most [`Selection`](or as many as there are), with the same start and end offsets from line start, if possible.
+fn selections_impl(selections: &Selections, count: usize, text: &Rope, semantics: CursorSemantics) -> Result<Selections, SelectionsError>{
     assert!(selections.count() > 0);  //ensure at least one selection in selections
 
     let top_selection = selections.first();
@@ -32,18 +36,30 @@ fn selections_impl(selections: &Selections, text: &Rope, semantics: CursorSemant
     // using primary selection here, because that is the selection we want our added selection to emulate, if possible with the available text
     let start_offset = text_util::offset_from_line_start(selections.primary().range.start, text);
     let end_offset = start_offset.saturating_add(selections.primary().range.end.saturating_sub(selections.primary().range.start));  //start_offset + (end char index - start char index)
-    let line_above = top_selection_line.saturating_sub(1);
-    let line_start = text.line_to_char(line_above);
-    let line_text = text.line(line_above);
+    // the last line to add a selection on is `count` lines above, clamped to the text start
+    let last_line = top_selection_line.saturating_sub(count);
+    let mut new_selections: Vec<Selection> = (last_line..top_selection_line).map(|line| {
+        let (start, end) = range_on_line(line, start_offset, end_offset, selections.primary(), text, semantics);
+        Selection::new(Range::new(start, end), selections.primary().direction)
+    }).collect();
+    let primary_selection_index = selections.primary_selection_index.saturating_add(new_selections.len());
+    new_selections.extend(selections.selections.iter().cloned());
+    Ok(Selections{selections: new_selections, primary_selection_index})
+}
+
+/// Returns the start and end of the selection added on `line`, at `start_offset` and `end_offset` from line start, clamped to the line.
+fn range_on_line(line: usize, start_offset: usize, end_offset: usize, primary: &Selection, text: &Rope, semantics: CursorSemantics) -> (usize, usize){
+    let line_start = text.line_to_char(line);
+    let line_text = text.line(line);
     let line_width = text_util::line_width(line_text, false);
     let line_width_including_newline = text_util::line_width(line_text, true);
-    let (start, end) = if line_text.to_string().is_empty() || line_text == "\n"{    //should be impossible for the text in the line above first selection to be empty. is_empty() check is redundant here...
+    if line_text.to_string().is_empty() || line_text == "\n"{    //should be impossible for the text in the line above first selection to be empty. is_empty() check is redundant here...
         match semantics{
             CursorSemantics::Bar => (line_start, line_start),
             CursorSemantics::Block => (line_start, text_util::next_grapheme_index(line_start, text))
         }
     }
-    else if selections.primary().is_extended(semantics){
+    else if primary.is_extended(semantics){
         if start_offset < line_width{   //should we exclusively handle start_offset < line_width && end_offset < line_width as well?
             (line_start.saturating_add(start_offset), line_start.saturating_add(end_offset.min(line_width_including_newline))) //start offset already verified within line text bounds
         }
@@ -60,13 +76,6 @@ fn selections_impl(selections: &Selections, text: &Rope, semantics: CursorSemant
             CursorSemantics::Bar => (line_start.saturating_add(start_offset.min(line_width)), line_start.saturating_add(start_offset.min(line_width))),
             CursorSemantics::Block => (line_start.saturating_add(start_offset.min(line_width)), text_util::next_grapheme_index(line_start.saturating_add(start_offset.min(line_width)), text))
         }
-    };
-
-    match selections.primary().direction{
-        //Direction::Forward => Ok(self.push_front(Selection::new(start, end), false)),
-        Direction::Forward => Ok(selections.push_front(Selection::new(Range::new(start, end), Direction::Forward), false)),
-        //Direction::Backward => Ok(self.push_front(Selection::new(end, start), false))
-        Direction::Backward => Ok(selections.push_front(Selection::new(Range::new(start, end), Direction::Backward), false))
     }
 }
 
@@ -99,6 +108,9 @@ mod tests{
     //    assert!(add_selection_above::document_impl(&mut doc, semantics).is_err());
     //}
     fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        test_count(1, semantics, text, tuple_selections, primary, tuple_expected_selections, expected_primary);
+    }
+    fn test_count(count: usize, semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
         let text = Rope::from(text);
         let mut vec_selections = Vec::new();
         for tuple in tuple_selections{
@@ -108,7 +120,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        let result = add_selection_above::document_impl(&mut doc, semantics);
+        let result = add_selection_above::document_impl(&mut doc, count, semantics);
         assert!(!result.is_err());
         let mut vec_expected_selections = Vec::new();
         for tuple in tuple_expected_selections{
@@ -128,7 +140,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        assert!(add_selection_above::document_impl(&mut doc, semantics).is_err());
+        assert!(add_selection_above::document_impl(&mut doc, 1, semantics).is_err());
         assert!(!doc.is_modified());
     }
 
@@ -483,4 +495,10 @@ mod tests{
             //block
                 //selection direction forward
                 //selection direction backward
+
+    #[test] fn with_count(){
+        test_count(2, CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(10, 10, None)], 0, vec![(1, 1, None), (5, 5, None), (10, 10, None)], 2);
+        test_count(5, CursorSemantics::Block, "idk\nsome\nshit\n", vec![(10, 11, None)], 0, vec![(1, 2, None), (5, 6, None), (10, 11, None)], 2);  //stops at doc start
+        test_count(usize::MAX, CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(10, 10, None)], 0, vec![(1, 1, None), (5, 5, None), (10, 10, None)], 2);
+    }
 }
@@ -1,23 +1,27 @@
 use crate::{
+    selection_history::SelectionChange,
     document::{Document, DocumentError},
     selections::{Selections, SelectionsError},
-    selection::{Selection, CursorSemantics, Direction},
+    selection::{Selection, CursorSemantics},
     range::Range,
     text_util
 };
 use ropey::Rope;
 
-pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
-    match selections_impl(&document.selections, &document.text, semantics){
-        Ok(new_selections) => {document.selections = new_selections;}
-        Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
-    }
+/// Adds `count` new selections below, or as many as there are lines for, if fewer.
+pub fn document_impl(document: &mut Document, count: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    if count == 0{return Err(DocumentError::InvalidInput);}
+    let new_selections = match selections_impl(&document.selections, count, &document.text, semantics){
+        Ok(new_selections) => new_selections,
+        Err(e) => {return Err(DocumentError::SelectionsError(e))}
+    };
+    document.set_selections(new_selections, SelectionChange::Jump);
     Ok(())
 }
 
 // TODO: selection added below at text end is not rendering on last line(this is a frontend issue though)
-/// Adds a new [`Selection`] directly below bottom-most [`Selection`], with the same start and end offsets from line start, if possible.
-fn selections_impl(selections: &Selections, text: &Rope, semantics: CursorSemantics) -> Result<Selections, SelectionsError>{
+/// Adds a new [`Selection`] on each of the `count` lines below the bottom-most [`Selection`](or as many as there are), with the same start and end offsets from line start, if possible.
+fn selections_impl(selections: &Selections, count: usize, text: &Rope, semantics: CursorSemantics) -> Result<Selections, SelectionsError>{
     assert!(selections.count() > 0);  //ensure at least one selection in selections
 
     let bottom_selection = selections.last();
@@ -32,18 +36,29 @@ fn selections_impl(selections: &Selections, text: &Rope, semantics: CursorSemant
     // using primary selection here, because that is the selection we want our added selection to emulate, if possible with the available text
     let start_offset = text_util::offset_from_line_start(selections.primary().range.start, text);
     let end_offset = start_offset.saturating_add(selections.primary().range.end.saturating_sub(selections.primary().range.start));  //start_offset + (end char index - start char index)
-    let line_below = bottom_selection_line.saturating_add(1);
-    let line_start = text.line_to_char(line_below);
-    let line_text = text.line(line_below);
+    // the last line to add a selection on is `count` lines below, clamped to the text end
+    let last_line = bottom_selection_line.saturating_add(count).min(text.len_lines().saturating_sub(1));
+    let mut new_selections = selections.selections.clone();
+    new_selections.extend((bottom_selection_line.saturating_add(1)..=last_line).map(|line| {
+        let (start, end) = range_on_line(line, start_offset, end_offset, selections.primary(), text, semantics);
+        Selection::new(Range::new(start, end), selections.primary().direction)
+    }));
+    Ok(Selections{selections: new_selections, primary_selection_index: selections.primary_selection_index})
+}
+
+/// Returns the start and end of the selection added on `line`, at `start_offset` and `end_offset` from line start, clamped to the line.
+fn range_on_line(line: usize, start_offset: usize, end_offset: usize, primary: &Selection, text: &Rope, semantics: CursorSemantics) -> (usize, usize){
+    let line_start = text.line_to_char(line);
+    let line_text = text.line(line);
     let line_width = text_util::line_width(line_text, false);
     let line_width_including_newline = text_util::line_width(line_text, true);
-    let (start, end) = if line_text.to_string().is_empty() || line_text == "\n"{    //should be impossible for the text in the line above first selection to be empty. is_empty() check is redundant here...
+    if line_text.to_string().is_empty() || line_text == "\n"{    //should be impossible for the text in the line above first selection to be empty. is_empty() check is redundant here...
         match semantics{
             CursorSemantics::Bar => (line_start, line_start),
             CursorSemantics::Block => (line_start, text_util::next_grapheme_index(line_start, text))
         }
     }
-    else if selections.primary().is_extended(semantics){
+    else if primary.is_extended(semantics){
         if start_offset < line_width{   //should we exclusively handle start_offset < line_width && end_offset < line_width as well?
             (line_start.saturating_add(start_offset), line_start.saturating_add(end_offset.min(line_width_including_newline))) //start offset already verified within line text bounds
         }
@@ -60,14 +75,6 @@ fn selections_impl(selections: &Selections, text: &Rope, semantics: CursorSemant
             CursorSemantics::Bar => (line_start.saturating_add(start_offset.min(line_width)), line_start.saturating_add(start_offset.min(line_width))),
             CursorSemantics::Block => (line_start.saturating_add(start_offset.min(line_width)), text_util::next_grapheme_index(line_start.saturating_add(start_offset.min(line_width)), text))
         }
-    };
-
-    match selections.primary().direction{
-        //Direction::Forward => Ok(self.push(Selection::new(start, end), false)),
-        Direction::Forward => Ok(selections.push(Selection::new(Range::new(start, end), Direction::Forward), false)),
-        //Direction::Backward => Ok(self.push(Selection::new(end, start), false))
-        //Direction::Backward => Ok(selections.push(Selection::new(Range::new(end, start), Direction::Backward), false))
-        Direction::Backward => Ok(selections.push(Selection::new(Range::new(start, end), Direction::Backward), false))
     }
 }
 
@@ -101,6 +108,9 @@ mod tests{
     //    assert!(add_selection_below::document_impl(&mut doc, semantics).is_err());
     //}
     fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        test_count(1, semantics, text, tuple_selections, primary, tuple_expected_selections, expected_primary);
+    }
+    fn test_count(count: usize, semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
         let text = Rope::from(text);
         let mut vec_selections = Vec::new();
         for tuple in tuple_selections{
@@ -110,7 +120,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        let result = add_selection_below::document_impl(&mut doc, semantics);
+        let result = add_selection_below::document_impl(&mut doc, count, semantics);
         assert!(!result.is_err());
         let mut vec_expected_selections = Vec::new();
         for tuple in tuple_expected_selections{
@@ -130,7 +140,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        assert!(add_selection_below::document_impl(&mut doc, semantics).is_err());
+        assert!(add_selection_below::document_impl(&mut doc, 1, semantics).is_err());
         assert!(!doc.is_modified());
     }
 
@@ -499,4 +509,10 @@ mod tests{
             //block
                 //selection direction forward
                 //selection direction backward
+
+    #[test] fn with_count(){
+        test_count(2, CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(1, 1, None)], 0, vec![(1, 1, None), (5, 5, None), (10, 10, None)], 0);
+        test_count(2, CursorSemantics::Block, "idk\nsome\nshit\n", vec![(1, 2, None)], 0, vec![(1, 2, None), (5, 6, None), (10, 11, None)], 0);
+        test_count(usize::MAX, CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(1, 1, None)], 0, vec![(1, 1, None), (5, 5, None), (10, 10, None), (14, 14, None)], 0);  //stops at doc end
+    }
 }
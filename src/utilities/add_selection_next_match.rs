@@ -0,0 +1,101 @@
+use crate::{
+    selection_history::SelectionChange,
+    document::{Document, DocumentError},
+    selections::{Selections, SelectionsError},
+    selection::{Selection, CursorSemantics, Direction},
+    utilities::search_next,
+};
+
+/// Adds a new primary [`Selection`] at the first match of the regex `pattern` in the whole text, after the primary selection,
+/// that doesn't overlap an existing selection. Wraps around to matches before the primary selection, if needed.
+/// Errors if `pattern` is not a valid regex, if there are no matches, or if every match is already selected.
+pub fn document_impl(document: &mut Document, pattern: &str, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    let matches = search_next::search_matches(pattern, &document.text)?;
+    let end = document.selections.primary().range.end;
+    let split = matches.iter().position(|search_match| search_match.start >= end).unwrap_or(matches.len());
+    let Some(search_match) = matches[split..].iter().chain(&matches[..split])
+        .find(|search_match| !document.selections.iter().any(|selection| selection.range.start < search_match.end && search_match.start < selection.range.end))
+    else{return Err(DocumentError::SelectionsError(SelectionsError::ResultsInSameState));};
+
+    let mut new_selections = document.selections.selections.clone();
+    new_selections.push(Selection::new(search_match.clone(), Direction::Forward));
+    let primary_selection_index = new_selections.len() - 1;
+    let new_selections = Selections::new(new_selections, primary_selection_index, &document.text, semantics);
+    document.set_selections(new_selections, SelectionChange::Jump);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::add_selection_next_match;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, pattern: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = add_selection_next_match::document_impl(&mut doc, pattern, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, pattern: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        assert!(add_selection_next_match::document_impl(&mut doc, pattern, semantics).is_err());
+        assert_eq!(selections, doc.selections);
+    }
+
+    #[test] fn adds_next_match_as_primary_selection(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0
+        // i d k \n s o m e \n s h i t \n s o m e \n
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(4, 8, None)], 0, vec![(4, 8, None), (14, 18, None)], 1);
+        test(CursorSemantics::Block, "idk\nsome\nshit\nsome\n", "some", vec![(4, 8, None)], 0, vec![(4, 8, None), (14, 18, None)], 1);
+    }
+    #[test] fn adds_match_after_cursor(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(9, 9, None)], 0, vec![(9, 9, None), (14, 18, None)], 1);
+        test(CursorSemantics::Block, "idk\nsome\nshit\nsome\n", "some", vec![(9, 10, None)], 0, vec![(9, 10, None), (14, 18, None)], 1);
+    }
+    #[test] fn wraps_to_matches_before_primary_selection(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(14, 18, None)], 0, vec![(4, 8, None), (14, 18, None)], 0);
+    }
+    #[test] fn skips_matches_already_selected(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4
+        // s o m e \n i d k \n s o m e \n s h i t \n s o m e \n
+        test(CursorSemantics::Bar, "some\nidk\nsome\nshit\nsome\n", "some", vec![(0, 4, None), (19, 23, None)], 1, vec![(0, 4, None), (9, 13, None), (19, 23, None)], 1);
+    }
+    #[test] fn errors_if_no_matches(){
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\n", "else", vec![(0, 0, None)], 0);
+    }
+    #[test] fn errors_if_every_match_is_selected(){
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(4, 8, None), (14, 18, None)], 1);
+    }
+    #[test] fn errors_if_invalid_regex(){
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\n", "(", vec![(0, 0, None)], 0);
+    }
+}
@@ -9,8 +9,7 @@ use crate::{
 //had to make the following public
     //Document.text
     //Document.selections
-    //Document.undo_stack
-    //Document.redo_stack
+    //Document.undo_tree
     //Document::apply_replace
 //is this easing of encapsulation acceptable?...
 pub fn document_impl(document: &mut Document, leading_char: char, trailing_char: char, semantics: CursorSemantics) -> Result<(), DocumentError>{
@@ -37,11 +36,8 @@ pub fn document_impl(document: &mut Document, leading_char: char, trailing_char:
 
     if document.selections.count() == 1 && cannot_add_surrounding_pair{return Err(DocumentError::SelectionAtDocBounds);}
     else{
-        // push change set to undo stack
-        document.undo_stack.push(ChangeSet::new(changes, selections_before_changes, document.selections.clone()));
-    
-        // clear redo stack. new actions invalidate the redo history
-        document.redo_stack.clear();
+        // push change set to undo tree
+        document.push_change_set(ChangeSet::new(changes, selections_before_changes, document.selections.clone(), semantics), semantics);
     }
     
     Ok(())
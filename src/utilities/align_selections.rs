@@ -0,0 +1,151 @@
+use crate::{
+    document::{Document, DocumentError},
+    selections::Selections,
+    selection::{Selection, CursorSemantics, Direction},
+    range::Range,
+    history::ChangeSet,
+    text_util,
+};
+use ropey::Rope;
+
+/// Pads before each selection with spaces(or hard tabs, then spaces) so the cursors of aligned selections land in the same display column.
+/// Selections are grouped by their cursor's line. The first selection on each line is aligned with the first on every other line,
+/// the second with the second, and so on. Selections whose start is on a different line than their cursor are not aligned.
+/// Display columns count graphemes, with tabs advancing to the next multiple of `tab_width`.
+/// All padding is recorded as a single change set.
+/// Errors if the selections are already aligned.
+pub fn document_impl(document: &mut Document, use_hard_tab: bool, tab_width: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    let selections_before_changes = document.selections.clone();
+    let mut selections: Vec<Selection> = selections_before_changes.iter().cloned().collect();
+    // (line, index into selections) of each alignable selection, in text order
+    let alignable: Vec<(usize, usize)> = selections.iter().enumerate()
+        .filter_map(|(i, selection)| {
+            let line = document.text.char_to_line(selection.cursor(&document.text, semantics));
+            (document.text.char_to_line(selection.range.start) == line).then_some((line, i))
+        })
+        .collect();
+    // columns[k] holds the index of the k-th selection on each line
+    let mut columns: Vec<Vec<usize>> = Vec::new();
+    let mut previous_line = None;
+    let mut k = 0;
+    for &(line, i) in &alignable{
+        k = if previous_line == Some(line){k + 1}else{0};
+        previous_line = Some(line);
+        if columns.len() <= k{columns.push(Vec::new());}
+        columns[k].push(i);
+    }
+
+    let mut changes = Vec::new();
+    for column in columns.iter().filter(|column| column.len() > 1){
+        let cursor_column = |selection: &Selection, text: &Rope| text_util::display_column(selection.cursor(text, semantics), tab_width, text);
+        let target = column.iter().map(|&i| cursor_column(&selections[i], &document.text)).max().unwrap_or(0);
+        for &i in column{
+            let distance = target - cursor_column(&selections[i], &document.text);
+            if distance == 0{continue;}
+            let start = selections[i].range.start;
+            let padding = padding(text_util::display_column(start, tab_width, &document.text), distance, use_hard_tab, tab_width);
+            let mut at_start = Selection::new(Range::new(start, match semantics{CursorSemantics::Bar => start, CursorSemantics::Block => start + 1}), Direction::Forward);
+            changes.push(Document::apply_insert(&mut document.text, &padding, &mut at_start, semantics));
+            let padding_len = padding.chars().count();
+            // selections don't overlap, so everything from `start` on is after the padding
+            for selection in selections.iter_mut().filter(|selection| selection.range.start >= start){
+                selection.range = Range::new(selection.range.start + padding_len, selection.range.end + padding_len);
+            }
+        }
+    }
+    if changes.is_empty(){return Err(DocumentError::InvalidInput);}
+
+    document.selections = Selections::new(selections, selections_before_changes.primary_selection_index, &document.text, semantics);
+    document.push_change_set(ChangeSet::new(changes, selections_before_changes, document.selections.clone(), semantics), semantics);
+    Ok(())
+}
+
+/// Returns whitespace spanning `distance` display columns, starting at display column `from`.
+fn padding(from: usize, distance: usize, use_hard_tab: bool, tab_width: usize) -> String{
+    let mut padding = String::new();
+    let mut column = from;
+    if use_hard_tab && tab_width > 0{
+        while (column / tab_width + 1) * tab_width <= from + distance{
+            padding.push('\t');
+            column = (column / tab_width + 1) * tab_width;
+        }
+    }
+    padding.push_str(&" ".repeat(from + distance - column));
+    padding
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{align_selections, undo};
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, use_hard_tab: bool, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        let result = align_selections::document_impl(&mut doc, use_hard_tab, 4, semantics);
+        assert!(result.is_ok());
+        let expected_text = Rope::from(expected_text);
+        assert_eq!(expected_text, doc.text);
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &expected_text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, primary, &expected_text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        // a single undo restores the text and selections
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(text, doc.text);
+        assert_eq!(selections, doc.selections);
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(align_selections::document_impl(&mut doc, false, 4, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn aligns_cursors_with_spaces(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2
+        // x   =   1 \n i d k   =   2 \n
+        test(CursorSemantics::Bar, false, "x = 1\nidk = 2\n", vec![(2, 2, None), (10, 10, None)], 0, "x   = 1\nidk = 2\n", vec![(4, 4, None), (12, 12, None)]);
+        test(CursorSemantics::Block, false, "x = 1\nidk = 2\n", vec![(2, 3, None), (10, 11, None)], 1, "x   = 1\nidk = 2\n", vec![(4, 5, None), (12, 13, None)]);
+    }
+    #[test] fn pads_before_extended_selections(){
+        test(CursorSemantics::Bar, false, "x = 1\nidk = 2\n", vec![(2, 5, None), (10, 13, None)], 0, "x   = 1\nidk = 2\n", vec![(4, 7, None), (12, 15, None)]);
+    }
+    #[test] fn aligns_each_column_of_selections_on_a_line(){
+        // a , b , c \n a a a , b b b , c \n
+        test(CursorSemantics::Bar, false, "a,b,c\naaa,bbb,c\n", vec![(1, 1, None), (3, 3, None), (9, 9, None), (13, 13, None)], 0, "a  ,b  ,c\naaa,bbb,c\n", vec![(3, 3, None), (7, 7, None), (13, 13, None), (17, 17, None)]);
+    }
+    #[test] fn counts_tabs_and_graphemes_as_display_columns(){
+        // \t x \n e \u{301} x \n
+        test(CursorSemantics::Bar, false, "\tx\ne\u{301}x\n", vec![(1, 1, None), (5, 5, None)], 0, "\tx\ne\u{301}   x\n", vec![(1, 1, None), (8, 8, None)]);
+    }
+    #[test] fn pads_with_hard_tabs(){
+        test(CursorSemantics::Bar, true, "x = 1\nidkidk = 2\n", vec![(2, 2, None), (13, 13, None)], 0, "x \t   = 1\nidkidk = 2\n", vec![(6, 6, None), (17, 17, None)]);
+    }
+    #[test] fn errors_if_already_aligned(){
+        test_error(CursorSemantics::Bar, "x = 1\ny = 2\n", vec![(2, 2, None), (8, 8, None)], 0);
+        test_error(CursorSemantics::Block, "x = 1\n", vec![(2, 3, None)], 0);
+    }
+}
@@ -4,16 +4,18 @@ use crate::{
     history::{ChangeSet, Change, Operation},
     text_util,
 };
+use ropey::Rope;
 
 //TODO: combine backspace with delete (make delete take a direction::Forward/Backward)
 
-/// Deletes the previous character, or deletes selection if extended.
+/// Deletes the previous `count` characters, or deletes selection if extended.
 /// #### Invariants:
 /// - will not delete past start of doc
 /// - at start of line, appends current line to end of previous line
 /// - removes previous soft tab, if `TAB_WIDTH` spaces are before cursor
 /// - deletes selection if selection extended
-pub fn document_impl(document: &mut Document, use_hard_tab: bool, tab_width: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+pub fn document_impl(document: &mut Document, count: usize, use_hard_tab: bool, tab_width: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    if count == 0{return Err(DocumentError::InvalidInput);}
     let selections_before_changes = document.selections.clone();
     let mut changes = Vec::with_capacity(document.selections.count());
     let mut cannot_delete = false;
@@ -23,7 +25,7 @@ pub fn document_impl(document: &mut Document, use_hard_tab: bool, tab_width: usi
         if selection.is_extended(semantics){
             let change = Document::apply_delete(&mut document.text, selection, semantics);
             if let Operation::Insert{inserted_text} = change.inverse(){
-                document.selections.shift_subsequent_selections_backward(i, inserted_text.len());
+                document.selections.shift_subsequent_selections_backward(i, inserted_text.chars().count());
             }
             changes.push(change);
         }else{
@@ -33,43 +35,43 @@ pub fn document_impl(document: &mut Document, use_hard_tab: bool, tab_width: usi
                 changes.push(change);
             }
             else{
-                let offset_from_line_start = text_util::offset_from_line_start(selection.cursor(&document.text, semantics), &document.text);
-                let line = document.text.line(document.text.char_to_line(selection.cursor(&document.text, semantics)));
-                let is_deletable_soft_tab = !use_hard_tab && offset_from_line_start >= tab_width
-                // handles case where user adds a space after a tab, and wants to delete only the space
-                && offset_from_line_start % tab_width == 0
-                // if previous 4 chars are spaces, delete 4. otherwise, use default behavior
-                && text_util::slice_is_all_spaces(line.slice(offset_from_line_start.saturating_sub(tab_width)..offset_from_line_start));
-
-                if is_deletable_soft_tab{
-                    selection.shift_and_extend(tab_width, &document.text, semantics);
-                    changes.push(Document::apply_delete(&mut document.text, selection, semantics));
-                    document.selections.shift_subsequent_selections_backward(i, tab_width);
-                }
-                else{
-                    //if let Ok(new_selection) = selection.move_left(&document.text, semantics){
-                    if let Ok(new_selection) = crate::utilities::move_cursor_left::selection_impl(selection, &document.text, semantics){
-                        *selection = new_selection;
-                    }   //TODO: handle error    //first for loop guarantees no selection is at doc bounds, so this should be ok to ignore...
-                    changes.push(Document::apply_delete(&mut document.text, selection, semantics));
-                    document.selections.shift_subsequent_selections_backward(i, 1);
+                // each of `count` backspaces deletes a previous soft tab, or the previous grapheme
+                let cursor = selection.cursor(&document.text, semantics);
+                let mut start = cursor;
+                for _ in 0..count{
+                    if start == 0{break;}
+                    start = if is_deletable_soft_tab(start, use_hard_tab, tab_width, &document.text){start - tab_width}
+                    else{text_util::previous_grapheme_index(start, &document.text)};
                 }
+                selection.shift_and_extend(cursor - start, &document.text, semantics);
+                changes.push(Document::apply_delete(&mut document.text, selection, semantics));
+                document.selections.shift_subsequent_selections_backward(i, cursor - start);
             }
         }
     }
 
     if document.selections.count() == 1 && cannot_delete{return Err(DocumentError::SelectionAtDocBounds);}
     else{
-        // push changes to undo stack
-        document.undo_stack.push(ChangeSet::new(changes, selections_before_changes, document.selections.clone()));
-
-        // clear redo stack. new actions invalidate the redo history
-        document.redo_stack.clear();
+        // push changes to undo stack, grouped with any previous change set from the same typing/deletion run
+        let mut change_set = ChangeSet::new(changes, selections_before_changes, document.selections.clone(), semantics);
+        change_set.group_with(document.undo_tree.next_undo(), semantics);
+        document.push_change_set(change_set, semantics);
     }
 
     Ok(())
 }
 
+/// Returns true if `index` is at a multiple of `tab_width` from line start, with `tab_width` spaces before it, making a soft tab.
+fn is_deletable_soft_tab(index: usize, use_hard_tab: bool, tab_width: usize, text: &Rope) -> bool{
+    let offset_from_line_start = text_util::offset_from_line_start(index, text);
+    let line = text.line(text.char_to_line(index));
+    !use_hard_tab && offset_from_line_start >= tab_width
+    // handles case where user adds a space after a tab, and wants to delete only the space
+    && offset_from_line_start % tab_width == 0
+    // if previous 4 chars are spaces, delete 4. otherwise, use default behavior
+    && text_util::slice_is_all_spaces(line.slice(offset_from_line_start.saturating_sub(tab_width)..offset_from_line_start))
+}
+
 #[cfg(test)]
 mod tests{
     use crate::utilities::backspace;
@@ -111,7 +113,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        let result = backspace::document_impl(&mut doc, false, 4, semantics);
+        let result = backspace::document_impl(&mut doc, 1, false, 4, semantics);
         assert!(!result.is_err());
         let expected_text = Rope::from(expected_text);
         assert_eq!(expected_text.clone(), doc.text);
@@ -123,6 +125,28 @@ mod tests{
         assert_eq!(expected_selections, doc.selections);
         assert!(doc.is_modified());
     }
+    fn test_count(count: usize, semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, 0, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = backspace::document_impl(&mut doc, count, false, 4, semantics);
+        assert!(result.is_ok());
+        let expected_text = Rope::from(expected_text);
+        assert_eq!(expected_text.clone(), doc.text);
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &expected_text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, 0, &expected_text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(doc.is_modified());
+    }
     fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
         let text = Rope::from(text);
         let mut vec_selections = Vec::new();
@@ -133,7 +157,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        assert!(backspace::document_impl(&mut doc, false, 4, semantics).is_err());
+        assert!(backspace::document_impl(&mut doc, 1, false, 4, semantics).is_err());
         assert!(!doc.is_modified());
     }
 
@@ -166,6 +190,35 @@ mod tests{
         );
     }
 
+    #[test] fn deletes_multi_byte_chars_and_shifts_subsequent_selections_by_char_count(){
+        test(
+            CursorSemantics::Block, 
+            "\u{e9}d\ns\u{f6}me\n", 
+            vec![
+                (1, 2, None),
+                (5, 6, None)
+            ], 0, 
+            "d\nsme\n", 
+            vec![
+                (0, 1, Some(0)),
+                (3, 4, Some(1))
+            ], 0
+        );
+        test(
+            CursorSemantics::Bar, 
+            "\u{e9}d\ns\u{f6}me\n", 
+            vec![
+                (1, 1, None),
+                (5, 5, None)
+            ], 0, 
+            "d\nsme\n", 
+            vec![
+                (0, 0, Some(0)),
+                (3, 3, Some(1))
+            ], 0
+        );
+    }
+
     #[test] fn when_at_line_start_appends_current_line_to_previous_line(){
         //test(
         //    CursorSemantics::Block, 
@@ -262,4 +315,12 @@ mod tests{
 
 
     //TODO: test error described in range.rs:15:9
+
+    #[test] fn with_count(){
+        test_count(2, CursorSemantics::Bar, "idk\nsome\n", vec![(3, 3, None), (8, 8, None)], "i\nso\n", vec![(1, 1, Some(1)), (4, 4, Some(2))]);
+        test_count(2, CursorSemantics::Block, "idk\nsome\n", vec![(3, 4, None), (8, 9, None)], "i\nso\n", vec![(1, 2, Some(1)), (4, 5, Some(2))]);
+        test_count(3, CursorSemantics::Bar, "idk\n", vec![(4, 4, None)], "i", vec![(1, 1, Some(1))]);    //joins lines
+        test_count(2, CursorSemantics::Bar, "        idk\n", vec![(8, 8, None)], "idk\n", vec![(0, 0, Some(0))]);  //soft tabs
+        test_count(5, CursorSemantics::Bar, "idk\n", vec![(2, 2, None)], "k\n", vec![(0, 0, Some(0))]);   //stops at doc start
+    }
 }
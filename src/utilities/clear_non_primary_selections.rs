@@ -1,11 +1,12 @@
 use crate::{
+    selection_history::SelectionChange,
     document::{Document, DocumentError},
     selections::{Selections, SelectionsError},
 };
 
 pub fn document_impl(document: &mut Document) -> Result<(), DocumentError>{
     match selections_impl(&document.selections){
-        Ok(new_selections) => {document.selections = new_selections;}
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Jump);}
         Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
     }
     Ok(())
@@ -1,4 +1,5 @@
 use crate::{
+    selection_history::SelectionChange,
     document::{Document, DocumentError},
     selection::{Selection, SelectionError, CursorSemantics, Movement},
 };
@@ -7,7 +8,7 @@ use ropey::Rope;
 //TODO: rename to collapse_selections_to_cursor
 pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
     match document.selections.move_cursor_non_overlapping(&document.text, semantics, selection_impl){
-        Ok(new_selections) => {document.selections = new_selections;}
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Jump);}
         Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
     }
     Ok(())
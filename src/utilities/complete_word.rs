@@ -0,0 +1,87 @@
+use crate::{
+    document::{Document, DocumentError},
+    selection::CursorSemantics,
+    range::Range,
+    completion,
+};
+
+/// Replaces the word prefix before each selection's cursor with `completion`, leaving a cursor after it.
+/// See [`WordIndex::candidates_at`](crate::completion::WordIndex::candidates_at) for completions of the current prefixes.
+/// All insertions are recorded as a single change set, which is never grouped with typing.
+/// Errors if `completion` is empty.
+pub fn document_impl(document: &mut Document, completion: &str, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    if completion.is_empty(){return Err(DocumentError::InvalidInput);}
+
+    // each cursor's prefix is replaced. a prefix never reaches back past the previous cursor, so replacements don't overlap
+    let mut previous_cursor = 0;
+    let mut prefixes = Vec::with_capacity(document.selections.count());
+    for selection in document.selections.iter(){
+        let cursor = selection.cursor(&document.text, semantics);
+        prefixes.push((Range::new(completion::word_prefix_start(cursor, &document.text).max(previous_cursor), cursor), completion));
+        previous_cursor = cursor;
+    }
+    document.replace_ranges(&prefixes, |_, _, cursor_after| cursor_after, semantics)
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{complete_word, undo};
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, completion: &str, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        let result = complete_word::document_impl(&mut doc, completion, semantics);
+        assert!(result.is_ok());
+        let expected_text = Rope::from(expected_text);
+        assert_eq!(expected_text, doc.text);
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &expected_text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, primary, &expected_text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        // a single undo restores the text and selections
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(text, doc.text);
+        assert_eq!(selections, doc.selections);
+    }
+
+    #[test] fn replaces_prefix_before_each_cursor(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0
+        // s o m e t h i n g \n s o \n s o \n
+        test(CursorSemantics::Bar, "something\nso\nso\n", vec![(12, 12, None), (15, 15, None)], 0, "something", "something\nsomething\nsomething\n", vec![(19, 19, Some(9)), (29, 29, Some(9))]);
+        test(CursorSemantics::Block, "something\nso\nso\n", vec![(12, 13, None), (15, 16, None)], 1, "something", "something\nsomething\nsomething\n", vec![(19, 20, Some(9)), (29, 30, Some(9))]);
+    }
+    #[test] fn keeps_text_after_cursor(){
+        test(CursorSemantics::Bar, "idk sothing\n", vec![(6, 6, None)], 0, "some", "idk something\n", vec![(8, 8, Some(8))]);
+    }
+    #[test] fn inserts_completion_without_prefix(){
+        test(CursorSemantics::Bar, "idk \n", vec![(4, 4, None)], 0, "some", "idk some\n", vec![(8, 8, Some(8))]);
+    }
+    #[test] fn extended_selection_completes_prefix_before_its_cursor(){
+        test(CursorSemantics::Bar, "idk so\n", vec![(0, 6, None)], 0, "some", "idk some\n", vec![(8, 8, Some(8))]);
+    }
+    #[test] fn prefix_stops_at_previous_cursor(){
+        test(CursorSemantics::Bar, "sosh\n", vec![(2, 2, None), (4, 4, None)], 0, "idk", "idkidk\n", vec![(3, 3, Some(3)), (6, 6, Some(6))]);
+    }
+    #[test] fn errors_if_completion_empty(){
+        let semantics = CursorSemantics::Bar;
+        let mut doc = Document::new(semantics).with_text(Rope::from("so\n"));
+        assert!(complete_word::document_impl(&mut doc, "", semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    document::{Document, DocumentError},
+    selections::Selections,
+    selection::{Selection, CursorSemantics, Direction},
+    range::Range,
+    history::ChangeSet,
+};
+use ropey::RopeSlice;
+
+/// Rewrites the leading whitespace of every line to hard tabs(`use_hard_tab`), or to spaces, keeping its visual width.
+/// Tabs advance to the next multiple of `tab_width`. Leading whitespace that isn't a whole number of indent levels keeps its remainder as spaces.
+/// Selections keep covering the same text.
+/// Errors if `tab_width` is 0, or if no line's indentation changes.
+pub fn document_impl(document: &mut Document, use_hard_tab: bool, tab_width: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    if tab_width == 0{return Err(DocumentError::InvalidInput);}
+    // (line start, old indentation len, new indentation)
+    let conversions: Vec<(usize, usize, String)> = document.text.lines().enumerate()
+        .filter_map(|(line_number, line)| {
+            let old_len = line.chars().take_while(|&char| char == ' ' || char == '\t').count();
+            // whitespace only lines have no indentation to convert
+            if old_len == 0 || old_len == line.chars().filter(|&char| char != '\n' && char != '\r').count(){return None;}
+            let width = indentation_width(line.slice(..old_len), tab_width);
+            let new_indentation = if use_hard_tab{
+                format!("{}{}", "\t".repeat(width / tab_width), " ".repeat(width % tab_width))
+            }else{" ".repeat(width)};
+            (new_indentation != line.slice(..old_len)).then(|| (document.text.line_to_char(line_number), old_len, new_indentation))
+        })
+        .collect();
+    if conversions.is_empty(){return Err(DocumentError::InvalidInput);}
+
+    // indices inside converted indentation keep their offset from line start, up to the new indentation's end
+    let shifted = |index: usize| {
+        let mut shifted = index;
+        for (line_start, old_len, new_indentation) in &conversions{
+            let new_len = new_indentation.chars().count();
+            if index >= line_start + old_len{shifted = shifted + new_len - old_len;}
+            else if index > *line_start{shifted = shifted - (index - line_start) + (index - line_start).min(new_len);}
+        }
+        shifted
+    };
+    let new_selections = document.selections.iter().map(|selection| {
+        let mut converted = selection.clone();
+        let start = shifted(selection.range.start);
+        let end = shifted(selection.range.end);
+        converted.range = Range::new(start, if semantics == CursorSemantics::Block{end.max(start + 1)}else{end});
+        converted
+    }).collect();
+
+    let selections_before_changes = document.selections.clone();
+    let mut changes = Vec::with_capacity(conversions.len());
+    // bottom to top, so line starts above stay valid
+    for (line_start, old_len, new_indentation) in conversions.iter().rev(){
+        let mut indentation = Selection::new(Range::new(*line_start, line_start + old_len), Direction::Forward);
+        changes.push(Document::apply_replace(&mut document.text, new_indentation, &mut indentation, semantics));
+    }
+
+    document.selections = Selections::new(new_selections, document.selections.primary_selection_index, &document.text, semantics);
+    document.push_change_set(ChangeSet::new(changes, selections_before_changes, document.selections.clone(), semantics), semantics);
+    Ok(())
+}
+
+/// Returns the visual width of `indentation`, made of spaces and tabs.
+fn indentation_width(indentation: RopeSlice, tab_width: usize) -> usize{
+    indentation.chars().fold(0, |width, char| if char == '\t'{(width / tab_width + 1) * tab_width}else{width + 1})
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{convert_indentation, undo};
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, use_hard_tab: bool, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        let result = convert_indentation::document_impl(&mut doc, use_hard_tab, 4, semantics);
+        assert!(result.is_ok());
+        let expected_text = Rope::from(expected_text);
+        assert_eq!(expected_text, doc.text);
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &expected_text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, primary, &expected_text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(doc.is_modified());
+        // a single undo restores the text and selections
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(text, doc.text);
+        assert_eq!(selections, doc.selections);
+    }
+    fn test_error(semantics: CursorSemantics, use_hard_tab: bool, text: &str){
+        let text = Rope::from(text);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone());
+        assert!(convert_indentation::document_impl(&mut doc, use_hard_tab, 4, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn converts_spaces_to_tabs(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0
+        // {               x \n                 y \n } \n
+        test(CursorSemantics::Bar, true, "{\n    x\n        y\n}\n", vec![(7, 7, None), (17, 18, None)], 0, "{\n\tx\n\t\ty\n}\n", vec![(4, 4, None), (8, 9, None)]);
+        test(CursorSemantics::Block, true, "{\n    x\n        y\n}\n", vec![(6, 7, None)], 0, "{\n\tx\n\t\ty\n}\n", vec![(3, 4, None)]);
+    }
+    #[test] fn converts_tabs_to_spaces(){
+        test(CursorSemantics::Bar, false, "{\n\tx\n\t\ty\n}\n", vec![(4, 4, None)], 0, "{\n    x\n        y\n}\n", vec![(7, 7, None)]);
+    }
+    #[test] fn keeps_partial_indentation_as_spaces(){
+        test(CursorSemantics::Bar, true, "      x\n", vec![(7, 7, None)], 0, "\t  x\n", vec![(4, 4, None)]);
+        // a tab after spaces advances to the next multiple of tab width
+        test(CursorSemantics::Bar, false, "  \tx\n", vec![(4, 4, None)], 0, "    x\n", vec![(5, 5, None)]);
+    }
+    #[test] fn cursor_inside_indentation_stays_inside(){
+        test(CursorSemantics::Block, true, "    x\n", vec![(2, 3, None)], 0, "\tx\n", vec![(1, 2, None)]);
+    }
+    #[test] fn leaves_whitespace_only_lines(){
+        test(CursorSemantics::Bar, true, "    \n    x\n", vec![(0, 0, None)], 0, "    \n\tx\n", vec![(0, 0, None)]);
+    }
+    #[test] fn errors_if_indentation_already_matches(){
+        test_error(CursorSemantics::Bar, true, "\tidk\n");
+        test_error(CursorSemantics::Block, false, "    idk\nsome\n");
+    }
+}
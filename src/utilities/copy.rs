@@ -1,16 +1,19 @@
 use crate::{
     document::{Document, DocumentError},
-    selections::SelectionsError
+    register::RegisterName,
+    clipboard::ClipboardProvider,
 };
 
-/// Copy single selection to clipboard.
-/// Ensure single selection when calling this function.
-pub fn document_impl(document: &mut Document) -> Result<(), DocumentError>{
-    if document.selections.count() > 1{return Err(DocumentError::SelectionsError(SelectionsError::MultipleSelections));}
-    
-    let selection = document.selections.primary().clone();
-    // Copy the selected text to the clipboard
-    document.clipboard = document.text.slice(selection.range.start..selection.range.end).to_string();
+/// Copy selected text to `register`, one fragment per selection.
+/// Copying to [`RegisterName::Clipboard`] also sets the system `clipboard` to all fragments joined with a newline.
+pub fn document_impl(document: &mut Document, register: RegisterName, clipboard: &mut dyn ClipboardProvider) -> Result<(), DocumentError>{
+    let fragments: Vec<String> = document.selections.iter()
+        .map(|selection| document.text.slice(selection.range.start..selection.range.end).to_string())
+        .collect();
+    if register == RegisterName::Clipboard{
+        clipboard.set_contents(&fragments.join("\n")).map_err(DocumentError::ClipboardError)?;
+    }
+    document.registers.set(register, fragments);
 
     Ok(())
 }
@@ -22,6 +25,8 @@ mod tests{
         document::Document,
         selections::Selections,
         selection::{Selection, CursorSemantics},
+        register::RegisterName,
+        clipboard::{ClipboardProvider, InMemoryProvider},
     };
     use ropey::Rope;
 
@@ -40,7 +45,7 @@ mod tests{
     //        .with_selections(Selections::new(selections, primary, &text, semantics));
     //    assert!(copy::document_impl(&mut doc).is_err());
     //}
-    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, expected_clipboard: &str){
+    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, expected_register: Vec<&str>){
         let text = Rope::from(text);
         let mut vec_selections = Vec::new();
         for tuple in tuple_selections{
@@ -50,25 +55,11 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        let result = copy::document_impl(&mut doc);
+        let result = copy::document_impl(&mut doc, RegisterName::default(), &mut InMemoryProvider::default());
         assert!(!result.is_err());
-        assert_eq!(expected_clipboard.to_string(), doc.clipboard);
+        assert_eq!(Some(expected_register.iter().map(|fragment| fragment.to_string()).collect::<Vec<String>>().as_slice()), doc.registers.get(RegisterName::default()));
         assert!(!doc.is_modified());
     }
-    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
-        let text = Rope::from(text);
-        let mut vec_selections = Vec::new();
-        for tuple in tuple_selections{
-            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
-        }
-        let selections = Selections::new(vec_selections, primary, &text, semantics);
-        let mut doc = Document::new(semantics)
-            .with_text(text.clone())
-            .with_selections(selections);
-        assert!(copy::document_impl(&mut doc).is_err());
-        assert!(!doc.is_modified());
-    }
-
     //TODO: copy with no selection extension
         //should fail with bar semantics?...
         //should copy single char with block semantics
@@ -86,7 +77,7 @@ mod tests{
             vec![
                 (4, 9, None)
             ], 0, 
-            "some\n"
+            vec!["some\n"]
         );
     }
     #[test] fn copy_with_selection_direction_forward_bar_semantics(){
@@ -102,7 +93,7 @@ mod tests{
             vec![
                 (4, 9, None)
             ], 0, 
-            "some\n"
+            vec!["some\n"]
         );
     }
 
@@ -119,7 +110,7 @@ mod tests{
             vec![
                 (9, 4, None)
             ], 0, 
-            "some\n"
+            vec!["some\n"]
         );
     }
     #[test] fn copy_with_selection_direction_backward_bar_semantics(){
@@ -135,42 +126,64 @@ mod tests{
             vec![
                 (9, 4, None)
             ], 0, 
-            "some\n"
+            vec!["some\n"]
         );
     }
 
-    #[test] fn copy_with_multiple_selections_should_error(){
-        //test_error(
-        //    "idk\nsome\nshit\n", 
-        //    vec![
-        //        Selection::new(Range::new(0, 1), Direction::Forward), 
-        //        Selection::new(Range::new(4, 5), Direction::Forward)
-        //    ], 0, 
-        //    CursorSemantics::Block
-        //);
-        test_error(
+    #[test] fn copy_with_multiple_selections_block_semantics(){
+        test(
             CursorSemantics::Block, 
             "idk\nsome\nshit\n", 
             vec![
-                (0, 1, None),
-                (4, 5, None)
-            ], 0
+                (0, 3, None),
+                (4, 5, None),
+                (13, 9, None)
+            ], 0, 
+            vec!["idk", "s", "shit"]
         );
-        //test_error(
-        //    "idk\nsome\nshit\n", 
-        //    vec![
-        //        Selection::new(Range::new(0, 0), Direction::Forward), 
-        //        Selection::new(Range::new(4, 4), Direction::Forward)
-        //    ], 0, 
-        //    CursorSemantics::Bar
-        //);
-        test_error(
+    }
+    #[test] fn copy_with_multiple_selections_bar_semantics(){
+        test(
             CursorSemantics::Bar, 
             "idk\nsome\nshit\n", 
             vec![
-                (0, 0, None),
-                (4, 4, None)
-            ], 0
+                (0, 3, None),
+                (4, 4, None),
+                (13, 9, None)
+            ], 0, 
+            vec!["idk", "", "shit"]
         );
     }
+    #[test] fn copy_to_named_register_does_not_change_default_register(){
+        let text = Rope::from("idk\nsome\nshit\n");
+        let mut doc = Document::new(CursorSemantics::Bar)
+            .with_text(text.clone())
+            .with_selections(Selections::new(vec![Selection::new_from_components(4, 8, None, &text, CursorSemantics::Bar)], 0, &text, CursorSemantics::Bar))
+            .with_register(RegisterName::default(), vec!["idk".to_string()]);
+        assert!(copy::document_impl(&mut doc, RegisterName::Named('a'), &mut InMemoryProvider::default()).is_ok());
+        assert_eq!(Some(["some".to_string()].as_slice()), doc.registers.get(RegisterName::Named('a')));
+        assert_eq!(Some(["idk".to_string()].as_slice()), doc.registers.get(RegisterName::default()));
+    }
+    #[test] fn copy_to_black_hole_register_discards_text(){
+        let text = Rope::from("idk\nsome\nshit\n");
+        let mut doc = Document::new(CursorSemantics::Bar)
+            .with_text(text.clone())
+            .with_selections(Selections::new(vec![Selection::new_from_components(4, 8, None, &text, CursorSemantics::Bar)], 0, &text, CursorSemantics::Bar));
+        assert!(copy::document_impl(&mut doc, RegisterName::BlackHole, &mut InMemoryProvider::default()).is_ok());
+        assert_eq!(None, doc.registers.get(RegisterName::BlackHole));
+        assert_eq!(None, doc.registers.get(RegisterName::default()));
+    }
+    #[test] fn copy_to_clipboard_register_sets_system_clipboard(){
+        let text = Rope::from("idk\nsome\nshit\n");
+        let mut doc = Document::new(CursorSemantics::Bar)
+            .with_text(text.clone())
+            .with_selections(Selections::new(vec![
+                Selection::new_from_components(0, 3, None, &text, CursorSemantics::Bar),
+                Selection::new_from_components(4, 8, None, &text, CursorSemantics::Bar)
+            ], 0, &text, CursorSemantics::Bar));
+        let mut clipboard = InMemoryProvider::default();
+        assert!(copy::document_impl(&mut doc, RegisterName::Clipboard, &mut clipboard).is_ok());
+        assert_eq!("idk\nsome", clipboard.get_contents().unwrap());
+        assert_eq!(Some(["idk".to_string(), "some".to_string()].as_slice()), doc.registers.get(RegisterName::Clipboard));
+    }
 }
@@ -1,19 +1,15 @@
 use crate::{
     document::{Document, DocumentError},
-    selections::SelectionsError,
     selection::CursorSemantics,
+    register::RegisterName,
+    clipboard::ClipboardProvider,
 };
 
-/// Cut single selection.
-/// Copies text to clipboard and removes selected text from document.
-/// Ensure single selection when calling this function.
-pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
-    if document.selections.count() > 1{return Err(DocumentError::SelectionsError(SelectionsError::MultipleSelections));}
-
-    let selection = document.selections.primary_mut();
-    // Copy the selected text to the clipboard
-    document.clipboard = document.text.slice(selection.range.start..selection.range.end).to_string();
-    crate::utilities::delete::document_impl(document, semantics)   //notice this is returning the result from delete
+/// Cut selected text.
+/// Copies text to `register`, one fragment per selection, and removes selected text from document.
+pub fn document_impl(document: &mut Document, register: RegisterName, clipboard: &mut dyn ClipboardProvider, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    crate::utilities::copy::document_impl(document, register, clipboard)?;
+    crate::utilities::delete::document_impl(document, 1, semantics)   //notice this is returning the result from delete
 }
 
 #[cfg(test)]
@@ -23,6 +19,8 @@ mod tests{
         document::Document,
         selections::Selections,
         selection::{Selection, CursorSemantics},
+        register::RegisterName,
+        clipboard::InMemoryProvider,
     };
     use ropey::Rope;
 
@@ -45,7 +43,7 @@ mod tests{
     //        .with_selections(Selections::new(selections, primary, &text, semantics));
     //    assert!(cut::document_impl(&mut doc, semantics).is_err())
     //}
-    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize, expected_clipboard: &str){
+    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize, expected_register: Vec<&str>){
         let text = Rope::from(text);
         let mut vec_selections = Vec::new();
         for tuple in tuple_selections{
@@ -55,7 +53,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        let result = cut::document_impl(&mut doc, semantics);
+        let result = cut::document_impl(&mut doc, RegisterName::default(), &mut InMemoryProvider::default(), semantics);
         assert!(!result.is_err());
         let expected_text = Rope::from(expected_text);
         assert_eq!(expected_text.clone(), doc.text);
@@ -65,23 +63,9 @@ mod tests{
         }
         let expected_selections = Selections::new(vec_expected_selections, expected_primary, &expected_text, semantics);
         assert_eq!(expected_selections, doc.selections);
-        assert_eq!(expected_clipboard.to_string(), doc.clipboard);
+        assert_eq!(Some(expected_register.iter().map(|fragment| fragment.to_string()).collect::<Vec<String>>().as_slice()), doc.registers.get(RegisterName::default()));
         assert!(doc.is_modified());
     }
-    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
-        let text = Rope::from(text);
-        let mut vec_selections = Vec::new();
-        for tuple in tuple_selections{
-            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
-        }
-        let selections = Selections::new(vec_selections, primary, &text, semantics);
-        let mut doc = Document::new(semantics)
-            .with_text(text.clone())
-            .with_selections(selections);
-        assert!(cut::document_impl(&mut doc, semantics).is_err());
-        assert!(!doc.is_modified());
-    }
-
     #[test] fn cut_with_selection_direction_forward_block_semantics(){
         //test(
         //    "idk\nsome\nshit\n",
@@ -101,7 +85,7 @@ mod tests{
             vec![
                 (4, 5, Some(0))
             ], 0, 
-            "some\n"
+            vec!["some\n"]
         );
     }
     #[test] fn cut_with_selection_direction_forward_bar_semantics(){
@@ -123,7 +107,7 @@ mod tests{
             vec![
                 (4, 4, Some(0))
             ], 0, 
-            "some\n"
+            vec!["some\n"]
         );
     }
 
@@ -146,7 +130,7 @@ mod tests{
             vec![
                 (4, 5, Some(0))
             ], 0, 
-            "some\n"
+            vec!["some\n"]
         );
     }
     #[test] fn cut_with_selection_direction_backward_bar_semantics(){
@@ -168,42 +152,40 @@ mod tests{
             vec![
                 (4, 4, Some(0))
             ], 0, 
-            "some\n"
+            vec!["some\n"]
         );
     }
 
-    #[test] fn cut_with_multiple_selections_returns_error(){
-        //test_error(
-        //    "idk\nsome\nshit\n",
-        //    vec![
-        //        Selection::new(Range::new(0, 3), Direction::Forward),
-        //        Selection::new(Range::new(4, 7), Direction::Forward)
-        //    ], 0, 
-        //    CursorSemantics::Bar
-        //);
-        test_error(
-            CursorSemantics::Bar, 
+    #[test] fn cut_with_multiple_selections_block_semantics(){
+        test(
+            CursorSemantics::Block, 
             "idk\nsome\nshit\n", 
             vec![
                 (0, 3, None),
                 (4, 7, None)
-            ], 0
+            ], 0, 
+            "\ne\nshit\n", 
+            vec![
+                (0, 1, Some(0)),
+                (1, 2, Some(0))
+            ], 0, 
+            vec!["idk", "som"]
         );
-        //test_error(
-        //    "idk\nsome\nshit\n",
-        //    vec![
-        //        Selection::new(Range::new(0, 3), Direction::Forward),
-        //        Selection::new(Range::new(4, 7), Direction::Forward)
-        //    ], 0, 
-        //    CursorSemantics::Block
-        //);
-        test_error(
-            CursorSemantics::Block, 
+    }
+    #[test] fn cut_with_multiple_selections_bar_semantics(){
+        test(
+            CursorSemantics::Bar, 
             "idk\nsome\nshit\n", 
             vec![
                 (0, 3, None),
                 (4, 7, None)
-            ], 0
+            ], 0, 
+            "\ne\nshit\n", 
+            vec![
+                (0, 0, Some(0)),
+                (1, 1, Some(0))
+            ], 0, 
+            vec!["idk", "som"]
         );
     }
 }
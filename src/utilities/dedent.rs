@@ -0,0 +1,136 @@
+use crate::{
+    document::{Document, DocumentError},
+    selections::Selections,
+    selection::{Selection, CursorSemantics, Direction},
+    range::Range,
+    history::ChangeSet,
+    utilities::move_lines_up,
+};
+
+/// Removes one indent level from the start of every line touched by any [`Selection`].
+/// A leading hard tab is removed, or else up to `tab_width` leading spaces, so partial indentation is removed entirely.
+/// Selections keep covering the same text.
+/// Errors if no touched line is indented.
+pub fn document_impl(document: &mut Document, tab_width: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    // (line start, number of indentation chars to remove)
+    let dedents: Vec<(usize, usize)> = move_lines_up::line_blocks(&document.selections, &document.text).into_iter()
+        .flat_map(|(first, last)| first..=last)
+        .map(|line| {
+            let line_text = document.text.line(line);
+            let distance = if line_text.chars().next() == Some('\t'){1}
+                else{line_text.chars().take(tab_width).take_while(|&char| char == ' ').count()};
+            (document.text.line_to_char(line), distance)
+        })
+        .filter(|&(_, distance)| distance > 0)
+        .collect();
+    if dedents.is_empty(){return Err(DocumentError::InvalidInput);}
+
+    // indices inside removed indentation move to their line start
+    let shifted = |index: usize| index - dedents.iter()
+        .filter(|&&(line_start, _)| line_start < index)
+        .map(|&(line_start, distance)| distance.min(index - line_start))
+        .sum::<usize>();
+    let new_selections = document.selections.iter().map(|selection| {
+        let mut dedented = selection.clone();
+        let start = shifted(selection.range.start);
+        let end = shifted(selection.range.end);
+        // a block cursor inside removed indentation stays a block cursor
+        dedented.range = Range::new(start, if semantics == CursorSemantics::Block{end.max(start + 1)}else{end});
+        dedented
+    }).collect();
+
+    let selections_before_changes = document.selections.clone();
+    let mut changes = Vec::with_capacity(dedents.len());
+    // bottom to top, so line starts above stay valid
+    for &(line_start, distance) in dedents.iter().rev(){
+        let mut indentation = Selection::new(Range::new(line_start, line_start + distance), Direction::Forward);
+        changes.push(Document::apply_delete(&mut document.text, &mut indentation, semantics));
+    }
+
+    document.selections = Selections::new(new_selections, document.selections.primary_selection_index, &document.text, semantics);
+    document.push_change_set(ChangeSet::new(changes, selections_before_changes, document.selections.clone(), semantics), semantics);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{dedent, undo};
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        let result = dedent::document_impl(&mut doc, 4, semantics);
+        assert!(result.is_ok());
+        let expected_text = Rope::from(expected_text);
+        assert_eq!(expected_text, doc.text);
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &expected_text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, primary, &expected_text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(doc.is_modified());
+        // a single undo restores the text and selections
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(text, doc.text);
+        assert_eq!(selections, doc.selections);
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(dedent::document_impl(&mut doc, 4, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn dedents_soft_tab(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0
+        //         i d k \n
+        test(CursorSemantics::Bar, "        idk\n", vec![(9, 9, None)], 0, "    idk\n", vec![(5, 5, None)]);
+        test(CursorSemantics::Block, "        idk\n", vec![(9, 10, None)], 0, "    idk\n", vec![(5, 6, None)]);
+    }
+    #[test] fn dedents_hard_tab(){
+        test(CursorSemantics::Bar, "\t\tidk\n", vec![(3, 3, None)], 0, "\tidk\n", vec![(2, 2, None)]);
+    }
+    #[test] fn dedents_partial_indentation(){
+        test(CursorSemantics::Bar, "  idk\n", vec![(3, 3, None)], 0, "idk\n", vec![(1, 1, None)]);
+    }
+    #[test] fn cursor_inside_removed_indentation_moves_to_line_start(){
+        test(CursorSemantics::Bar, "    idk\n", vec![(2, 2, None)], 0, "idk\n", vec![(0, 0, None)]);
+        test(CursorSemantics::Block, "    idk\n", vec![(2, 3, None)], 0, "idk\n", vec![(0, 1, None)]);
+    }
+    #[test] fn dedents_every_touched_line_and_keeps_selection_text(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0
+        //         i d k \n s o m e \n     s h i t \n
+        test(CursorSemantics::Bar, "    idk\nsome\n  shit\n", vec![(4, 19, None)], 0, "idk\nsome\nshit\n", vec![(0, 13, None)]);
+        test(CursorSemantics::Block, "    idk\nsome\n  shit\n", vec![(19, 4, None)], 0, "idk\nsome\nshit\n", vec![(13, 0, None)]);
+    }
+    #[test] fn with_multiple_selections_on_same_line(){
+        test(CursorSemantics::Bar, "    idk some\n", vec![(4, 4, None), (8, 8, None)], 1, "idk some\n", vec![(0, 0, None), (4, 4, None)]);
+    }
+    #[test] fn errors_if_no_touched_line_is_indented(){
+        test_error(CursorSemantics::Bar, "idk\n    some\n", vec![(1, 1, None)], 0);
+        test_error(CursorSemantics::Block, "\n", vec![(0, 1, None)], 0);
+    }
+}
@@ -1,13 +1,14 @@
 use crate::{
     document::{Document, DocumentError},
-    selection::CursorSemantics,
+    selection::{CursorSemantics, Movement, Direction},
     history::{ChangeSet, Change, Operation},
 };
 
 //TODO: can this function and backspace be combined?...
 
-/// Deletes text inside each [`Selection`] in [`Selections`], or if [`Selection`] not extended, the next character, and pushes changes to undo stack.
-pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
+/// Deletes text inside each [`Selection`] in [`Selections`], or if [`Selection`] not extended, the next `count` characters, and pushes changes to undo stack.
+pub fn document_impl(document: &mut Document, count: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    if count == 0{return Err(DocumentError::InvalidInput);}
     let selections_before_changes = document.selections.clone();
     let mut changes = Vec::new();
     let mut cannot_delete = false;
@@ -20,9 +21,16 @@ pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Res
             changes.push(change);
         }
         else{   //apply the delete
+            if count > 1 && !selection.is_extended(semantics){
+                // extend over the next `count` graphemes, so they are deleted together
+                let amount = match semantics{CursorSemantics::Bar => count, CursorSemantics::Block => count - 1};
+                if let Ok(new_selection) = selection.move_horizontally(amount, &document.text, Movement::Extend, Direction::Forward, semantics){
+                    *selection = new_selection;
+                }
+            }
             let change = Document::apply_delete(&mut document.text, selection, semantics);
             if let Operation::Insert{inserted_text} = change.inverse(){
-                document.selections.shift_subsequent_selections_backward(i, inserted_text.len());
+                document.selections.shift_subsequent_selections_backward(i, inserted_text.chars().count());
             }
             changes.push(change);
         }
@@ -30,11 +38,10 @@ pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Res
 
     if document.selections.count() == 1 && cannot_delete{return Err(DocumentError::SelectionAtDocBounds);}
     else{
-        // push change set to undo stack
-        document.undo_stack.push(ChangeSet::new(changes, selections_before_changes, document.selections.clone()));
-
-        // clear redo stack. new actions invalidate the redo history
-        document.redo_stack.clear();
+        // push change set to undo stack, grouped with any previous change set from the same typing/deletion run
+        let mut change_set = ChangeSet::new(changes, selections_before_changes, document.selections.clone(), semantics);
+        change_set.group_with(document.undo_tree.next_undo(), semantics);
+        document.push_change_set(change_set, semantics);
     }
 
     Ok(())
@@ -80,7 +87,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        let result = delete::document_impl(&mut doc, semantics);
+        let result = delete::document_impl(&mut doc, 1, semantics);
         assert!(!result.is_err());
         let expected_text = Rope::from(expected_text);
         assert_eq!(expected_text.clone(), doc.text);
@@ -92,6 +99,28 @@ mod tests{
         assert_eq!(expected_selections, doc.selections);
         assert!(doc.is_modified());
     }
+    fn test_count(count: usize, semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, 0, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = delete::document_impl(&mut doc, count, semantics);
+        assert!(result.is_ok());
+        let expected_text = Rope::from(expected_text);
+        assert_eq!(expected_text.clone(), doc.text);
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &expected_text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, 0, &expected_text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(doc.is_modified());
+    }
     fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
         let text = Rope::from(text);
         let mut vec_selections = Vec::new();
@@ -102,7 +131,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        assert!(delete::document_impl(&mut doc, semantics).is_err());
+        assert!(delete::document_impl(&mut doc, 1, semantics).is_err());
         assert!(!doc.is_modified());
     }
 
@@ -135,6 +164,35 @@ mod tests{
         );
     }
 
+    #[test] fn with_extended_selections_over_multi_byte_chars(){
+        test(
+            CursorSemantics::Block, 
+            "\u{1f980}\u{e9}x\ns\u{f6}me\n", 
+            vec![
+                (0, 2, None),
+                (4, 6, None)
+            ], 0, 
+            "x\nme\n", 
+            vec![
+                (0, 1, Some(0)),
+                (2, 3, Some(0))
+            ], 0
+        );
+        test(
+            CursorSemantics::Bar, 
+            "\u{1f980}\u{e9}x\ns\u{f6}me\n", 
+            vec![
+                (0, 2, None),
+                (4, 6, None)
+            ], 0, 
+            "x\nme\n", 
+            vec![
+                (0, 0, Some(0)),
+                (2, 2, Some(0))
+            ], 0
+        );
+    }
+
     #[test] fn with_extended_selections(){
         //test(
         //    "idk\nsome\nshit\n", 
@@ -208,4 +266,23 @@ mod tests{
             ], 0
         );
     }
+
+    #[test] fn with_count(){
+        test_count(2, CursorSemantics::Bar, "idk\nsome\n", vec![(0, 0, None), (4, 4, None)], "k\nme\n", vec![(0, 0, Some(0)), (2, 2, Some(0))]);
+        test_count(2, CursorSemantics::Block, "idk\nsome\n", vec![(0, 1, None), (4, 5, None)], "k\nme\n", vec![(0, 1, Some(0)), (2, 3, Some(0))]);
+        test_count(5, CursorSemantics::Bar, "idk\n", vec![(1, 1, None)], "i", vec![(1, 1, Some(1))]); //stops at doc end
+    }
+    #[test] fn with_count_is_undone_at_once(){
+        let text = Rope::from("idk\nsome\n");
+        let mut doc = Document::new(CursorSemantics::Bar).with_text(text.clone()).with_selections(Selections::new(vec![Selection::new_from_components(0, 0, None, &text, CursorSemantics::Bar)], 0, &text, CursorSemantics::Bar));
+        assert!(delete::document_impl(&mut doc, 3, CursorSemantics::Bar).is_ok());
+        assert!(crate::utilities::undo::document_impl(&mut doc, CursorSemantics::Bar).is_ok());
+        assert_eq!(text, doc.text);
+    }
+    #[test] fn errors_with_count_of_zero(){
+        let text = Rope::from("idk\n");
+        let mut doc = Document::new(CursorSemantics::Bar).with_text(text.clone()).with_selections(Selections::new(vec![Selection::new_from_components(0, 0, None, &text, CursorSemantics::Bar)], 0, &text, CursorSemantics::Bar));
+        assert!(delete::document_impl(&mut doc, 0, CursorSemantics::Bar).is_err());
+        assert!(!doc.is_modified());
+    }
 }
@@ -1,19 +1,20 @@
 use crate::{
+    selection_history::SelectionChange,
     document::{Document, DocumentError},
     selection::{Selection, SelectionError, CursorSemantics, Direction, Movement},
 };
 use ropey::Rope;
 
-pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
-    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, selection_impl){
-        Ok(new_selections) => {document.selections = new_selections;}
+pub fn document_impl(document: &mut Document, count: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, |selection, text, semantics| selection_impl(selection, count, text, semantics)){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Step);}
         Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
     }
     Ok(())
 }
 
-/// Returns a new instance of [`Selection`] with the [`Selection`] extended down.
-pub fn selection_impl(selection: &Selection, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{ //TODO: ensure this can't extend past doc text end
+/// Returns a new instance of [`Selection`] with the [`Selection`] extended down `count` lines.
+pub fn selection_impl(selection: &Selection, count: usize, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{ //TODO: ensure this can't extend past doc text end
     selection.assert_invariants(text, semantics);
     //if text.char_to_line(self.cursor(text, semantics)) == text.len_lines().saturating_sub(1){return Err(SelectionError::ResultsInSameState);}
     let last_line = text.len_lines().saturating_sub(1);
@@ -21,7 +22,7 @@ pub fn selection_impl(selection: &Selection, text: &Rope, semantics: CursorSeman
     || text.char_to_line(selection.range.end) == last_line
     || text.char_to_line(selection.cursor(text, semantics)) == last_line{return Err(SelectionError::ResultsInSameState);}
 
-    selection.move_vertically(1, text, Movement::Extend, Direction::Forward, semantics)
+    selection.move_vertically(count, text, Movement::Extend, Direction::Forward, semantics)
 }
 
 #[cfg(test)]
@@ -54,6 +55,9 @@ mod tests{
     //    assert!(!doc.is_modified());
     //}
     fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        test_count(1, semantics, text, tuple_selections, primary, tuple_expected_selections, expected_primary);
+    }
+    fn test_count(count: usize, semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
         let text = Rope::from(text);
         let mut vec_selections = Vec::new();
         for tuple in tuple_selections{
@@ -63,7 +67,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        let result = extend_selection_down::document_impl(&mut doc, semantics);
+        let result = extend_selection_down::document_impl(&mut doc, count, semantics);
         assert!(!result.is_err());
         let mut vec_expected_selections = Vec::new();
         for tuple in tuple_expected_selections{
@@ -83,7 +87,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        assert!(extend_selection_down::document_impl(&mut doc, semantics).is_err());
+        assert!(extend_selection_down::document_impl(&mut doc, 1, semantics).is_err());
         assert!(!doc.is_modified());
     }
 
@@ -221,4 +225,9 @@ mod tests{
             ], 0
         );
     }
+
+    #[test] fn with_count(){
+        test_count(2, CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(1, 1, None)], 0, vec![(1, 10, Some(1))], 0);
+        test_count(2, CursorSemantics::Block, "idk\nsome\nshit\n", vec![(1, 2, None)], 0, vec![(1, 11, Some(1))], 0);
+    }
 }
@@ -0,0 +1,84 @@
+use crate::{
+    selection_history::SelectionChange,
+    document::{Document, DocumentError},
+    selection::{Selection, SelectionError, CursorSemantics, Movement},
+    utilities::move_cursor_matching_pair
+};
+use ropey::Rope;
+
+pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, selection_impl){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Jump);}
+        Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
+    }
+    Ok(())
+}
+
+/// Returns a new instance of [`Selection`] extended to the char matching the pair char under the cursor.
+/// If the cursor is not over a pair char, the first pair char after the cursor on the same line is used.
+pub fn selection_impl(selection: &Selection, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
+    selection.assert_invariants(text, semantics);
+    let Some(to) = move_cursor_matching_pair::matching_pair_index(selection.cursor(text, semantics), text) else{return Err(SelectionError::ResultsInSameState);};
+    selection.put_cursor(to, text, Movement::Extend, semantics, true)
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::extend_selection_matching_pair;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = extend_selection_matching_pair::document_impl(&mut doc, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(extend_selection_matching_pair::document_impl(&mut doc, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn to_matching_close_and_back(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2
+        // ( i d k [ s o m e ] ) \n
+        test(CursorSemantics::Bar, "(idk[some])\n", vec![(0, 0, None)], 0, vec![(0, 10, Some(10))], 0);
+        test(CursorSemantics::Bar, "(idk[some])\n", vec![(0, 10, None)], 0, vec![(0, 0, Some(0))], 0);
+        test(CursorSemantics::Block, "(idk[some])\n", vec![(4, 5, None)], 0, vec![(4, 10, Some(9))], 0);
+        test(CursorSemantics::Block, "(idk[some])\n", vec![(10, 11, None)], 0, vec![(11, 0, Some(0))], 0);
+    }
+    #[test] fn uses_first_pair_char_after_cursor_on_line(){
+        test(CursorSemantics::Bar, "idk (some)\n", vec![(1, 1, None)], 0, vec![(1, 9, Some(9))], 0);
+    }
+    #[test] fn errors_if_no_pair_char_on_rest_of_line(){
+        test_error(CursorSemantics::Block, "idk) some\n", vec![(5, 6, None)], 0);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{
+    selection_history::SelectionChange,
+    document::{Document, DocumentError},
+    selection::{Selection, SelectionError, CursorSemantics, Movement},
+    utilities::move_cursor_paragraph_end
+};
+use ropey::Rope;
+
+pub fn document_impl(document: &mut Document, count: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, |selection, text, semantics| selection_impl(selection, count, text, semantics)){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Jump);}
+        Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
+    }
+    Ok(())
+}
+
+/// Returns a new instance of [`Selection`] extended to the `count`th next paragraph end.
+/// The first is the end of the current paragraph, unless the cursor is already at the end of one, or between paragraphs.
+pub fn selection_impl(selection: &Selection, count: usize, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
+    selection.assert_invariants(text, semantics);
+    let Some(to) = move_cursor_paragraph_end::paragraph_end(selection.cursor(text, semantics), count, text) else{return Err(SelectionError::ResultsInSameState);};
+    selection.put_cursor(to, text, Movement::Extend, semantics, true)
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::extend_selection_paragraph_end;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        test_count(1, semantics, text, tuple_selections, primary, tuple_expected_selections, expected_primary);
+    }
+    fn test_count(count: usize, semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = extend_selection_paragraph_end::document_impl(&mut doc, count, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(extend_selection_paragraph_end::document_impl(&mut doc, 1, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn to_current_and_next_paragraph_end(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0
+        // i d k \n s o m e \n \n s h i t \n e l s e \n
+        test(CursorSemantics::Bar, "idk\nsome\n\nshit\nelse\n", vec![(1, 1, None)], 0, vec![(1, 8, Some(4))], 0);
+        test(CursorSemantics::Bar, "idk\nsome\n\nshit\nelse\n", vec![(1, 8, None)], 0, vec![(1, 19, Some(4))], 0);
+        test(CursorSemantics::Block, "idk\nsome\n\nshit\nelse\n", vec![(1, 2, None)], 0, vec![(1, 9, Some(4))], 0);
+    }
+    #[test] fn errors_when_no_paragraph_end_after_cursor(){
+        test_error(CursorSemantics::Bar, "idk\nsome\n\nshit\nelse\n", vec![(10, 19, None)], 0);
+    }
+
+    #[test] fn with_count(){
+        test_count(2, CursorSemantics::Bar, "idk\nsome\n\nshit\nelse\n", vec![(1, 1, None)], 0, vec![(1, 19, Some(4))], 0);
+    }
+}
@@ -1,24 +1,30 @@
 use crate::{
+    selection_history::SelectionChange,
     document::{Document, DocumentError},
     selection::{Selection, SelectionError, CursorSemantics, Movement},
     text_util,
 };
 use ropey::Rope;
 
-pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
-    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, selection_impl){
-        Ok(new_selections) => {document.selections = new_selections;}
+pub fn document_impl(document: &mut Document, count: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, |selection, text, semantics| selection_impl(selection, count, text, semantics)){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Step);}
         Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
     }
     Ok(())
 }
 
-/// Returns a new instance of [`Selection`] with cursor extended left to the nearest word boundary.
-pub fn selection_impl(selection: &Selection, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
+/// Returns a new instance of [`Selection`] with cursor extended left to the `count`th previous word boundary.
+pub fn selection_impl(selection: &Selection, count: usize, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
     selection.assert_invariants(text, semantics);
     if selection.cursor(text, semantics) == 0{return Err(SelectionError::ResultsInSameState);}
     
-    let goal_index = text_util::previous_word_boundary(selection.cursor(text, semantics), text);
+    let mut goal_index = selection.cursor(text, semantics);
+    for _ in 0..count{
+        let next = text_util::previous_word_boundary(goal_index, text);
+        if next == goal_index{break;}    //at text start
+        goal_index = next;
+    }
     selection.put_cursor(goal_index, text, Movement::Extend, semantics, true)
 }
 
@@ -52,6 +58,9 @@ mod tests{
     //    assert!(!doc.is_modified());
     //}
     fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        test_count(1, semantics, text, tuple_selections, primary, tuple_expected_selections, expected_primary);
+    }
+    fn test_count(count: usize, semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
         let text = Rope::from(text);
         let mut vec_selections = Vec::new();
         for tuple in tuple_selections{
@@ -61,7 +70,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        let result = extend_selection_word_boundary_backward::document_impl(&mut doc, semantics);
+        let result = extend_selection_word_boundary_backward::document_impl(&mut doc, count, semantics);
         assert!(!result.is_err());
         let mut vec_expected_selections = Vec::new();
         for tuple in tuple_expected_selections{
@@ -81,7 +90,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        assert!(extend_selection_word_boundary_backward::document_impl(&mut doc, semantics).is_err());
+        assert!(extend_selection_word_boundary_backward::document_impl(&mut doc, 1, semantics).is_err());
         assert!(!doc.is_modified());
     }
 
@@ -215,4 +224,9 @@ mod tests{
     //    assert!(Selection::new(Range::new(0, 1), Direction::Forward).extend_left_word_boundary(&text, CursorSemantics::Block).is_err());
     //    assert!(Selection::new(Range::new(0, 0), Direction::Forward).extend_left_word_boundary(&text, CursorSemantics::Bar).is_err());
     //}
-}
\ No newline at end of file
+
+    #[test] fn with_count(){
+        test_count(2, CursorSemantics::Bar, "idk some shit\n", vec![(13, 13, None)], 0, vec![(13, 4, Some(4))], 0);
+        test_count(2, CursorSemantics::Block, "idk some shit\n", vec![(12, 13, None)], 0, vec![(13, 4, Some(4))], 0);
+    }
+}
@@ -1,16 +1,18 @@
 use crate::{
+    selection_history::SelectionChange,
     document::{Document, DocumentError},
     selections::{Selections, SelectionsError},
     selection::{Selection, Direction, CursorSemantics},
-    range::Range
+    search::Search
 };
 use ropey::Rope;
-use regex::Regex;
 
 pub fn document_impl(document: &mut Document, search_text: &str, selections_before_search: &Selections, semantics: CursorSemantics) -> Result<(), DocumentError>{
     match selections_impl(selections_before_search, search_text, &document.text, semantics){
         Ok(new_selections) => {
-            document.selections = new_selections;
+            // every search in a run starts from selections_before_search, so the whole run is recorded as one jump from there
+            document.selections = selections_before_search.clone();
+            document.set_selections(new_selections, SelectionChange::Jump);
             Ok(())
         }
         Err(_) => {
@@ -52,7 +54,6 @@ pub fn selections_impl(selections: &Selections, input: &str, text: &Rope, semant
 /// Returns a [`Vec`] of [`Selection`]s where the underlying text is a match for the `input` search string.
 #[must_use] pub fn selection_impl(selection: &Selection, input: &str, text: &Rope) -> Vec<Selection>{   //text should be the text within a selection, not the whole document text       //TODO: -> Result<Vec<Selection>>
     let mut selections = Vec::new();
-    let start = selection.range.start;
 
     //match Regex::new(input){
     //    Ok(regex) => {
@@ -62,10 +63,9 @@ pub fn selections_impl(selections: &Selections, input: &str, text: &Rope, semant
     //    }
     //    Err(_) => {}    //return error FailedToParseRegex
     //}
-    if let Ok(regex) = Regex::new(input){
-        for search_match in regex.find_iter(&text.to_string()[start..selection.range.end.min(text.len_chars())]){
-            //selections.push(Selection::new(search_match.start().saturating_add(start), search_match.end().saturating_add(start)));
-            selections.push(Selection::new(Range::new(search_match.start().saturating_add(start), search_match.end().saturating_add(start)), Direction::Forward));
+    if let Ok(search) = Search::new(input){
+        for search_match in search.find_in(&selection.range, text){
+            selections.push(Selection::new(search_match, Direction::Forward));
         }
     }
     //else{/*return error FailedToParseRegex*/}
@@ -202,6 +202,19 @@ mod tests{
             ], 0    //is this correct?...
         );
     }
+    #[test] fn with_multibyte_chars_before_selection(){
+        test(
+            CursorSemantics::Block, 
+            "é😀\nidk\nsome\n", 
+            "s[oh]", 
+            vec![
+                (3, 11, None)
+            ], 0, 
+            vec![
+                (7, 9, None)
+            ], 0
+        );
+    }
     #[test] fn errors_if_all_selections_have_no_match(){
         //test_error(
         //    CursorSemantics::Block, 
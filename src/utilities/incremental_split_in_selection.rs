@@ -1,16 +1,19 @@
 use crate::{
+    selection_history::SelectionChange,
     document::{Document, DocumentError},
     selections::{Selections, SelectionsError},
     selection::{Selection, Direction, CursorSemantics},
-    range::Range
+    range::Range,
+    search::Search
 };
 use ropey::Rope;
-use regex::Regex;
 
 pub fn document_impl(document: &mut Document, search_text: &str, selections_before_split: &Selections, semantics: CursorSemantics) -> Result<(), DocumentError>{
     match selections_impl(selections_before_split, search_text, &document.text, semantics){
         Ok(new_selections) => {
-            document.selections = new_selections;
+            // every search in a run starts from selections_before_split, so the whole run is recorded as one jump from there
+            document.selections = selections_before_split.clone();
+            document.set_selections(new_selections, SelectionChange::Jump);
             Ok(())
         }
         Err(_) => {
@@ -58,18 +61,18 @@ fn selections_impl(selections: &Selections, input: &str, text: &Rope, semantics:
 /// Returns a [`Vec`] of [`Selection`]s containing each part of the current selection except the split pattern.
 #[must_use] fn selection_impl(selection: &Selection, pattern: &str, text: &Rope) -> Vec<Selection>{
     let mut selections = Vec::new();
-    if let Ok(regex) = Regex::new(pattern){
+    if let Ok(search) = Search::new(pattern){
         let mut start = selection.range.start; //0;
         let mut found_split = false;
         // Iter over each split, and push the retained selection before it, if any...       TODO: test split at start of selection
-        for split in regex.find_iter(&text.to_string()[selection.range.start..selection.range.end.min(text.len_chars())]){
+        for split in search.find_in(&selection.range, text){
             found_split = true;
-            let selection_range = Range::new(start, split.start().saturating_add(selection.range.start));
+            let selection_range = Range::new(start, split.start);
             if selection_range.start < selection_range.end{
                 //selections.push(Selection::new(selection_range.start, selection_range.end));
                 selections.push(Selection::new(Range::new(selection_range.start, selection_range.end), Direction::Forward));
             }
-            start = split.end().saturating_add(selection.range.start);
+            start = split.end;
         }
         // Handle any remaining text after the last split
         //if split found and end of last split < selection end
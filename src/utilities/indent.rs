@@ -0,0 +1,117 @@
+use crate::{
+    document::{Document, DocumentError},
+    selections::Selections,
+    selection::{Selection, CursorSemantics, Direction},
+    range::Range,
+    history::ChangeSet,
+    utilities::move_lines_up,
+};
+
+/// Inserts one indent level(a hard tab, or `tab_width` spaces) at the start of every non empty line touched by any [`Selection`].
+/// Selections keep covering the same text.
+/// Errors if every touched line is empty.
+pub fn document_impl(document: &mut Document, use_hard_tab: bool, tab_width: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    let indent = if use_hard_tab{"\t".to_string()}else{" ".repeat(tab_width)};
+    if indent.is_empty(){return Err(DocumentError::InvalidInput);}
+    let line_starts: Vec<usize> = move_lines_up::line_blocks(&document.selections, &document.text).into_iter()
+        .flat_map(|(first, last)| first..=last)
+        .filter(|&line| !document.text.line(line).chars().all(|char| char == '\n' || char == '\r'))
+        .map(|line| document.text.line_to_char(line))
+        .collect();
+    if line_starts.is_empty(){return Err(DocumentError::InvalidInput);}
+
+    let indent_len = indent.chars().count();
+    // indentation inserted at a selection's start is kept out of the selection
+    let shifted = |index: usize, include_line_start: bool| index + indent_len * line_starts.iter()
+        .filter(|&&line_start| line_start < index || (include_line_start && line_start == index))
+        .count();
+    let new_selections = document.selections.iter().map(|selection| {
+        let mut indented = selection.clone();
+        let start = shifted(selection.range.start, true);
+        indented.range = Range::new(start, shifted(selection.range.end, false).max(start));
+        indented
+    }).collect();
+
+    let selections_before_changes = document.selections.clone();
+    let mut changes = Vec::with_capacity(line_starts.len());
+    // bottom to top, so line starts above stay valid
+    for &line_start in line_starts.iter().rev(){
+        let mut cursor = Selection::new(Range::new(line_start, match semantics{CursorSemantics::Bar => line_start, CursorSemantics::Block => line_start + 1}), Direction::Forward);
+        changes.push(Document::apply_insert(&mut document.text, &indent, &mut cursor, semantics));
+    }
+
+    document.selections = Selections::new(new_selections, document.selections.primary_selection_index, &document.text, semantics);
+    document.push_change_set(ChangeSet::new(changes, selections_before_changes, document.selections.clone(), semantics), semantics);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{indent, undo};
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, use_hard_tab: bool, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        let result = indent::document_impl(&mut doc, use_hard_tab, 4, semantics);
+        assert!(result.is_ok());
+        let expected_text = Rope::from(expected_text);
+        assert_eq!(expected_text, doc.text);
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &expected_text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, primary, &expected_text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(doc.is_modified());
+        // a single undo restores the text and selections
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(text, doc.text);
+        assert_eq!(selections, doc.selections);
+    }
+
+    #[test] fn indents_cursor_line_with_soft_tab(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0
+        // i d k \n s o m e \n
+        test(CursorSemantics::Bar, false, "idk\nsome\n", vec![(1, 1, None)], 0, "    idk\nsome\n", vec![(5, 5, None)]);
+        test(CursorSemantics::Block, false, "idk\nsome\n", vec![(4, 5, None)], 0, "idk\n    some\n", vec![(8, 9, None)]);
+        test(CursorSemantics::Bar, false, "idk\nsome\n", vec![(0, 0, None)], 0, "    idk\nsome\n", vec![(4, 4, None)]);
+    }
+    #[test] fn indents_with_hard_tab(){
+        test(CursorSemantics::Bar, true, "idk\nsome\n", vec![(1, 1, None)], 0, "\tidk\nsome\n", vec![(2, 2, None)]);
+    }
+    #[test] fn indents_every_touched_line_and_keeps_selection_text(){
+        test(CursorSemantics::Bar, false, "idk\nsome\n", vec![(0, 8, None)], 0, "    idk\n    some\n", vec![(4, 16, None)]);
+        test(CursorSemantics::Block, false, "idk\nsome\n", vec![(8, 1, None)], 0, "    idk\n    some\n", vec![(16, 5, None)]);
+        // a selection ending at a line start doesn't touch that line
+        test(CursorSemantics::Bar, false, "idk\nsome\n", vec![(0, 4, None)], 0, "    idk\nsome\n", vec![(4, 8, None)]);
+    }
+    #[test] fn skips_empty_lines(){
+        test(CursorSemantics::Bar, false, "idk\n\nsome\n", vec![(0, 9, None)], 0, "    idk\n\n    some\n", vec![(4, 17, None)]);
+    }
+    #[test] fn with_multiple_selections_on_same_line(){
+        test(CursorSemantics::Bar, false, "idk some\n", vec![(0, 0, None), (4, 4, None)], 1, "    idk some\n", vec![(4, 4, None), (8, 8, None)]);
+    }
+    #[test] fn errors_if_only_empty_lines_touched(){
+        let semantics = CursorSemantics::Bar;
+        let text = Rope::from("\n\n");
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(Selections::new(vec![Selection::new_from_components(0, 0, None, &text, semantics)], 0, &text, semantics));
+        assert!(indent::document_impl(&mut doc, false, 4, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+}
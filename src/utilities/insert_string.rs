@@ -1,21 +1,49 @@
 use crate::{
     document::{Document, DocumentError},
-    selection::CursorSemantics,
+    selection::{Selection, CursorSemantics, Direction},
+    range::Range,
     history::{ChangeSet, Change, Operation},
     text_util
 };
+use ropey::Rope;
 
 /// Inserts provided string into text at each selection.
+/// A newline is followed by the current line's indentation, plus one indent level after an opening bracket.
+/// A closing bracket typed in a line's leading whitespace removes one indent level before it.
 pub fn document_impl(document: &mut Document, string: &str, use_hard_tab: bool, tab_width: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    if string.is_empty(){return Err(DocumentError::InvalidInput);}
+
+    let strings = vec![string; document.selections.count()];
+    insert_strings(document, &strings, use_hard_tab, tab_width, semantics);
+
+    Ok(())
+}
+
+/// Inserts `strings[i]` into text at the selection at index i. Selections with an empty string are left unchanged.
+/// # Errors
+/// Errors if `strings` does not hold one string per selection, or if every string is empty.
+pub fn document_impl_per_selection(document: &mut Document, strings: &[String], use_hard_tab: bool, tab_width: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    if strings.len() != document.selections.count() || strings.iter().all(String::is_empty){return Err(DocumentError::InvalidInput);}
+
+    let strings: Vec<&str> = strings.iter().map(String::as_str).collect();
+    insert_strings(document, &strings, use_hard_tab, tab_width, semantics);
+
+    Ok(())
+}
+
+fn insert_strings(document: &mut Document, strings: &[&str], use_hard_tab: bool, tab_width: usize, semantics: CursorSemantics){
     let selections_before_changes = document.selections.clone();
     let mut changes = Vec::new();
 
-    if string.is_empty(){return Err(DocumentError::InvalidInput);}
-
-    for i in 0..document.selections.count(){
+    for (i, string) in strings.iter().enumerate(){
         let selection = document.selections.nth_mut(i);
-        let change = match string{
-            //"\n" => {}    //handle behavior specific to pressing "enter". auto-indent, etc... //TODO: create tests for newline behavior...
+        let change = match *string{
+            "" => Change::new(Operation::NoOp, selection.clone(), selection.clone(), Operation::NoOp),
+            "\n" => {   //handle behavior specific to pressing "enter". auto-indent
+                let indented_newline = format!("\n{}", indentation(selection.range.start, use_hard_tab, tab_width, &document.text));
+                if selection.is_extended(semantics){handle_insert_replace(document, i, semantics, &indented_newline)}
+                else{handle_insert(document, &indented_newline, i, semantics)}
+            }
             "\t" => {   //handle behavior specific to pressing "tab".
                 if use_hard_tab{
                     if selection.is_extended(semantics){handle_insert_replace(document, i, semantics, "\t")}
@@ -30,8 +58,15 @@ pub fn document_impl(document: &mut Document, string: &str, use_hard_tab: bool,
                     else{handle_insert(document, &soft_tab, i, semantics)}
                 }
             }
+            ")" | "]" | "}" if !selection.is_extended(semantics) && dedent_distance(selection.range.start, use_hard_tab, tab_width, &document.text) > 0 => {
+                // replace one indent level before the cursor with the closing bracket
+                let cursor = selection.range.start;
+                let distance = dedent_distance(cursor, use_hard_tab, tab_width, &document.text);
+                *selection = Selection::new(Range::new(cursor - distance, cursor), Direction::Forward);
+                handle_insert_replace(document, i, semantics, string)
+            }
             //handle any other inserted string
-            _ => {
+            string => {
                 if selection.is_extended(semantics){handle_insert_replace(document, i, semantics, string)}
                 else{handle_insert(document, string, i, semantics)}
             }
@@ -40,22 +75,44 @@ pub fn document_impl(document: &mut Document, string: &str, use_hard_tab: bool,
         changes.push(change);
     }
 
-    // push change set to undo stack
-    document.undo_stack.push(ChangeSet::new(changes, selections_before_changes, document.selections.clone()));
-
-    // clear redo stack. new actions invalidate the redo history
-    document.redo_stack.clear();
-
-    Ok(())
+    // push change set to undo stack, grouped with any previous change set from the same typing/deletion run
+    let mut change_set = ChangeSet::new(changes, selections_before_changes, document.selections.clone(), semantics);
+    change_set.group_with(document.undo_tree.next_undo(), semantics);
+    document.push_change_set(change_set, semantics);
+}
+/// Returns the indentation for a new line inserted at `index`: the leading whitespace of `index`'s line, before `index`,
+/// plus one indent level if the char before `index` is an opening bracket.
+fn indentation(index: usize, use_hard_tab: bool, tab_width: usize, text: &Rope) -> String{
+    let line_start = text.line_to_char(text.char_to_line(index));
+    let leading_whitespace = text_util::first_non_whitespace_character_offset(text.line(text.char_to_line(index))).min(index - line_start);
+    let mut indentation = text.slice(line_start..line_start + leading_whitespace).to_string();
+    if index > 0 && matches!(text.char(index - 1), '(' | '[' | '{'){
+        if use_hard_tab{indentation.push('\t');}
+        else{indentation.push_str(&" ".repeat(tab_width));}
+    }
+    indentation
+}
+/// Returns the number of chars making up one indent level directly before `cursor`, if only whitespace precedes `cursor` on its line, or 0.
+/// A soft tab is dedented to the previous multiple of `tab_width`.
+fn dedent_distance(cursor: usize, use_hard_tab: bool, tab_width: usize, text: &Rope) -> usize{
+    let line_start = text.line_to_char(text.char_to_line(cursor));
+    if cursor == line_start || !text.slice(line_start..cursor).chars().all(|char| char == ' ' || char == '\t'){return 0;}
+    if use_hard_tab || text.char(cursor - 1) == '\t'{
+        usize::from(text.char(cursor - 1) == '\t')
+    }else{
+        let to_previous_multiple = match (cursor - line_start) % tab_width.max(1){0 => tab_width, remainder => remainder};
+        (line_start..cursor).rev().take_while(|&index| text.char(index) == ' ').count().min(to_previous_multiple)
+    }
 }
 fn handle_insert_replace(document: &mut Document, current_selection_index: usize, semantics: CursorSemantics, new_text: &str) -> Change{
     use std::cmp::Ordering;
     let selection = document.selections.nth_mut(current_selection_index);
     let change = Document::apply_replace(&mut document.text, new_text, selection, semantics);
     if let Operation::Replace{replacement_text} = change.inverse(){
-        match replacement_text.len().cmp(&new_text.len()){    //old selected text vs new text
-            Ordering::Greater => {document.selections.shift_subsequent_selections_backward(current_selection_index, replacement_text.len().saturating_sub(new_text.len()));}
-            Ordering::Less => {document.selections.shift_subsequent_selections_forward(current_selection_index, new_text.len().saturating_sub(replacement_text.len()));}
+        let (replaced_len, new_len) = (replacement_text.chars().count(), new_text.chars().count());
+        match replaced_len.cmp(&new_len){    //old selected text vs new text
+            Ordering::Greater => {document.selections.shift_subsequent_selections_backward(current_selection_index, replaced_len.saturating_sub(new_len));}
+            Ordering::Less => {document.selections.shift_subsequent_selections_forward(current_selection_index, new_len.saturating_sub(replaced_len));}
             Ordering::Equal => {}   // no change to subsequent selections
         }
     }
@@ -64,13 +121,13 @@ fn handle_insert_replace(document: &mut Document, current_selection_index: usize
 fn handle_insert(document: &mut Document, string: &str, current_selection_index: usize, semantics: CursorSemantics) -> Change{
     let selection = document.selections.nth_mut(current_selection_index);
     let change = Document::apply_insert(&mut document.text, string, selection, semantics);
-    document.selections.shift_subsequent_selections_forward(current_selection_index, string.len());
+    document.selections.shift_subsequent_selections_forward(current_selection_index, string.chars().count());
     change
 }
 
 #[cfg(test)]
 mod tests{
-    use crate::utilities::insert_string;
+    use crate::utilities::{insert_string, undo};
     use ropey::Rope;
     use crate::{
         document::Document,
@@ -196,9 +253,159 @@ mod tests{
         );
     }
     
+    #[test] fn insert_multi_byte_char_with_multi_selection_block_semantics(){
+        test(
+            CursorSemantics::Block, 
+            "idk\nsome\n", 
+            vec![
+                (0, 1, None),
+                (4, 5, None)
+            ], 0, 
+            "\u{e9}", 
+            "\u{e9}idk\n\u{e9}some\n", 
+            vec![
+                (1, 2, Some(1)),
+                (6, 7, Some(1))
+            ], 0
+        );
+    }
+
+    #[test] fn insert_multi_byte_char_with_multi_selection_bar_semantics(){
+        test(
+            CursorSemantics::Bar, 
+            "idk\nsome\n", 
+            vec![
+                (0, 0, None),
+                (4, 4, None)
+            ], 0, 
+            "\u{e9}", 
+            "\u{e9}idk\n\u{e9}some\n", 
+            vec![
+                (1, 1, Some(1)),
+                (6, 6, Some(1))
+            ], 0
+        );
+    }
+
+    #[test] fn insert_zwj_sequence_with_multi_selection_block_semantics(){
+        test(
+            CursorSemantics::Block, 
+            "idk\nsome\n", 
+            vec![
+                (0, 1, None),
+                (4, 5, None)
+            ], 0, 
+            "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}", 
+            "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}idk\n\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}some\n", 
+            vec![
+                (5, 6, Some(5)),
+                (14, 15, Some(5))
+            ], 0
+        );
+    }
+
+    #[test] fn insert_zwj_sequence_with_multi_selection_bar_semantics(){
+        test(
+            CursorSemantics::Bar, 
+            "idk\nsome\n", 
+            vec![
+                (0, 0, None),
+                (4, 4, None)
+            ], 0, 
+            "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}", 
+            "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}idk\n\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}some\n", 
+            vec![
+                (5, 5, Some(5)),
+                (14, 14, Some(5))
+            ], 0
+        );
+    }
+
+    #[test] fn insert_combining_mark_sequence_with_multi_selection_block_semantics(){
+        test(
+            CursorSemantics::Block, 
+            "idk\nsome\n", 
+            vec![
+                (0, 1, None),
+                (4, 5, None)
+            ], 0, 
+            "e\u{301}", 
+            "e\u{301}idk\ne\u{301}some\n", 
+            vec![
+                (2, 3, Some(2)),
+                (8, 9, Some(2))
+            ], 0
+        );
+    }
+
+    #[test] fn insert_combining_mark_sequence_with_multi_selection_bar_semantics(){
+        test(
+            CursorSemantics::Bar, 
+            "idk\nsome\n", 
+            vec![
+                (0, 0, None),
+                (4, 4, None)
+            ], 0, 
+            "e\u{301}", 
+            "e\u{301}idk\ne\u{301}some\n", 
+            vec![
+                (2, 2, Some(2)),
+                (8, 8, Some(2))
+            ], 0
+        );
+    }
+
     // TODO: insert multi-char with multi selection bar/block semantics
     //TODO: test insert tab (hard/soft/tab width)
-    //TODO: test insert newline
+
+    #[test] fn newline_copies_previous_line_indentation(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0
+        //         i d k \n
+        test(CursorSemantics::Bar, "    idk\n", vec![(7, 7, None)], 0, "\n", "    idk\n    \n", vec![(12, 12, Some(4))], 0);
+        test(CursorSemantics::Block, "    idk\n", vec![(7, 8, None)], 0, "\n", "    idk\n    \n", vec![(12, 13, Some(4))], 0);
+        // only the indentation before the cursor is copied
+        test(CursorSemantics::Bar, "    idk\n", vec![(2, 2, None)], 0, "\n", "  \n    idk\n", vec![(5, 5, Some(2))], 0);
+    }
+    #[test] fn newline_after_opening_bracket_adds_indent_level(){
+        test(CursorSemantics::Bar, "  if idk{\n", vec![(9, 9, None)], 0, "\n", "  if idk{\n      \n", vec![(16, 16, Some(6))], 0);
+        test(CursorSemantics::Block, "(\n", vec![(1, 2, None)], 0, "\n", "(\n    \n", vec![(6, 7, Some(4))], 0);
+    }
+    #[test] fn newline_with_hard_tab_indentation(){
+        let semantics = CursorSemantics::Bar;
+        let text = Rope::from("\tidk[\n");
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(Selections::new(vec![Selection::new_from_components(5, 5, None, &text, semantics)], 0, &text, semantics));
+        assert!(insert_string::document_impl(&mut doc, "\n", true, 4, semantics).is_ok());
+        assert_eq!(Rope::from("\tidk[\n\t\t\n"), doc.text);
+        assert!(insert_string::document_impl(&mut doc, "]", true, 4, semantics).is_ok());
+        assert_eq!(Rope::from("\tidk[\n\t]\n"), doc.text);
+    }
+    #[test] fn closing_bracket_at_line_start_removes_indent_level(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0
+        // {                 \n
+        test(CursorSemantics::Bar, "{\n        \n", vec![(10, 10, None)], 0, "}", "{\n    }\n", vec![(7, 7, Some(5))], 0);
+        test(CursorSemantics::Block, "{\n        \n", vec![(10, 11, None)], 0, "}", "{\n    }\n", vec![(7, 8, Some(5))], 0);
+        // dedents to the previous multiple of tab width
+        test(CursorSemantics::Bar, "{\n      \n", vec![(8, 8, None)], 0, ")", "{\n    )\n", vec![(7, 7, Some(5))], 0);
+        // not dedented after text
+        test(CursorSemantics::Bar, "    idk\n", vec![(7, 7, None)], 0, "]", "    idk]\n", vec![(8, 8, Some(8))], 0);
+    }
+    #[test] fn dedent_is_undone_with_a_single_undo(){
+        let semantics = CursorSemantics::Block;
+        let text = Rope::from("{\n    \n");
+        let selections = Selections::new(vec![Selection::new_from_components(6, 7, None, &text, semantics)], 0, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        assert!(insert_string::document_impl(&mut doc, "}", false, 4, semantics).is_ok());
+        assert_eq!(Rope::from("{\n}\n"), doc.text);
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(text, doc.text);
+        assert_eq!(selections, doc.selections);
+    }
     
     #[test] fn errors_if_empty_insert_string(){
         //test_error(
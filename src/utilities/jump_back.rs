@@ -0,0 +1,78 @@
+use crate::document::{Document, DocumentError};
+
+/// Restores the selections most recently replaced by a selection action. Runs of small movements are jumped over as a whole.
+/// The current selections can be returned to with [`jump_forward`](crate::utilities::jump_forward).
+pub fn document_impl(document: &mut Document) -> Result<(), DocumentError>{
+    match document.selection_history.jump_back(document.selections.clone()){
+        Some(selections) => {document.selections = selections;}
+        None => {return Err(DocumentError::NoEarlierSelections);}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{jump_back, move_cursor_right, move_cursor_down, move_cursor_document_end, select_all, insert_string};
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn document(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize) -> Document{
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        Document::new(semantics)
+            .with_text(text)
+            .with_selections(selections)
+    }
+    fn assert_selections(doc: &Document, tuple_expected_ranges: Vec<(usize, usize)>){
+        let ranges: Vec<(usize, usize)> = doc.selections.iter().map(|selection| (selection.range.start, selection.range.end)).collect();
+        assert_eq!(tuple_expected_ranges, ranges);
+    }
+
+    #[test] fn jumps_back_over_run_of_steps(){
+        let semantics = CursorSemantics::Bar;
+        let mut doc = document(semantics, "idk\nsome\nshit\n", vec![(0, 0, None)], 0);
+        assert!(move_cursor_right::document_impl(&mut doc, 1, semantics).is_ok());
+        assert!(move_cursor_right::document_impl(&mut doc, 1, semantics).is_ok());
+        assert!(move_cursor_down::document_impl(&mut doc, 1, semantics).is_ok());
+        assert!(jump_back::document_impl(&mut doc).is_ok());
+        assert_selections(&doc, vec![(0, 0)]);
+        assert!(jump_back::document_impl(&mut doc).is_err());
+    }
+    #[test] fn jumps_back_through_each_jump(){
+        let semantics = CursorSemantics::Block;
+        let mut doc = document(semantics, "idk\nsome\nshit\n", vec![(0, 1, None)], 0);
+        assert!(move_cursor_right::document_impl(&mut doc, 1, semantics).is_ok());
+        assert!(select_all::document_impl(&mut doc, semantics).is_ok());
+        assert!(move_cursor_document_end::document_impl(&mut doc, semantics).is_ok());
+        assert!(jump_back::document_impl(&mut doc).is_ok());
+        assert_selections(&doc, vec![(0, 14)]);
+        assert!(jump_back::document_impl(&mut doc).is_ok());
+        assert_selections(&doc, vec![(1, 2)]);
+        assert!(jump_back::document_impl(&mut doc).is_ok());
+        assert_selections(&doc, vec![(0, 1)]);
+    }
+    #[test] fn recorded_selections_follow_text_edits(){
+        let semantics = CursorSemantics::Bar;
+        let mut doc = document(semantics, "idk\nsome\nshit\n", vec![(4, 4, None)], 0);
+        assert!(move_cursor_document_end::document_impl(&mut doc, semantics).is_ok());
+        assert!(insert_string::document_impl(&mut doc, "x", false, 4, semantics).is_ok());
+        assert!(select_all::document_impl(&mut doc, semantics).is_ok());
+        assert!(jump_back::document_impl(&mut doc).is_ok());
+        assert_selections(&doc, vec![(15, 15)]);
+        assert!(jump_back::document_impl(&mut doc).is_ok());
+        assert_selections(&doc, vec![(4, 4)]);
+    }
+    #[test] fn errors_without_selection_history(){
+        let semantics = CursorSemantics::Bar;
+        let mut doc = document(semantics, "idk\n", vec![(0, 0, None)], 0);
+        assert!(jump_back::document_impl(&mut doc).is_err());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::document::{Document, DocumentError};
+
+/// Restores the selections most recently jumped back from with [`jump_back`](crate::utilities::jump_back).
+/// Any selection action after jumping back discards the selections that could be jumped forward to.
+pub fn document_impl(document: &mut Document) -> Result<(), DocumentError>{
+    match document.selection_history.jump_forward(document.selections.clone()){
+        Some(selections) => {document.selections = selections;}
+        None => {return Err(DocumentError::NoLaterSelections);}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{jump_forward, jump_back, move_cursor_document_end, select_all};
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn document(semantics: CursorSemantics, text: &str) -> Document{
+        let text = Rope::from(text);
+        let selections = Selections::new(vec![Selection::new_from_components(0, usize::from(semantics == CursorSemantics::Block), None, &text, semantics)], 0, &text, semantics);
+        Document::new(semantics)
+            .with_text(text)
+            .with_selections(selections)
+    }
+
+    #[test] fn jump_forward_reverses_jump_back(){
+        for semantics in [CursorSemantics::Bar, CursorSemantics::Block]{
+            let mut doc = document(semantics, "idk\nsome\nshit\n");
+            assert!(move_cursor_document_end::document_impl(&mut doc, semantics).is_ok());
+            assert!(select_all::document_impl(&mut doc, semantics).is_ok());
+            let latest = doc.selections.clone();
+            assert!(jump_back::document_impl(&mut doc).is_ok());
+            assert!(jump_back::document_impl(&mut doc).is_ok());
+            assert!(jump_forward::document_impl(&mut doc).is_ok());
+            assert!(jump_forward::document_impl(&mut doc).is_ok());
+            assert_eq!(latest, doc.selections);
+            assert!(jump_forward::document_impl(&mut doc).is_err());
+        }
+    }
+    #[test] fn selection_action_discards_forward_history(){
+        for semantics in [CursorSemantics::Bar, CursorSemantics::Block]{
+            let mut doc = document(semantics, "idk\nsome\nshit\n");
+            assert!(move_cursor_document_end::document_impl(&mut doc, semantics).is_ok());
+            assert!(jump_back::document_impl(&mut doc).is_ok());
+            assert!(select_all::document_impl(&mut doc, semantics).is_ok());
+            assert!(jump_forward::document_impl(&mut doc).is_err());
+        }
+    }
+}
@@ -10,6 +10,9 @@ pub mod move_cursor_line_end;
 pub mod move_cursor_line_start;
 pub mod move_cursor_line_text_start;
 pub mod move_cursor_home;
+pub mod move_cursor_matching_pair;
+pub mod move_cursor_paragraph_start;
+pub mod move_cursor_paragraph_end;
 pub mod move_cursor_document_start;
 pub mod move_cursor_document_end;
 pub mod move_cursor_page_up;
@@ -24,13 +27,25 @@ pub mod extend_selection_line_end;  //may rename to extend_selection_line_text_e
 pub mod extend_selection_line_start;
 pub mod extend_selection_line_text_start;
 pub mod extend_selection_home;
+pub mod extend_selection_matching_pair;
+pub mod extend_selection_paragraph_start;
+pub mod extend_selection_paragraph_end;
 pub mod select_line;
 pub mod select_all;
+pub mod select_word;
+pub mod select_long_word;
+pub mod select_sentence;
+pub mod select_paragraph;
 pub mod flip_direction;
 pub mod surround;
 pub mod nearest_surrounding_pair;   //may rename to nearest bracket pair, and have separate nearest quote pair fn
+pub mod select_surrounding_pair;
 pub mod incremental_search_in_selection;
 pub mod incremental_split_in_selection;
+pub mod search_next;
+pub mod search_previous;
+pub mod add_selection_next_match;
+pub mod select_all_matches;
 pub mod collapse_selections_to_cursor;
     //collapse selections to anchor
 pub mod clear_non_primary_selections;
@@ -39,6 +54,8 @@ pub mod add_selection_below;
 pub mod remove_primary_selection;
 pub mod increment_primary_selection;
 pub mod decrement_primary_selection;
+pub mod jump_back;
+pub mod jump_forward;
 
 //view actions
 pub mod scroll_view_up;
@@ -61,10 +78,23 @@ pub mod insert_string;
 pub mod add_surrounding_pair;
 pub mod undo;
 pub mod redo;
-    //swap selected text with line above
-    //swap selected text with line below
-    //align selected text vertically
-    //rotate text between selections
+pub mod undo_earlier;
+pub mod undo_later;
+pub mod next_undo_branch;
+pub mod previous_undo_branch;
+pub mod move_lines_up;
+pub mod move_lines_down;
+pub mod indent;
+pub mod dedent;
+pub mod convert_indentation;
+pub mod pipe_selections;
+pub mod complete_word;
+pub mod align_selections;
+pub mod rotate_selection_contents_forward;
+pub mod rotate_selection_contents_backward;
+pub mod reverse_selection_contents;
+pub mod sort_selection_contents;
+pub mod shuffle_selection_contents;
 
 //editor actions
 pub mod save;
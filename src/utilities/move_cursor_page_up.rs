@@ -1,23 +1,24 @@
 use crate::{
+    selection_history::SelectionChange,
     document::{Document, DocumentError},
     selection::{Selection, SelectionError, CursorSemantics, Direction, Movement},
     view::View,
 };
 use ropey::Rope;
 
-pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
-    match document.selections.move_cursor_page(&document.text, &document.client_view, semantics, selection_impl){
-        Ok(new_selections) => {document.selections = new_selections;}
+pub fn document_impl(document: &mut Document, count: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_page(&document.text, &document.client_view, semantics, |selection, text, client_view, semantics| selection_impl(selection, count, text, client_view, semantics)){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Jump);}
         Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
     }
     Ok(())
 }
 
-/// Returns a new instance of [`Selection`] with the cursor moved up by the height of `client_view`.
-pub fn selection_impl(selection: &Selection, text: &Rope, client_view: &View, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
+/// Returns a new instance of [`Selection`] with the cursor moved up `count` times the height of `client_view`.
+pub fn selection_impl(selection: &Selection, count: usize, text: &Rope, client_view: &View, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
     selection.assert_invariants(text, semantics);
     if text.char_to_line(selection.cursor(text, semantics)) == 0{return Err(SelectionError::ResultsInSameState);}
-    selection.move_vertically(client_view.height().saturating_sub(1), text, Movement::Move, Direction::Backward, semantics)
+    selection.move_vertically(client_view.height().saturating_sub(1).saturating_mul(count), text, Movement::Move, Direction::Backward, semantics)
 }
 
 #[cfg(test)]
@@ -0,0 +1,112 @@
+use crate::{
+    selection_history::SelectionChange,
+    document::{Document, DocumentError},
+    selection::{Selection, SelectionError, CursorSemantics, Movement},
+    text_util
+};
+use ropey::Rope;
+
+pub fn document_impl(document: &mut Document, count: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, |selection, text, semantics| selection_impl(selection, count, text, semantics)){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Jump);}
+        Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
+    }
+    Ok(())
+}
+
+/// Returns a new instance of [`Selection`] with the cursor moved to the `count`th previous paragraph start.
+/// The first is the start of the current paragraph, unless already at the start of one, or between paragraphs.
+pub fn selection_impl(selection: &Selection, count: usize, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
+    selection.assert_invariants(text, semantics);
+    let Some(to) = paragraph_start(selection.cursor(text, semantics), count, text) else{return Err(SelectionError::ResultsInSameState);};
+    selection.put_cursor(to, text, Movement::Move, semantics, true)
+}
+
+/// Returns the index `count` paragraph starts before `cursor`, or as many as there are, if fewer. Returns `None` if there are none.
+/// The first is the start of the paragraph containing `cursor`, if `cursor` is after it. A paragraph is a block of non blank lines.
+pub(crate) fn paragraph_start(cursor: usize, count: usize, text: &Rope) -> Option<usize>{
+    let mut index = cursor;
+    for _ in 0..count{
+        let line = text.char_to_line(index);
+        if !text_util::is_blank_line(text.line(line)){
+            let start = text.line_to_char(text_util::paragraph_lines(line, text).0);
+            if index > start{index = start; continue;}
+        }
+        let Some(previous) = (0..line).rev().find(|&line| !text_util::is_blank_line(text.line(line))) else{break;};
+        index = text.line_to_char(text_util::paragraph_lines(previous, text).0);
+    }
+    (index != cursor).then_some(index)
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::move_cursor_paragraph_start;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        test_count(1, semantics, text, tuple_selections, primary, tuple_expected_selections, expected_primary);
+    }
+    fn test_count(count: usize, semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = move_cursor_paragraph_start::document_impl(&mut doc, count, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(move_cursor_paragraph_start::document_impl(&mut doc, 1, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn to_current_paragraph_start(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0
+        // i d k \n s o m e \n \n s h i t \n e l s e \n
+        test(CursorSemantics::Bar, "idk\nsome\n\nshit\nelse\n", vec![(12, 12, None)], 0, vec![(10, 10, Some(0))], 0);
+        test(CursorSemantics::Block, "idk\nsome\n\nshit\nelse\n", vec![(17, 18, None)], 0, vec![(10, 11, Some(0))], 0);
+    }
+    #[test] fn to_previous_paragraph_start_from_paragraph_start_or_blank_line(){
+        test(CursorSemantics::Bar, "idk\nsome\n\nshit\nelse\n", vec![(10, 10, None)], 0, vec![(0, 0, Some(0))], 0);
+        test(CursorSemantics::Block, "idk\nsome\n\nshit\nelse\n", vec![(9, 10, None)], 0, vec![(0, 1, Some(0))], 0);
+        test(CursorSemantics::Bar, "idk\nsome\n\nshit\nelse\n", vec![(20, 20, None)], 0, vec![(10, 10, Some(0))], 0);
+    }
+    #[test] fn with_multiple_selections(){
+        test(CursorSemantics::Block, "idk\nsome\n\nshit\nelse\n", vec![(1, 2, None), (5, 6, None), (15, 16, None)], 2, vec![(0, 1, Some(0)), (10, 11, Some(0))], 1);
+    }
+    #[test] fn errors_when_no_paragraph_start_before_cursor(){
+        test_error(CursorSemantics::Bar, "idk\nsome\n\nshit\nelse\n", vec![(0, 0, None)], 0);
+        test_error(CursorSemantics::Block, "\n\nidk\n", vec![(1, 2, None)], 0);
+    }
+
+    #[test] fn with_count(){
+        test_count(2, CursorSemantics::Bar, "idk\nsome\n\nshit\nelse\n", vec![(12, 12, None)], 0, vec![(0, 0, Some(0))], 0);
+        test_count(5, CursorSemantics::Block, "idk\nsome\n\nshit\nelse\n", vec![(20, 21, None)], 0, vec![(0, 1, Some(0))], 0);  //stops at first paragraph
+    }
+}
@@ -1,24 +1,30 @@
 use crate::{
+    selection_history::SelectionChange,
     document::{Document, DocumentError},
     selection::{Selection, SelectionError, CursorSemantics, Movement},
     text_util
 };
 use ropey::Rope;
 
-pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
-    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, selection_impl){
-        Ok(new_selections) => {document.selections = new_selections;}
+pub fn document_impl(document: &mut Document, count: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, |selection, text, semantics| selection_impl(selection, count, text, semantics)){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Step);}
         Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
     }
     Ok(())
 }
 
-/// Returns a new instance of [`Selection`] with cursor moved right to the nearest word boundary.
-pub fn selection_impl(selection: &Selection, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
+/// Returns a new instance of [`Selection`] with cursor moved right to the `count`th next word boundary.
+pub fn selection_impl(selection: &Selection, count: usize, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
     selection.assert_invariants(text, semantics);
     if selection.cursor(text, semantics) == text.len_chars(){return Err(SelectionError::ResultsInSameState);}
     
-    let goal_index = text_util::next_word_boundary(selection.head(), text);
+    let mut goal_index = selection.head();
+    for _ in 0..count{
+        let next = text_util::next_word_boundary(goal_index, text);
+        if next == goal_index{break;}    //at text end
+        goal_index = next;
+    }
     match semantics{
         CursorSemantics::Bar => {
             selection.put_cursor(goal_index, text, Movement::Move, semantics, true)
@@ -63,6 +69,9 @@ mod tests{
     //    assert!(!doc.is_modified());
     //}
     fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        test_count(1, semantics, text, tuple_selections, primary, tuple_expected_selections, expected_primary);
+    }
+    fn test_count(count: usize, semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
         let text = Rope::from(text);
         let mut vec_selections = Vec::new();
         for tuple in tuple_selections{
@@ -72,7 +81,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        let result = move_cursor_word_boundary_forward::document_impl(&mut doc, semantics);
+        let result = move_cursor_word_boundary_forward::document_impl(&mut doc, count, semantics);
         assert!(!result.is_err());
         let mut vec_expected_selections = Vec::new();
         for tuple in tuple_expected_selections{
@@ -92,7 +101,7 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections);
-        assert!(move_cursor_word_boundary_forward::document_impl(&mut doc, semantics).is_err());
+        assert!(move_cursor_word_boundary_forward::document_impl(&mut doc, 1, semantics).is_err());
         assert!(!doc.is_modified());
     }
 
@@ -260,4 +269,10 @@ mod tests{
             ], 0
         );
     }
+
+    #[test] fn with_count(){
+        test_count(2, CursorSemantics::Bar, "idk some shit\n", vec![(0, 0, None)], 0, vec![(8, 8, Some(8))], 0);
+        test_count(2, CursorSemantics::Block, "idk some shit\n", vec![(0, 1, None)], 0, vec![(7, 8, Some(7))], 0);
+        test_count(usize::MAX, CursorSemantics::Bar, "idk some shit\n", vec![(0, 0, None)], 0, vec![(14, 14, Some(0))], 0);  //stops at doc end
+    }
 }
@@ -0,0 +1,89 @@
+use crate::{
+    document::{Document, DocumentError},
+    selection::{CursorSemantics, Direction},
+    utilities::move_lines_up,
+};
+
+/// Swaps every line touched by a [`Selection`](crate::selection::Selection) with the line below it. Selections stay with their text.
+/// Errors if the bottom-most selected line is the last line.
+pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    move_lines_up::move_lines(document, Direction::Forward, semantics)
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{move_lines_down, undo};
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        let result = move_lines_down::document_impl(&mut doc, semantics);
+        assert!(result.is_ok());
+        let expected_text = Rope::from(expected_text);
+        assert_eq!(expected_text, doc.text);
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &expected_text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &expected_text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(doc.is_modified());
+        // a single undo restores the text and selections
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(text, doc.text);
+        assert_eq!(selections, doc.selections);
+        assert!(doc.undo_stack().is_empty());
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(move_lines_down::document_impl(&mut doc, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn moves_cursor_line_down(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3
+        // i d k \n s o m e \n s h i t \n
+        test(CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(6, 6, None)], 0, "idk\nshit\nsome\n", vec![(11, 11, None)], 0);
+        test(CursorSemantics::Block, "idk\nsome\nshit\n", vec![(6, 7, None)], 0, "idk\nshit\nsome\n", vec![(11, 12, None)], 0);
+    }
+    #[test] fn moves_every_line_of_extended_selection_down(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(1, 6, None)], 0, "shit\nidk\nsome\n", vec![(6, 11, None)], 0);
+        test(CursorSemantics::Block, "idk\nsome\nshit\n", vec![(6, 1, None)], 0, "shit\nidk\nsome\n", vec![(11, 6, None)], 0);
+    }
+    #[test] fn merges_blocks_of_adjacent_selections(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(1, 1, None), (5, 5, None)], 0, "shit\nidk\nsome\n", vec![(6, 6, None), (10, 10, None)], 0);
+    }
+    #[test] fn moves_separate_blocks_independently(){
+        // i d k \n s o m e \n s h i t \n e l s e \n
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nelse\n", vec![(1, 1, None), (10, 10, None)], 1, "some\nidk\nelse\nshit\n", vec![(6, 6, None), (15, 15, None)], 1);
+    }
+    #[test] fn keeps_last_line_without_newline_last(){
+        test(CursorSemantics::Bar, "idk\nsome", vec![(1, 1, None)], 0, "some\nidk", vec![(6, 6, None)], 0);
+    }
+    #[test] fn errors_at_last_line(){
+        test_error(CursorSemantics::Bar, "idk\nsome\n", vec![(5, 5, None)], 0);
+        test_error(CursorSemantics::Block, "idk\nsome", vec![(1, 2, None), (5, 6, None)], 0);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{
+    document::{Document, DocumentError},
+    selections::Selections,
+    selection::{Selection, CursorSemantics, Direction},
+    range::Range,
+    history::ChangeSet,
+};
+use ropey::Rope;
+
+/// Swaps every line touched by a [`Selection`] with the line above it. Selections stay with their text.
+/// Errors if the top-most selected line is the first line.
+pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    move_lines(document, Direction::Backward, semantics)
+}
+
+/// Moves the blocks of lines touched by any [`Selection`] one line up(`Direction::Backward`) or down(`Direction::Forward`),
+/// by swapping each block with the line next to it. Blocks from multiple selections that touch are moved as one.
+/// All swaps are recorded as a single [`ChangeSet`].
+pub(crate) fn move_lines(document: &mut Document, direction: Direction, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    let blocks = line_blocks(&document.selections, &document.text);
+    let can_move = match direction{
+        Direction::Backward => blocks.first().is_some_and(|&(first, _)| first > 0),
+        // the line below must have content. the empty line after a trailing newline can't be swapped with
+        Direction::Forward => blocks.last().is_some_and(|&(_, last)| last + 1 < document.text.len_lines() && document.text.line(last + 1).len_chars() > 0)
+    };
+    if !can_move{return Err(DocumentError::SelectionAtDocBounds);}
+
+    let selections_before_changes = document.selections.clone();
+    let mut changes = Vec::with_capacity(blocks.len());
+    let mut new_selections = Vec::with_capacity(document.selections.count());
+    let mut selections = document.selections.iter().peekable();
+    for (first, last) in blocks{
+        let (other_line, block_start, block_end) = (
+            if direction == Direction::Backward{first - 1}else{last + 1},
+            document.text.line_to_char(first),
+            document.text.line_to_char(last) + document.text.line(last).len_chars()
+        );
+        let other_start = document.text.line_to_char(other_line);
+        let other_end = other_start + document.text.line(other_line).len_chars();
+        let mut block = document.text.slice(block_start..block_end).to_string();
+        let mut other = document.text.slice(other_start..other_end).to_string();
+        // the last line has no newline. keep it last, by moving the newline to the line that becomes last
+        if !block.ends_with('\n'){
+            block.push('\n');
+            other.pop();
+        }else if !other.ends_with('\n'){
+            other.push('\n');
+            block.pop();
+        }
+        let (start, end, replacement, offset) = match direction{
+            Direction::Backward => (other_start, block_end, format!("{block}{other}"), -(other_end as isize - other_start as isize)),
+            Direction::Forward => (block_start, other_end, format!("{other}{block}"), other.chars().count() as isize),
+        };
+
+        while let Some(selection) = selections.next_if(|selection| selection.range.start < block_end || selection.range.start == block_start){
+            let mut moved = selection.clone();
+            moved.range = Range::new(selection.range.start.saturating_add_signed(offset), selection.range.end.saturating_add_signed(offset));
+            new_selections.push(moved);
+        }
+        let mut region = Selection::new(Range::new(start, end), Direction::Forward);
+        changes.push(Document::apply_replace(&mut document.text, &replacement, &mut region, semantics));
+    }
+
+    document.selections = Selections::new(new_selections, document.selections.primary_selection_index, &document.text, semantics);
+    document.push_change_set(ChangeSet::new(changes, selections_before_changes, document.selections.clone(), semantics), semantics);
+    Ok(())
+}
+
+/// Returns the first and last line of each block of lines touched by `selections`, in order.
+/// Blocks from different selections are merged if they overlap, or are on adjacent lines.
+pub(crate) fn line_blocks(selections: &Selections, text: &Rope) -> Vec<(usize, usize)>{
+    let mut blocks: Vec<(usize, usize)> = Vec::new();
+    for selection in selections.iter(){
+        let first = text.char_to_line(selection.range.start);
+        // a selection ending at a line start doesn't touch that line
+        let last = text.char_to_line(selection.range.end.saturating_sub(1).max(selection.range.start).min(text.len_chars()));
+        match blocks.last_mut(){
+            Some(block) if first <= block.1 + 1 => {block.1 = block.1.max(last);}
+            _ => {blocks.push((first, last));}
+        }
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{move_lines_up, undo};
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        let result = move_lines_up::document_impl(&mut doc, semantics);
+        assert!(result.is_ok());
+        let expected_text = Rope::from(expected_text);
+        assert_eq!(expected_text, doc.text);
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &expected_text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &expected_text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(doc.is_modified());
+        // a single undo restores the text and selections
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(text, doc.text);
+        assert_eq!(selections, doc.selections);
+        assert!(doc.undo_stack().is_empty());
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(move_lines_up::document_impl(&mut doc, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn moves_cursor_line_up(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3
+        // i d k \n s o m e \n s h i t \n
+        test(CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(6, 6, None)], 0, "some\nidk\nshit\n", vec![(2, 2, None)], 0);
+        test(CursorSemantics::Block, "idk\nsome\nshit\n", vec![(6, 7, None)], 0, "some\nidk\nshit\n", vec![(2, 3, None)], 0);
+    }
+    #[test] fn moves_every_line_of_extended_selection_up(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(5, 11, None)], 0, "some\nshit\nidk\n", vec![(1, 7, None)], 0);
+        test(CursorSemantics::Block, "idk\nsome\nshit\n", vec![(11, 5, None)], 0, "some\nshit\nidk\n", vec![(7, 1, None)], 0);
+    }
+    #[test] fn selection_ending_at_line_start_does_not_move_that_line(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(4, 9, None)], 0, "some\nidk\nshit\n", vec![(0, 5, None)], 0);
+    }
+    #[test] fn merges_blocks_of_adjacent_selections(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\n", vec![(5, 5, None), (10, 10, None)], 1, "some\nshit\nidk\n", vec![(1, 1, None), (6, 6, None)], 1);
+    }
+    #[test] fn moves_separate_blocks_independently(){
+        // i d k \n s o m e \n s h i t \n e l s e \n
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nelse\n", vec![(5, 5, None), (15, 15, None)], 0, "some\nidk\nelse\nshit\n", vec![(1, 1, None), (10, 10, None)], 0);
+    }
+    #[test] fn keeps_last_line_without_newline_last(){
+        test(CursorSemantics::Bar, "idk\nsome", vec![(6, 6, None)], 0, "some\nidk", vec![(2, 2, None)], 0);
+    }
+    #[test] fn errors_at_first_line(){
+        test_error(CursorSemantics::Bar, "idk\nsome\n", vec![(1, 1, None)], 0);
+        test_error(CursorSemantics::Block, "idk\nsome\n", vec![(1, 2, None), (5, 6, None)], 1);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{
+    selection_history::SelectionChange,
     document::{Document, DocumentError},
+    utilities::clear_non_primary_selections,
     selection::{Selection, SelectionError, Direction, CursorSemantics, Movement},
 };
 use ropey::Rope;
@@ -11,11 +13,12 @@ pub fn document_impl(document: &mut Document, line_number: usize, semantics: Cur
     if line_number >= document.text.len_lines(){return Err(DocumentError::InvalidInput);}
     
     //if let Ok(()) = document.clear_non_primary_selections(){};
-    if let Ok(()) = crate::utilities::clear_non_primary_selections::document_impl(document){};
-    match selection_impl(document.selections.primary(), line_number, &document.text, Movement::Move, semantics){
-        Ok(new_selection) => {*document.selections.primary_mut() = new_selection;}
+    let mut new_selections = clear_non_primary_selections::selections_impl(&document.selections).unwrap_or_else(|_| document.selections.clone());
+    match selection_impl(new_selections.primary(), line_number, &document.text, Movement::Move, semantics){
+        Ok(new_selection) => {*new_selections.primary_mut() = new_selection;}
         Err(_) => {return Err(DocumentError::InvalidInput);}    //should be same state error
     }
+    document.set_selections(new_selections, SelectionChange::Jump);
     Ok(())
 }
 
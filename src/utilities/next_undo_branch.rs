@@ -0,0 +1,43 @@
+use crate::{
+    document::{Document, DocumentError},
+    utilities::previous_undo_branch
+};
+
+/// Switches redo to follow the next newer branch of changes made from the current state, wrapping around to the oldest.
+/// Only which branch redo follows changes. The document text and selections are left as they are.
+pub fn document_impl(document: &mut Document) -> Result<(), DocumentError>{
+    previous_undo_branch::switch_branch(document, 1)
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{next_undo_branch, insert_string, undo, redo};
+    use crate::document::Document;
+    use crate::selection::CursorSemantics;
+    use ropey::Rope;
+
+    #[test] fn next_undo_branch_changes_what_redo_reapplies(){
+        for semantics in [CursorSemantics::Bar, CursorSemantics::Block]{
+            let mut doc = Document::new(semantics);
+            assert!(insert_string::document_impl(&mut doc, "idk\n", false, 4, semantics).is_ok());
+            assert!(undo::document_impl(&mut doc, semantics).is_ok());
+            assert!(insert_string::document_impl(&mut doc, "some\n", false, 4, semantics).is_ok());
+            assert!(undo::document_impl(&mut doc, semantics).is_ok());
+
+            // most recent branch("some\n") is active, and the next branch wraps around to the oldest("idk\n")
+            assert!(next_undo_branch::document_impl(&mut doc).is_ok());
+            assert_eq!(Rope::from(""), doc.text);
+            assert!(redo::document_impl(&mut doc, semantics).is_ok());
+            assert_eq!(Rope::from("idk\n"), doc.text);
+        }
+    }
+    #[test] fn errors_without_multiple_branches(){
+        for semantics in [CursorSemantics::Bar, CursorSemantics::Block]{
+            let mut doc = Document::new(semantics);
+            assert!(next_undo_branch::document_impl(&mut doc).is_err());
+            assert!(insert_string::document_impl(&mut doc, "idk\n", false, 4, semantics).is_ok());
+            assert!(undo::document_impl(&mut doc, semantics).is_ok());
+            assert!(next_undo_branch::document_impl(&mut doc).is_err());
+        }
+    }
+}
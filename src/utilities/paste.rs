@@ -1,11 +1,25 @@
 use crate::{
     document::{Document, DocumentError},
-    selection::CursorSemantics
+    selection::CursorSemantics,
+    register::RegisterName,
+    clipboard::ClipboardProvider,
 };
 
-/// Insert clipboard contents at cursor position(s).
-pub fn document_impl(document: &mut Document, use_hard_tab: bool, tab_width: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
-    crate::utilities::insert_string::document_impl(document, &document.clipboard.clone(), use_hard_tab, tab_width, semantics)
+/// Insert `register` contents at cursor position(s).
+/// If `register` holds one fragment per selection, each fragment is inserted at its own selection.
+/// Otherwise, all fragments joined with a newline are inserted at every selection.
+/// Pasting from [`RegisterName::Clipboard`] reads the system `clipboard`. Its contents are only split back into fragments
+/// if they are unchanged since they were copied from edit_core.
+pub fn document_impl(document: &mut Document, register: RegisterName, clipboard: &mut dyn ClipboardProvider, use_hard_tab: bool, tab_width: usize, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    if register == RegisterName::Clipboard{
+        let contents = clipboard.get_contents().map_err(DocumentError::ClipboardError)?;
+        let copied_from_edit_core = document.registers.get(register).is_some_and(|fragments| fragments.join("\n") == contents);
+        if !copied_from_edit_core{
+            document.registers.set(register, vec![contents]);
+        }
+    }
+    let Some(fragments) = document.registers.fragments_for(register, document.selections.count()) else{return Err(DocumentError::InvalidInput);};
+    crate::utilities::insert_string::document_impl_per_selection(document, &fragments, use_hard_tab, tab_width, semantics)
 }
 
 #[cfg(test)]
@@ -14,9 +28,11 @@ mod tests{
     use crate::document::Document;
     use crate::selection::{Selection, CursorSemantics};
     use crate::selections::Selections;
+    use crate::register::RegisterName;
+    use crate::clipboard::{ClipboardProvider, InMemoryProvider};
     use ropey::Rope;
 
-    //fn test(text: &str, selections: Vec<Selection>, primary: usize, clipboard: &str, expected_selections: Vec<Selection>, expected_primary: usize, expected_text: &str, semantics: CursorSemantics){
+    //fn test(text: &str, selections: Vec<Selection>, primary: usize, clipboard: Vec<&str>, expected_selections: Vec<Selection>, expected_primary: usize, expected_text: &str, semantics: CursorSemantics){
     //    let text = Rope::from(text);
     //    let mut doc = Document::new(semantics)
     //        .with_text(text.clone())
@@ -28,7 +44,7 @@ mod tests{
     //    let expected_selections = Selections::new(expected_selections, expected_primary, &text, semantics);
     //    assert_eq!(expected_selections, doc.selections);
     //}
-    //fn test_error(text: &str, selections: Vec<Selection>, primary: usize, clipboard: &str, semantics: CursorSemantics){
+    //fn test_error(text: &str, selections: Vec<Selection>, primary: usize, clipboard: Vec<&str>, semantics: CursorSemantics){
     //    let text = Rope::from(text);
     //    let mut doc = Document::new(semantics)
     //        .with_text(text.clone())
@@ -36,7 +52,7 @@ mod tests{
     //        .with_clipboard(clipboard.to_string());
     //    assert!(crate::utilities::paste::document_impl(&mut doc, false, 4, semantics).is_err());
     //}
-    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, clipboard: &str, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, clipboard: Vec<&str>, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
         let text = Rope::from(text);
         let mut vec_selections = Vec::new();
         for tuple in tuple_selections{
@@ -46,8 +62,8 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections)
-            .with_clipboard(clipboard.to_string());
-        let result = paste::document_impl(&mut doc, false, 4, semantics);
+            .with_register(RegisterName::default(), clipboard.iter().map(|fragment| fragment.to_string()).collect());
+        let result = paste::document_impl(&mut doc, RegisterName::default(), &mut InMemoryProvider::default(), false, 4, semantics);
         assert!(!result.is_err());
         let expected_text = Rope::from(expected_text);
         assert_eq!(expected_text.clone(), doc.text);
@@ -59,7 +75,7 @@ mod tests{
         assert_eq!(expected_selections, doc.selections);
         assert!(doc.is_modified());
     }
-    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, clipboard: &str){
+    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, clipboard: Vec<&str>){
         let text = Rope::from(text);
         let mut vec_selections = Vec::new();
         for tuple in tuple_selections{
@@ -69,8 +85,8 @@ mod tests{
         let mut doc = Document::new(semantics)
             .with_text(text.clone())
             .with_selections(selections)
-            .with_clipboard(clipboard.to_string());
-        assert!(paste::document_impl(&mut doc, false, 4, semantics).is_err());
+            .with_register(RegisterName::default(), clipboard.iter().map(|fragment| fragment.to_string()).collect());
+        assert!(paste::document_impl(&mut doc, RegisterName::default(), &mut InMemoryProvider::default(), false, 4, semantics).is_err());
         assert!(!doc.is_modified());
     }
 
@@ -89,7 +105,7 @@ mod tests{
             vec![
                 (9, 10, None)
             ], 0, 
-            "other\n", 
+            vec!["other\n"], 
             "idk\nsome\nother\nshit\n", 
             vec![
                 (15, 16, Some(0))
@@ -111,15 +127,79 @@ mod tests{
             vec![
                 (9, 9, None)
             ], 0, 
-            "other\n", 
+            vec!["other\n"], 
             "idk\nsome\nother\nshit\n", 
             vec![
                 (15, 15, Some(0))
             ], 0
         );
     }
-    //TODO: paste_multi_selection_block_semantics
-    //TODO: paste_multi_selection_bar_semantics
+    #[test] fn paste_one_fragment_per_selection_block_semantics(){
+        test(
+            CursorSemantics::Block, 
+            "idk\nsome\nshit\n", 
+            vec![
+                (0, 1, None),
+                (4, 5, None)
+            ], 0, 
+            vec!["a", "bc"], 
+            "aidk\nbcsome\nshit\n", 
+            vec![
+                (1, 2, Some(1)),
+                (7, 8, Some(2))
+            ], 0
+        );
+    }
+    #[test] fn paste_one_fragment_per_selection_bar_semantics(){
+        test(
+            CursorSemantics::Bar, 
+            "idk\nsome\nshit\n", 
+            vec![
+                (0, 0, None),
+                (4, 4, None)
+            ], 0, 
+            vec!["a", "bc"], 
+            "aidk\nbcsome\nshit\n", 
+            vec![
+                (1, 1, Some(1)),
+                (7, 7, Some(2))
+            ], 0
+        );
+    }
+    #[test] fn paste_fragment_count_mismatch_pastes_joined_fragments_at_each_selection(){
+        test(
+            CursorSemantics::Bar, 
+            "idk\nsome\nshit\n", 
+            vec![
+                (0, 0, None),
+                (4, 4, None),
+                (9, 9, None)
+            ], 0, 
+            vec!["a", "b"], 
+            "a\nbidk\na\nbsome\na\nbshit\n", 
+            vec![
+                (3, 3, Some(1)),
+                (10, 10, Some(1)),
+                (18, 18, Some(1))
+            ], 0
+        );
+    }
+    #[test] fn paste_replaces_extended_selections(){
+        test(
+            CursorSemantics::Bar, 
+            "idk\nsome\nshit\n", 
+            vec![
+                (0, 3, None),
+                (4, 8, None)
+            ], 0, 
+            vec!["some", "idk"], 
+            "some\nidk\nshit\n", 
+            vec![
+                (4, 4, Some(4)),
+                (8, 8, Some(3))
+            ], 0
+        );
+    }
 
     #[test] fn errors_if_empty_clipboard(){
         //test_error(
@@ -134,8 +214,54 @@ mod tests{
             vec![
                 (4, 5, None)
             ], 0, 
-            ""
+            vec![""]
         );
     }
-
+    #[test] fn errors_if_empty_register(){
+        let text = Rope::from("idk\nshit\n");
+        let mut doc = Document::new(CursorSemantics::Bar)
+            .with_text(text.clone())
+            .with_selections(Selections::new(vec![Selection::new_from_components(4, 4, None, &text, CursorSemantics::Bar)], 0, &text, CursorSemantics::Bar));
+        assert!(paste::document_impl(&mut doc, RegisterName::Named('a'), &mut InMemoryProvider::default(), false, 4, CursorSemantics::Bar).is_err());
+        assert!(paste::document_impl(&mut doc, RegisterName::BlackHole, &mut InMemoryProvider::default(), false, 4, CursorSemantics::Bar).is_err());
+        assert!(!doc.is_modified());
+    }
+    #[test] fn paste_from_clipboard_register_reads_system_clipboard(){
+        let text = Rope::from("idk\nshit\n");
+        let mut clipboard = InMemoryProvider::default();
+        let _ = clipboard.set_contents("some\n");
+        let mut doc = Document::new(CursorSemantics::Bar)
+            .with_text(text.clone())
+            .with_selections(Selections::new(vec![Selection::new_from_components(4, 4, None, &text, CursorSemantics::Bar)], 0, &text, CursorSemantics::Bar));
+        assert!(paste::document_impl(&mut doc, RegisterName::Clipboard, &mut clipboard, false, 4, CursorSemantics::Bar).is_ok());
+        assert_eq!(Rope::from("idk\nsome\nshit\n"), doc.text);
+    }
+    #[test] fn paste_from_clipboard_register_distributes_fragments_copied_from_edit_core(){
+        let text = Rope::from("idk\nsome\nshit\n");
+        let mut doc = Document::new(CursorSemantics::Bar)
+            .with_text(text.clone())
+            .with_selections(Selections::new(vec![
+                Selection::new_from_components(0, 0, None, &text, CursorSemantics::Bar),
+                Selection::new_from_components(4, 4, None, &text, CursorSemantics::Bar)
+            ], 0, &text, CursorSemantics::Bar))
+            .with_register(RegisterName::Clipboard, vec!["a".to_string(), "b".to_string()]);
+        let mut clipboard = InMemoryProvider::default();
+        let _ = clipboard.set_contents("a\nb");
+        assert!(paste::document_impl(&mut doc, RegisterName::Clipboard, &mut clipboard, false, 4, CursorSemantics::Bar).is_ok());
+        assert_eq!(Rope::from("aidk\nbsome\nshit\n"), doc.text);
+    }
+    #[test] fn paste_from_clipboard_register_does_not_distribute_fragments_if_clipboard_changed_externally(){
+        let text = Rope::from("idk\nsome\nshit\n");
+        let mut doc = Document::new(CursorSemantics::Bar)
+            .with_text(text.clone())
+            .with_selections(Selections::new(vec![
+                Selection::new_from_components(0, 0, None, &text, CursorSemantics::Bar),
+                Selection::new_from_components(4, 4, None, &text, CursorSemantics::Bar)
+            ], 0, &text, CursorSemantics::Bar))
+            .with_register(RegisterName::Clipboard, vec!["a".to_string(), "b".to_string()]);
+        let mut clipboard = InMemoryProvider::default();
+        let _ = clipboard.set_contents("c");
+        assert!(paste::document_impl(&mut doc, RegisterName::Clipboard, &mut clipboard, false, 4, CursorSemantics::Bar).is_ok());
+        assert_eq!(Rope::from("cidk\ncsome\nshit\n"), doc.text);
+    }
 }
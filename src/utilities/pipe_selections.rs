@@ -0,0 +1,167 @@
+use crate::{
+    document::{Document, DocumentError},
+    selections::Selections,
+    selection::{Selection, CursorSemantics},
+    range::Range,
+    selection_history::SelectionChange,
+    shell::{self, PipeMode, ShellError},
+};
+
+/// Runs `command` once per selection, with the selected text on stdin, and uses each output according to `mode`.
+/// If the selected text doesn't end with a newline, one trailing newline is removed from the command's output.
+/// With [`PipeMode::Replace`], each selection is replaced with, and then selects, its output.
+/// With [`PipeMode::Insert`], each output is inserted before its selection, and selections keep covering the same text.
+/// All edits are recorded as a single change set.
+/// With [`PipeMode::Keep`], only selections the command exits successfully for are kept. Text is never modified.
+/// Errors, without modifying text or selections, if `command` is empty, can't be run, or(unless `mode` is keep) exits unsuccessfully for any selection.
+/// With [`PipeMode::Keep`], errors if the command exits unsuccessfully for every selection.
+pub fn document_impl(document: &mut Document, command: &str, mode: PipeMode, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    if command.trim().is_empty(){return Err(DocumentError::InvalidInput);}
+    // every command is run before any edit, so a failure leaves the document unchanged
+    let mut outputs = Vec::with_capacity(document.selections.count());
+    for selection in document.selections.iter(){
+        let input = selection.contents_as_string(&document.text);
+        let mut output = shell::run(command, &input).map_err(DocumentError::ShellError)?;
+        if !input.ends_with('\n') && output.stdout.ends_with('\n'){output.stdout.pop();}
+        outputs.push(output);
+    }
+
+    if mode == PipeMode::Keep{
+        let primary = document.selections.primary_selection_index;
+        if outputs.iter().all(|output| !output.success){
+            return Err(DocumentError::ShellError(ShellError::CommandFailed(outputs.swap_remove(primary).stderr)));
+        }
+        let kept_before_primary = outputs.iter().take(primary).filter(|output| output.success).count();
+        // if the primary selection is dropped, the previous kept selection(or the first) becomes primary
+        let new_primary = if outputs[primary].success{kept_before_primary}else{kept_before_primary.saturating_sub(1)};
+        let kept = document.selections.iter().zip(&outputs).filter(|(_, output)| output.success).map(|(selection, _)| selection.clone()).collect();
+        document.set_selections(Selections::new(kept, new_primary, &document.text, semantics), SelectionChange::Jump);
+        return Ok(());
+    }
+    if let Some(failed) = outputs.iter().find(|output| !output.success){
+        return Err(DocumentError::ShellError(ShellError::CommandFailed(failed.stderr.clone())));
+    }
+
+    let selections_before_changes = document.selections.clone();
+    match mode{
+        PipeMode::Replace => {
+            let replacements: Vec<(Range, &str)> = selections_before_changes.iter().zip(&outputs).map(|(selection, output)| (selection.range.clone(), output.stdout.as_str())).collect();
+            document.replace_ranges(&replacements, |i, output, _| Selection::new(Document::selected_text(output, semantics), selections_before_changes.selections[i].direction), semantics)
+        }
+        PipeMode::Insert => {
+            // each output is inserted at its selection's start, and the selection is shifted past it
+            let replacements: Vec<(Range, &str)> = selections_before_changes.iter().zip(&outputs).map(|(selection, output)| (Range::new(selection.range.start, selection.range.start), output.stdout.as_str())).collect();
+            document.replace_ranges(&replacements, |i, output, _| {
+                let mut shifted = selections_before_changes.selections[i].clone();
+                shifted.range = Range::new(output.end, output.end + (shifted.range.end - shifted.range.start));
+                shifted
+            }, semantics)
+        }
+        PipeMode::Keep => unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{pipe_selections, undo};
+    use crate::{
+        document::{Document, DocumentError},
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+        shell::{PipeMode, ShellError},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, command: &str, mode: PipeMode, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, 0, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        let result = pipe_selections::document_impl(&mut doc, command, mode, semantics);
+        assert!(result.is_ok());
+        let expected_text = Rope::from(expected_text);
+        assert_eq!(expected_text, doc.text);
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &expected_text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, 0, &expected_text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        // a single undo restores the text and selections
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(text, doc.text);
+        assert_eq!(selections, doc.selections);
+    }
+    fn test_keep(semantics: CursorSemantics, command: &str, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = pipe_selections::document_impl(&mut doc, command, PipeMode::Keep, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, command: &str, mode: PipeMode, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize) -> DocumentError{
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        let error = pipe_selections::document_impl(&mut doc, command, mode, semantics).unwrap_err();
+        assert!(!doc.is_modified());
+        assert_eq!(selections, doc.selections);
+        error
+    }
+
+    #[test] fn replaces_each_selection_with_its_output(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0
+        // i d k   s o m e \n
+        test(CursorSemantics::Bar, "tr a-z A-Z", PipeMode::Replace, "idk some\n", vec![(0, 3, None), (8, 4, None)], "IDK SOME\n", vec![(0, 3, None), (8, 4, None)]);
+        test(CursorSemantics::Block, "tr a-z A-Z", PipeMode::Replace, "idk some\n", vec![(0, 3, None), (4, 8, None)], "IDK SOME\n", vec![(0, 3, None), (4, 8, None)]);
+    }
+    #[test] fn replaced_selections_select_output_of_different_length(){
+        test(CursorSemantics::Bar, "echo something", PipeMode::Replace, "idk some\n", vec![(0, 3, None), (4, 8, None)], "something something\n", vec![(0, 9, None), (10, 19, None)]);
+        test(CursorSemantics::Bar, "printf ''", PipeMode::Replace, "idk some\n", vec![(0, 4, None)], "some\n", vec![(0, 0, None)]);
+    }
+    #[test] fn keeps_trailing_newline_of_selected_lines(){
+        test(CursorSemantics::Bar, "sort", PipeMode::Replace, "some\nidk\n", vec![(0, 9, None)], "idk\nsome\n", vec![(0, 9, None)]);
+    }
+    #[test] fn inserts_output_before_each_selection(){
+        test(CursorSemantics::Bar, "echo x", PipeMode::Insert, "idk some\n", vec![(0, 3, None), (4, 8, None)], "xidk xsome\n", vec![(1, 4, None), (6, 10, None)]);
+        test(CursorSemantics::Block, "tr a-z A-Z", PipeMode::Insert, "idk some\n", vec![(4, 5, None)], "idk Ssome\n", vec![(5, 6, None)]);
+    }
+    #[test] fn keeps_selections_command_succeeds_for(){
+        test_keep(CursorSemantics::Bar, "grep -q o", "idk some shit\n", vec![(0, 3, None), (4, 8, None), (9, 13, None)], 2, vec![(4, 8, None)], 0);
+        test_keep(CursorSemantics::Block, "grep -q i", "idk some shit\n", vec![(0, 3, None), (4, 8, None), (9, 13, None)], 1, vec![(0, 3, None), (9, 13, None)], 0);
+    }
+    #[test] fn errors_without_modifying_text_if_command_fails(){
+        let error = test_error(CursorSemantics::Bar, "grep -q i || { echo idk >&2; exit 1; }", PipeMode::Replace, "idk some\n", vec![(0, 3, None), (4, 8, None)], 0);
+        assert!(matches!(error, DocumentError::ShellError(ShellError::CommandFailed(stderr)) if stderr == "idk\n"));
+        test_error(CursorSemantics::Block, "false", PipeMode::Insert, "idk\n", vec![(0, 3, None)], 0);
+        test_error(CursorSemantics::Bar, "false", PipeMode::Keep, "idk some\n", vec![(0, 3, None), (4, 8, None)], 0);
+    }
+    #[test] fn errors_if_command_empty(){
+        assert!(matches!(test_error(CursorSemantics::Bar, " ", PipeMode::Replace, "idk\n", vec![(0, 3, None)], 0), DocumentError::InvalidInput));
+    }
+}
@@ -0,0 +1,53 @@
+use crate::document::{Document, DocumentError};
+
+/// Switches redo to follow the next older branch of changes made from the current state, wrapping around to the newest.
+/// Only which branch redo follows changes. The document text and selections are left as they are.
+pub fn document_impl(document: &mut Document) -> Result<(), DocumentError>{
+    switch_branch(document, -1)
+}
+
+/// Makes the branch `offset` places from the active one(in creation order, wrapping) active, so redo follows it.
+pub(crate) fn switch_branch(document: &mut Document, offset: isize) -> Result<(), DocumentError>{
+    let Some(node) = document.undo_tree.node(document.undo_tree.current()) else{return Err(DocumentError::NoOtherUndoBranch);};
+    let children = node.children();
+    if children.len() < 2{return Err(DocumentError::NoOtherUndoBranch);}
+    let active = children.iter().position(|&child| Some(child) == node.active_child()).unwrap_or(0);
+    let branch = children[(active as isize + offset).rem_euclid(children.len() as isize) as usize];
+    document.undo_tree.set_active_child(branch);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{previous_undo_branch, next_undo_branch, insert_string, undo, redo};
+    use crate::document::Document;
+    use crate::selection::CursorSemantics;
+    use ropey::Rope;
+
+    #[test] fn previous_undo_branch_changes_what_redo_reapplies(){
+        for semantics in [CursorSemantics::Bar, CursorSemantics::Block]{
+            let mut doc = Document::new(semantics);
+            for edit in ["idk\n", "some\n", "shit\n"]{
+                assert!(insert_string::document_impl(&mut doc, edit, false, 4, semantics).is_ok());
+                assert!(undo::document_impl(&mut doc, semantics).is_ok());
+            }
+
+            assert!(previous_undo_branch::document_impl(&mut doc).is_ok());
+            assert!(redo::document_impl(&mut doc, semantics).is_ok());
+            assert_eq!(Rope::from("some\n"), doc.text);
+            assert!(undo::document_impl(&mut doc, semantics).is_ok());
+
+            // previous then next returns to the branch redo followed before
+            assert!(previous_undo_branch::document_impl(&mut doc).is_ok());
+            assert!(next_undo_branch::document_impl(&mut doc).is_ok());
+            assert!(redo::document_impl(&mut doc, semantics).is_ok());
+            assert_eq!(Rope::from("some\n"), doc.text);
+        }
+    }
+    #[test] fn errors_without_multiple_branches(){
+        for semantics in [CursorSemantics::Bar, CursorSemantics::Block]{
+            let mut doc = Document::new(semantics);
+            assert!(previous_undo_branch::document_impl(&mut doc).is_err());
+        }
+    }
+}
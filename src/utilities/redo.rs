@@ -1,62 +1,62 @@
 use crate::{
     document::{Document, DocumentError},
     selection::CursorSemantics,
-    history::Operation
+    history::{ChangeSet, Operation}
 };
-use std::cmp::Ordering;
 
 /// Re-applies the last undone changes to the document.
-// Make sure to clear the redo stack in every edit fn. new actions invalidate the redo history
+/// Change sets grouped together(consecutive typing or deletion) are re-applied as a single unit.
 pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{    //should this be HistoryError instead?...
     // Check if there is something to redo
-    if let Some(change_set) = document.redo_stack.pop(){
-        let changes = change_set.changes();
-
-        document.selections = change_set.clone().selections_before_changes();    //set selections to selections_before_changes to account for any selection movements that may have occurred since undo
-        assert!(document.selections.count() == changes.len());   //num selections should match num changes
-
-        for (i, change) in changes.iter().enumerate().take(document.selections.count()){
-            let selection = document.selections.nth_mut(i);
-            match change.operation(){
-                Operation::Insert{inserted_text} => {
-                    let _ = Document::apply_insert(&mut document.text, &inserted_text, selection, semantics);
-                    document.selections.shift_subsequent_selections_forward(i, inserted_text.len());
-                }
-                Operation::Delete => {
-                    *selection = change.selection_before_change();
-                    let change = Document::apply_delete(&mut document.text, selection, semantics);
-                    if let Operation::Insert{inserted_text} = change.inverse(){
-                        document.selections.shift_subsequent_selections_backward(i, inserted_text.len());
-                    }
-                }
-                Operation::Replace{replacement_text} => {
-                    let inserted_text = replacement_text;
-                    let change = Document::apply_replace(&mut document.text, &inserted_text, selection, semantics);
-                    if let Operation::Replace{replacement_text} = change.inverse(){   //destructure to get currently selected text
-                        match replacement_text.len().cmp(&inserted_text.len()){    //old selected text vs new text
-                            Ordering::Greater => {document.selections.shift_subsequent_selections_backward(i, replacement_text.len().saturating_sub(inserted_text.len()));}
-                            Ordering::Less => {document.selections.shift_subsequent_selections_forward(i, inserted_text.len().saturating_sub(replacement_text.len()));}
-                            Ordering::Equal => {}   // no change to subsequent selections
-                        }
-                    }
-                }
-                Operation::NoOp => {}
+    if redo_change_set(document, semantics).is_none(){return Err(DocumentError::NoChangesToRedo);}
+    // any change sets grouped with the one just re-applied are next to redo
+    while document.undo_tree.next_redo().is_some_and(ChangeSet::is_grouped_with_previous){
+        redo_change_set(document, semantics);
+    }
+    Ok(())
+}
+/// Re-applies the [`ChangeSet`] leading to the current undo tree node's active child, and makes that child current.
+/// Returns the re-applied [`ChangeSet`], or `None` if the current node has no children.
+pub(crate) fn redo_change_set(document: &mut Document, semantics: CursorSemantics) -> Option<ChangeSet>{
+    let change_set = document.undo_tree.next_redo()?.clone();
+    let recorded_semantics = change_set.semantics();
+    // each change's selections were recorded with all previous changes in the set applied, so re-applying the changes in order
+    // lets each be re-applied where it was recorded, without shifting positions
+    for change in change_set.changes(){
+        let mut selection = change.selection_before_change();
+        match change.operation(){
+            Operation::Insert{inserted_text} => {
+                let _ = Document::apply_insert(&mut document.text, &inserted_text, &mut selection, recorded_semantics);
             }
+            Operation::Delete => {
+                let _ = Document::apply_delete(&mut document.text, &mut selection, recorded_semantics);
+            }
+            Operation::Replace{replacement_text} => {
+                let _ = Document::apply_replace(&mut document.text, &replacement_text, &mut selection, recorded_semantics);
+            }
+            Operation::NoOp => {}
         }
-        assert!(document.selections == change_set.clone().selections_after_changes());
-
-        // Push changes back onto the undo stack
-        document.undo_stack.push(change_set);
+    }
+    // selections recorded by a client with other cursor semantics don't fit this client, so its own are moved across the redo instead
+    document.selections = if recorded_semantics == semantics{
+        change_set.clone().selections_after_changes()
+    }else{
+        document.selections.map_edits(&change_set.edits(), &document.text, semantics)
+    };
 
-        Ok(())
-    }else{Err(DocumentError::NoChangesToRedo)}
+    document.selection_history.map_edits(&change_set.edits(), &document.text, semantics);
+    document.word_index.update(&change_set.edits(), &document.text);
+    document.undo_tree.move_to_active_child();
+    Some(change_set)
 }
 
 #[cfg(test)]
 mod tests{
-    use crate::utilities::redo;
+    use crate::utilities::{redo, undo, insert_string};
     use crate::document::Document;
-    use crate::selection::CursorSemantics;
+    use crate::selections::Selections;
+    use crate::selection::{Selection, CursorSemantics};
+    use ropey::Rope;
 
     //TODO: test more
 
@@ -64,4 +64,48 @@ mod tests{
         let mut doc = Document::new(CursorSemantics::Bar);
         assert!(redo::document_impl(&mut doc, CursorSemantics::Bar).is_err());
     }
+
+    #[test] fn redo_insert_of_multi_codepoint_graphemes_restores_text_and_selections(){
+        for (semantics, width) in [(CursorSemantics::Block, 1), (CursorSemantics::Bar, 0)]{
+            for string in ["\u{e9}", "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}", "e\u{301}"]{
+                let text = Rope::from("idk\nsome\n");
+                let selections = Selections::new(
+                    vec![
+                        Selection::new_from_components(0, width, None, &text, semantics),
+                        Selection::new_from_components(4, 4 + width, None, &text, semantics)
+                    ], 0, &text, semantics
+                );
+                let mut doc = Document::new(semantics)
+                    .with_text(text.clone())
+                    .with_selections(selections);
+                assert!(insert_string::document_impl(&mut doc, string, false, 4, semantics).is_ok());
+                let (text_after_insert, selections_after_insert) = (doc.text.clone(), doc.selections.clone());
+                assert!(undo::document_impl(&mut doc, semantics).is_ok());
+                assert!(redo::document_impl(&mut doc, semantics).is_ok());
+                assert_eq!(text_after_insert, doc.text);
+                assert_eq!(selections_after_insert, doc.selections);
+            }
+        }
+    }
+
+    #[test] fn redo_reapplies_consecutive_typing_as_a_single_unit(){
+        for (semantics, selection) in [(CursorSemantics::Block, (3, 4, None)), (CursorSemantics::Bar, (3, 3, None))]{
+            let text = Rope::from("idk\n");
+            let selections = Selections::new(vec![Selection::new_from_components(selection.0, selection.1, selection.2, &text, semantics)], 0, &text, semantics);
+            let mut doc = Document::new(semantics)
+                .with_text(text.clone())
+                .with_selections(selections);
+            let _ = insert_string::document_impl(&mut doc, "\n", false, 4, semantics);
+            for string in ["s", "o", "m", "e"]{
+                let _ = insert_string::document_impl(&mut doc, string, false, 4, semantics);
+            }
+            let selections_after_typing = doc.selections.clone();
+            assert!(undo::document_impl(&mut doc, semantics).is_ok());
+            assert_eq!(Rope::from("idk\n\n"), doc.text);
+            assert!(redo::document_impl(&mut doc, semantics).is_ok());
+            assert_eq!(Rope::from("idk\nsome\n"), doc.text);
+            assert_eq!(selections_after_typing, doc.selections);
+            assert!(doc.redo_stack().is_empty());
+        }
+    }
 }
\ No newline at end of file
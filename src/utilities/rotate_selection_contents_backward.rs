@@ -0,0 +1,73 @@
+use crate::{
+    document::{Document, DocumentError},
+    selection::CursorSemantics,
+};
+
+/// Moves the text of each selection into the previous selection. The first selection's text moves into the last.
+/// The primary selection stays at its position.
+pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    let mut contents = document.selection_contents();
+    contents.rotate_left(1);
+    document.replace_selection_contents(contents, semantics)
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{rotate_selection_contents_backward, undo};
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, expected_text: &str, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        let result = rotate_selection_contents_backward::document_impl(&mut doc, semantics);
+        assert!(result.is_ok());
+        let expected_text = Rope::from(expected_text);
+        assert_eq!(expected_text, doc.text);
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &expected_text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, primary, &expected_text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        // a single undo restores the text and selections
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(text, doc.text);
+        assert_eq!(selections, doc.selections);
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(rotate_selection_contents_backward::document_impl(&mut doc, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn rotates_contents_backward(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3
+        // i d k   s o m e   s h i t \n
+        test(CursorSemantics::Bar, "idk some shit\n", vec![(0, 3, None), (4, 8, None), (9, 13, None)], 2, "some shit idk\n", vec![(0, 4, None), (5, 9, None), (10, 13, None)]);
+        test(CursorSemantics::Block, "abc\n", vec![(0, 1, None), (1, 2, None), (2, 3, None)], 1, "bca\n", vec![(0, 1, None), (1, 2, None), (2, 3, None)]);
+    }
+    #[test] fn errors_with_single_selection(){
+        test_error(CursorSemantics::Block, "idk\n", vec![(0, 3, None)], 0);
+    }
+}
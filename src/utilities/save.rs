@@ -1,17 +1,33 @@
 use std::fs;
+use std::io;
 use std::error::Error;
 use std::io::BufWriter;
 use crate::document::Document;
+use crate::undo_file;
 
-/// Saves the document's content to its file path.
-pub fn document_impl(document: &mut Document) -> Result<(), Box<dyn Error>>{
+/// The result of a successful [`document_impl`].
+#[derive(Debug)]
+pub enum SaveOutcome{
+    /// the content and undo history were saved(or there was nothing to save, because the document has no file path)
+    Saved,
+    /// the content was saved, but its undo history could not be. only that history is lost
+    UndoHistoryNotSaved(io::Error),
+}
+
+/// Saves the document's content to its file path, and its undo history to the file's undo file.
+/// Only failing to save the content is an error. Failing to save the undo history is reported as [`SaveOutcome::UndoHistoryNotSaved`].
+pub fn document_impl(document: &mut Document) -> Result<SaveOutcome, Box<dyn Error>>{
     if let Some(path) = &document.file_path{ // does nothing if path is None    //maybe return Err(()) instead?
         document.text.write_to(BufWriter::new(fs::File::create(path)?))?;
         //self.modified = false;    //old code. no longer used this way...
         document.last_saved_text = document.text.clone();
+        // the file is saved at this point. failing to save its undo history(read only dir, full disk) only loses that history
+        if let Err(e) = undo_file::save(path, &document.text, &document.undo_tree){
+            return Ok(SaveOutcome::UndoHistoryNotSaved(e));
+        }
     }
     
-    Ok(())
+    Ok(SaveOutcome::Saved)
 }
 
 //not sure how to test this here. has been tested by using fn from frontend code...
@@ -0,0 +1,124 @@
+use crate::{
+    selection_history::SelectionChange,
+    document::{Document, DocumentError},
+    selections::{Selections, SelectionsError},
+    selection::{Selection, CursorSemantics, Direction},
+    range::Range,
+    search::Search,
+};
+use ropey::Rope;
+
+/// Replaces the primary [`Selection`] with the first match of the regex `pattern` in the whole text, starting after the
+/// primary selection start. Wraps around to the first match in the text, if there are no matches after it.
+/// Errors if `pattern` is not a valid regex, or if there are no matches.
+pub fn document_impl(document: &mut Document, pattern: &str, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    let matches = search_matches(pattern, &document.text)?;
+    let start = document.selections.primary().range.start;
+    let search_match = matches.iter()
+        .find(|search_match| search_match.start > start)
+        .unwrap_or(&matches[0]);
+    jump_to_match(document, search_match, semantics)
+}
+
+/// Returns the char [`Range`] of every non empty match of the regex `pattern` in `text`, in text order.
+/// Errors if `pattern` is not a valid regex, or if there are no matches.
+pub(crate) fn search_matches(pattern: &str, text: &Rope) -> Result<Vec<Range>, DocumentError>{
+    if pattern.is_empty(){return Err(DocumentError::SelectionsError(SelectionsError::NoSearchMatches));}
+    let Ok(search) = Search::new(pattern) else{return Err(DocumentError::InvalidInput);};
+    let matches: Vec<Range> = search.find_all(text).into_iter()
+        .filter(|search_match| search_match.start < search_match.end)
+        .collect();
+    if matches.is_empty(){return Err(DocumentError::SelectionsError(SelectionsError::NoSearchMatches));}
+    Ok(matches)
+}
+
+/// Replaces the primary [`Selection`] with a selection of `search_match`, and records the change as a jump.
+/// Errors if the primary selection already selects `search_match`.
+pub(crate) fn jump_to_match(document: &mut Document, search_match: &Range, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    if &document.selections.primary().range == search_match{return Err(DocumentError::SelectionsError(SelectionsError::ResultsInSameState));}
+    let mut new_selections = document.selections.selections.clone();
+    new_selections[document.selections.primary_selection_index] = Selection::new(search_match.clone(), Direction::Forward);
+    let new_selections = Selections::new(new_selections, document.selections.primary_selection_index, &document.text, semantics);
+    document.set_selections(new_selections, SelectionChange::Jump);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::search_next;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, pattern: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = search_next::document_impl(&mut doc, pattern, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, pattern: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        assert!(search_next::document_impl(&mut doc, pattern, semantics).is_err());
+        assert_eq!(selections, doc.selections);
+    }
+
+    #[test] fn selects_next_match(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0
+        // i d k \n s o m e \n s h i t \n s o m e \n
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(0, 0, None)], 0, vec![(4, 8, None)], 0);
+        test(CursorSemantics::Block, "idk\nsome\nshit\nsome\n", "some", vec![(0, 1, None)], 0, vec![(4, 8, None)], 0);
+    }
+    #[test] fn skips_match_at_primary_selection(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(4, 8, None)], 0, vec![(14, 18, None)], 0);
+        test(CursorSemantics::Block, "idk\nsome\nshit\nsome\n", "some", vec![(4, 8, None)], 0, vec![(14, 18, None)], 0);
+    }
+    #[test] fn wraps_to_first_match_at_doc_end(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(16, 16, None)], 0, vec![(4, 8, None)], 0);
+        test(CursorSemantics::Block, "idk\nsome\nshit\nsome\n", "some", vec![(16, 17, None)], 0, vec![(4, 8, None)], 0);
+    }
+    #[test] fn searches_regex(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\n", r"s\w+", vec![(5, 5, None)], 0, vec![(9, 13, None)], 0);
+    }
+    #[test] fn only_replaces_primary_selection(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(0, 0, None), (9, 9, None)], 1, vec![(0, 0, None), (14, 18, None)], 1);
+    }
+    #[test] fn uses_char_indices_with_multibyte_text(){
+        test(CursorSemantics::Bar, "é\nidk\n", "idk", vec![(0, 0, None)], 0, vec![(2, 5, None)], 0);
+    }
+    #[test] fn errors_if_no_matches(){
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\n", "else", vec![(0, 0, None)], 0);
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\n", "", vec![(0, 0, None)], 0);
+    }
+    #[test] fn errors_if_invalid_regex(){
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\n", "(", vec![(0, 0, None)], 0);
+    }
+    #[test] fn errors_if_only_match_is_already_selected(){
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\n", "some", vec![(4, 8, None)], 0);
+    }
+}
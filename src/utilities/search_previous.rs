@@ -0,0 +1,85 @@
+use crate::{
+    document::{Document, DocumentError},
+    selection::CursorSemantics,
+    utilities::search_next,
+};
+
+/// Replaces the primary [`Selection`](crate::selection::Selection) with the last match of the regex `pattern` in the whole text,
+/// starting before the primary selection start. Wraps around to the last match in the text, if there are no matches before it.
+/// Errors if `pattern` is not a valid regex, or if there are no matches.
+pub fn document_impl(document: &mut Document, pattern: &str, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    let matches = search_next::search_matches(pattern, &document.text)?;
+    let start = document.selections.primary().range.start;
+    let search_match = matches.iter()
+        .rev()
+        .find(|search_match| search_match.start < start)
+        .unwrap_or(&matches[matches.len() - 1]);
+    search_next::jump_to_match(document, search_match, semantics)
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::search_previous;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, pattern: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = search_previous::document_impl(&mut doc, pattern, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, pattern: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        assert!(search_previous::document_impl(&mut doc, pattern, semantics).is_err());
+        assert_eq!(selections, doc.selections);
+    }
+
+    #[test] fn selects_previous_match(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0
+        // i d k \n s o m e \n s h i t \n s o m e \n
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(19, 19, None)], 0, vec![(14, 18, None)], 0);
+        test(CursorSemantics::Block, "idk\nsome\nshit\nsome\n", "some", vec![(19, 20, None)], 0, vec![(14, 18, None)], 0);
+    }
+    #[test] fn skips_match_at_primary_selection(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(14, 18, None)], 0, vec![(4, 8, None)], 0);
+        test(CursorSemantics::Block, "idk\nsome\nshit\nsome\n", "some", vec![(18, 14, None)], 0, vec![(4, 8, None)], 0);
+    }
+    #[test] fn wraps_to_last_match_at_doc_start(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(2, 2, None)], 0, vec![(14, 18, None)], 0);
+        test(CursorSemantics::Block, "idk\nsome\nshit\nsome\n", "some", vec![(2, 3, None)], 0, vec![(14, 18, None)], 0);
+    }
+    #[test] fn errors_if_no_matches(){
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\n", "else", vec![(0, 0, None)], 0);
+    }
+    #[test] fn errors_if_invalid_regex(){
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\n", "[", vec![(0, 0, None)], 0);
+    }
+}
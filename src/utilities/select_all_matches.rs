@@ -0,0 +1,96 @@
+use crate::{
+    selection_history::SelectionChange,
+    document::{Document, DocumentError},
+    selections::{Selections, SelectionsError},
+    selection::{Selection, CursorSemantics, Direction},
+    utilities::search_next,
+};
+
+/// Replaces all selections with a [`Selection`] of every match of the regex `pattern` in the whole text.
+/// The first match at or after the primary selection start becomes the primary selection, wrapping around to the first match.
+/// Errors if `pattern` is not a valid regex, or if there are no matches.
+pub fn document_impl(document: &mut Document, pattern: &str, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    let matches = search_next::search_matches(pattern, &document.text)?;
+    let start = document.selections.primary().range.start;
+    let primary_selection_index = matches.iter().position(|search_match| search_match.start >= start).unwrap_or(0);
+    let new_selections = Selections::new(
+        matches.into_iter().map(|search_match| Selection::new(search_match, Direction::Forward)).collect(),
+        primary_selection_index,
+        &document.text,
+        semantics
+    );
+    if new_selections == document.selections{return Err(DocumentError::SelectionsError(SelectionsError::ResultsInSameState));}
+    document.set_selections(new_selections, SelectionChange::Jump);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::select_all_matches;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, text: &str, pattern: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = select_all_matches::document_impl(&mut doc, pattern, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, text: &str, pattern: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        assert!(select_all_matches::document_impl(&mut doc, pattern, semantics).is_err());
+        assert_eq!(selections, doc.selections);
+    }
+
+    #[test] fn selects_every_match(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0
+        // i d k \n s o m e \n s h i t \n s o m e \n
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(0, 0, None)], 0, vec![(4, 8, None), (14, 18, None)], 0);
+        test(CursorSemantics::Block, "idk\nsome\nshit\nsome\n", "some", vec![(0, 1, None)], 0, vec![(4, 8, None), (14, 18, None)], 0);
+    }
+    #[test] fn primary_is_first_match_after_primary_selection(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(0, 0, None), (9, 9, None)], 1, vec![(4, 8, None), (14, 18, None)], 1);
+    }
+    #[test] fn primary_wraps_to_first_match(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(19, 19, None)], 0, vec![(4, 8, None), (14, 18, None)], 0);
+    }
+    #[test] fn selects_every_regex_match(){
+        test(CursorSemantics::Bar, "idk\nsome\nshit\n", r"s\w", vec![(0, 0, None)], 0, vec![(4, 6, None), (9, 11, None)], 0);
+    }
+    #[test] fn errors_if_no_matches(){
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\n", "else", vec![(0, 0, None)], 0);
+    }
+    #[test] fn errors_if_invalid_regex(){
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\n", "(", vec![(0, 0, None)], 0);
+    }
+    #[test] fn errors_if_matches_already_selected(){
+        test_error(CursorSemantics::Bar, "idk\nsome\nshit\nsome\n", "some", vec![(4, 8, None), (14, 18, None)], 0);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{
+    selection_history::SelectionChange,
+    document::{Document, DocumentError},
+    selection::{Selection, SelectionError, CursorSemantics, Direction, TextObjectScope},
+    range::Range,
+    text_util
+};
+use ropey::Rope;
+
+pub fn document_impl(document: &mut Document, scope: TextObjectScope, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, |selection, text, semantics| selection_impl(selection, scope, text, semantics)){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Jump);}
+        Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
+    }
+    Ok(())
+}
+
+/// Returns a new instance of [`Selection`] over the long word(WORD) at the cursor. A long word is a run of non whitespace chars.
+/// If the cursor is over spaces/tabs, the run of spaces/tabs is selected instead.
+/// Around also selects the spaces/tabs after the long word(or before it, if there are none after), or the long word after selected spaces/tabs.
+/// Errors if the cursor is at a line end, or text end.
+pub fn selection_impl(selection: &Selection, scope: TextObjectScope, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
+    selection.assert_invariants(text, semantics);
+    let cursor = selection.cursor(text, semantics);
+    if cursor >= text.len_chars() || text.char(cursor) == '\n'{return Err(SelectionError::ResultsInSameState);}
+
+    let is_space = |char: char| char == ' ' || char == '\t';
+    let (start, end) = if is_space(text.char(cursor)){
+        let mut start = cursor;
+        while start > 0 && is_space(text.char(start - 1)){start -= 1;}
+        let mut end = cursor;
+        while end < text.len_chars() && is_space(text.char(end)){end += 1;}
+        match scope{
+            TextObjectScope::Inside => (start, end),
+            TextObjectScope::Around => (start, long_word_end(end, text))
+        }
+    }else{
+        let mut start = cursor;
+        while start > 0 && !text_util::is_whitespace(text.char(start - 1)){start -= 1;}
+        let end = long_word_end(cursor, text);
+        match scope{
+            TextObjectScope::Inside => (start, end),
+            TextObjectScope::Around => text_util::include_adjacent_whitespace(start, end, text)
+        }
+    };
+
+    if selection.range == Range::new(start, end) && selection.direction == Direction::Forward{return Err(SelectionError::ResultsInSameState);}
+    Ok(Selection::new(Range::new(start, end), Direction::Forward))
+}
+
+/// Returns the index after the run of non whitespace chars starting at `index`.
+fn long_word_end(index: usize, text: &Rope) -> usize{
+    let mut end = index;
+    while end < text.len_chars() && !text_util::is_whitespace(text.char(end)){end += 1;}
+    end
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::select_long_word;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics, TextObjectScope},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, scope: TextObjectScope, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = select_long_word::document_impl(&mut doc, scope, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, scope: TextObjectScope, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(select_long_word::document_impl(&mut doc, scope, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn inside_long_word(){
+        //  0 1 2 3 4 5 6 7 8 9 0 1
+        //  i d k _ s o m e . x _ \n
+        test(CursorSemantics::Bar, TextObjectScope::Inside, "idk some.x \n", vec![(5, 5, None)], 0, vec![(4, 10, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Inside, "idk some.x \n", vec![(8, 9, None)], 0, vec![(4, 10, None)], 0);
+    }
+    #[test] fn around_long_word(){
+        test(CursorSemantics::Bar, TextObjectScope::Around, "idk some.x \n", vec![(5, 5, None)], 0, vec![(4, 11, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Around, "idk some.x\n", vec![(5, 6, None)], 0, vec![(3, 10, None)], 0);
+        test(CursorSemantics::Bar, TextObjectScope::Around, "idk some.x\n", vec![(3, 3, None)], 0, vec![(3, 10, None)], 0);
+    }
+    #[test] fn errors_if_results_in_same_state(){
+        test_error(CursorSemantics::Bar, TextObjectScope::Inside, "idk\n", vec![(4, 4, None)], 0);  //at text end
+        test_error(CursorSemantics::Block, TextObjectScope::Inside, "idk\n", vec![(0, 3, None)], 0);
+    }
+}
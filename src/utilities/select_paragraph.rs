@@ -0,0 +1,107 @@
+use crate::{
+    selection_history::SelectionChange,
+    document::{Document, DocumentError},
+    selection::{Selection, SelectionError, CursorSemantics, Direction, TextObjectScope},
+    range::Range,
+    text_util
+};
+use ropey::Rope;
+
+pub fn document_impl(document: &mut Document, scope: TextObjectScope, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, |selection, text, semantics| selection_impl(selection, scope, text, semantics)){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Jump);}
+        Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
+    }
+    Ok(())
+}
+
+/// Returns a new instance of [`Selection`] over the paragraph at the cursor, including its last newline.
+/// A paragraph is a block of non blank lines. If the cursor is on a blank line, the block of blank lines is selected instead.
+/// Around also selects the blank lines after the paragraph(or before it, if there are none after), or the paragraph after selected blank lines.
+/// Errors if there is nothing to select.
+pub fn selection_impl(selection: &Selection, scope: TextObjectScope, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
+    selection.assert_invariants(text, semantics);
+    let line = text.char_to_line(selection.cursor(text, semantics));
+    let (first_line, last_line) = text_util::paragraph_lines(line, text);
+    let (mut start, mut end) = (text.line_to_char(first_line), text.line_to_char(last_line + 1));
+    if scope == TextObjectScope::Around{
+        let following_end = if last_line + 1 < text.len_lines(){
+            let (_, following_last_line) = text_util::paragraph_lines(last_line + 1, text);
+            text.line_to_char(following_last_line + 1)
+        }else{end};
+        if following_end > end{end = following_end;}
+        else if first_line > 0 && !text_util::is_blank_line(text.line(first_line)){
+            let (preceding_first_line, _) = text_util::paragraph_lines(first_line - 1, text);
+            start = text.line_to_char(preceding_first_line);
+        }
+    }
+    if start == end{return Err(SelectionError::ResultsInSameState);}
+
+    if selection.range == Range::new(start, end) && selection.direction == Direction::Forward{return Err(SelectionError::ResultsInSameState);}
+    Ok(Selection::new(Range::new(start, end), Direction::Forward))
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::select_paragraph;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics, TextObjectScope},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, scope: TextObjectScope, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = select_paragraph::document_impl(&mut doc, scope, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, scope: TextObjectScope, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(select_paragraph::document_impl(&mut doc, scope, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn inside_paragraph(){
+        // paragraphs are "idk\nsome\n" and "shit\n"
+        test(CursorSemantics::Bar, TextObjectScope::Inside, "idk\nsome\n\n\nshit\n", vec![(5, 5, None)], 0, vec![(0, 9, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Inside, "idk\nsome\n\n\nshit\n", vec![(11, 12, None)], 0, vec![(11, 16, None)], 0);
+        // blank lines
+        test(CursorSemantics::Block, TextObjectScope::Inside, "idk\nsome\n\n\nshit\n", vec![(9, 10, None)], 0, vec![(9, 11, None)], 0);
+    }
+    #[test] fn around_paragraph(){
+        test(CursorSemantics::Bar, TextObjectScope::Around, "idk\nsome\n\n\nshit\n", vec![(0, 0, None)], 0, vec![(0, 11, None)], 0);
+        // no following blank lines, so preceding blank lines are selected
+        test(CursorSemantics::Block, TextObjectScope::Around, "idk\nsome\n\n\nshit", vec![(11, 12, None)], 0, vec![(9, 15, None)], 0);
+        test(CursorSemantics::Bar, TextObjectScope::Around, "idk\n\nshit\n", vec![(6, 6, None)], 0, vec![(4, 10, None)], 0);
+        // blank lines, and the following paragraph
+        test(CursorSemantics::Bar, TextObjectScope::Around, "idk\nsome\n\n\nshit\n", vec![(10, 10, None)], 0, vec![(9, 16, None)], 0);
+    }
+    #[test] fn errors_if_results_in_same_state(){
+        test_error(CursorSemantics::Bar, TextObjectScope::Inside, "idk\n", vec![(4, 4, None)], 0);   //empty last line
+        test_error(CursorSemantics::Block, TextObjectScope::Inside, "idk\n", vec![(0, 4, None)], 0);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    selection_history::SelectionChange,
+    document::{Document, DocumentError},
+    selection::{Selection, SelectionError, CursorSemantics, Direction, TextObjectScope},
+    range::Range,
+    text_util
+};
+use ropey::Rope;
+
+pub fn document_impl(document: &mut Document, scope: TextObjectScope, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, |selection, text, semantics| selection_impl(selection, scope, text, semantics)){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Jump);}
+        Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
+    }
+    Ok(())
+}
+
+/// Returns a new instance of [`Selection`] over the sentence at the cursor. Sentences end after a `.`, `!` or `?`(and any closing
+/// quotes/brackets) followed by whitespace, or at the end of their paragraph. A cursor between sentences selects the following sentence.
+/// Around also selects the spaces/tabs after the sentence(or before it, if there are none after).
+/// Errors if the cursor is on a blank line, or after the last sentence in its paragraph.
+pub fn selection_impl(selection: &Selection, scope: TextObjectScope, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
+    selection.assert_invariants(text, semantics);
+    let cursor = selection.cursor(text, semantics);
+    let line = text.char_to_line(cursor);
+    if text_util::is_blank_line(text.line(line)){return Err(SelectionError::ResultsInSameState);}
+    let (first_line, last_line) = text_util::paragraph_lines(line, text);
+    let paragraph_start = text.line_to_char(first_line);
+    let paragraph_end = text.line_to_char(last_line) + text_util::line_width(text.line(last_line), false);
+
+    let mut start = paragraph_start;
+    let (start, end) = loop{
+        while start < paragraph_end && text_util::is_whitespace(text.char(start)){start += 1;}
+        if start >= paragraph_end{return Err(SelectionError::ResultsInSameState);}
+        let end = sentence_end(start, paragraph_end, text);
+        if cursor < end{break (start, end);}
+        start = end;
+    };
+    let (start, end) = match scope{
+        TextObjectScope::Inside => (start, end),
+        TextObjectScope::Around => text_util::include_adjacent_whitespace(start, end, text)
+    };
+
+    if selection.range == Range::new(start, end) && selection.direction == Direction::Forward{return Err(SelectionError::ResultsInSameState);}
+    Ok(Selection::new(Range::new(start, end), Direction::Forward))
+}
+
+/// Returns the index after the end of the sentence starting at `start`, which is no later than `paragraph_end`.
+fn sentence_end(start: usize, paragraph_end: usize, text: &Rope) -> usize{
+    let mut end = start;
+    while end < paragraph_end{
+        let char = text.char(end);
+        end += 1;
+        if matches!(char, '.' | '!' | '?'){
+            while end < paragraph_end && matches!(text.char(end), '.' | '!' | '?' | '"' | '\'' | ')' | ']'){end += 1;}
+            if end == paragraph_end || text_util::is_whitespace(text.char(end)){break;}
+        }
+    }
+    end
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::select_sentence;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics, TextObjectScope},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, scope: TextObjectScope, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = select_sentence::document_impl(&mut doc, scope, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, scope: TextObjectScope, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(select_sentence::document_impl(&mut doc, scope, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn inside_sentence(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3
+        // I d k .   S o m e . t h i n g ?   S h i t . \n
+        test(CursorSemantics::Bar, TextObjectScope::Inside, "Idk. Some.thing? Shit.\n", vec![(1, 1, None)], 0, vec![(0, 4, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Inside, "Idk. Some.thing? Shit.\n", vec![(10, 11, None)], 0, vec![(5, 16, None)], 0);
+        // between sentences selects the following sentence
+        test(CursorSemantics::Bar, TextObjectScope::Inside, "Idk. Some.thing? Shit.\n", vec![(16, 16, None)], 0, vec![(17, 22, None)], 0);
+    }
+    #[test] fn sentence_spans_lines_but_not_paragraphs(){
+        test(CursorSemantics::Block, TextObjectScope::Inside, "Idk\nsome. Shit\n\nelse.\n", vec![(0, 1, None)], 0, vec![(0, 9, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Inside, "Idk\nsome. Shit\n\nelse.\n", vec![(11, 12, None)], 0, vec![(10, 14, None)], 0);
+    }
+    #[test] fn around_sentence(){
+        test(CursorSemantics::Bar, TextObjectScope::Around, "Idk. Some.thing? Shit.\n", vec![(1, 1, None)], 0, vec![(0, 5, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Around, "Idk. Some.thing? Shit.\n", vec![(18, 19, None)], 0, vec![(16, 22, None)], 0);
+    }
+    #[test] fn errors_if_results_in_same_state(){
+        test_error(CursorSemantics::Bar, TextObjectScope::Inside, "Idk.\n\nSome.\n", vec![(5, 5, None)], 0);   //blank line
+        test_error(CursorSemantics::Block, TextObjectScope::Inside, "Idk.\n", vec![(0, 4, None)], 0);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    selection_history::SelectionChange,
+    document::{Document, DocumentError},
+    selection::{Selection, SelectionError, CursorSemantics, Direction, TextObjectScope},
+    range::Range,
+    text_util
+};
+use ropey::Rope;
+
+/// Selects inside/around the nearest pair from `pairs` surrounding each selection. `text_util::DEFAULT_PAIRS` holds brackets and quotes.
+pub fn document_impl(document: &mut Document, scope: TextObjectScope, pairs: &[(char, char)], semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, |selection, text, semantics| selection_impl(selection, scope, pairs, text, semantics)){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Jump);}
+        Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
+    }
+    Ok(())
+}
+
+/// Returns a new instance of [`Selection`] inside(excluding) or around(including) the innermost pair from `pairs` surrounding `selection`.
+/// Nested and escaped pair chars are skipped. A cursor over a pair char selects that pair.
+/// If `selection` already covers the innermost pair, the next pair outward is selected instead, so repeated calls grow the selection.
+/// Pairs whose chars are the same(quotes) are matched in order within the cursor's line, and do not nest.
+/// Errors if no surrounding pair is found, or(with block semantics) if the pair is empty and `scope` is inside.
+pub fn selection_impl(selection: &Selection, scope: TextObjectScope, pairs: &[(char, char)], text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
+    selection.assert_invariants(text, semantics);
+    // a pair surrounds if its open char is at or before `before`, and its close char is at or after `after`
+    let (mut before, mut after) = if selection.is_extended(semantics){
+        if selection.range.start == 0{return Err(SelectionError::ResultsInSameState);}
+        (selection.range.start - 1, selection.range.end - 1)
+    }else{
+        let cursor = selection.cursor(text, semantics);
+        (cursor, cursor)
+    };
+
+    loop{
+        let Some((open_index, close_index)) = pairs.iter()
+            .filter_map(|&(open, close)| surrounding_pair(before, after, open, close, text))
+            .max_by_key(|&(open_index, _)| open_index)  //innermost
+        else{return Err(SelectionError::ResultsInSameState);};
+        let range = match scope{
+            TextObjectScope::Inside => Range::new(open_index + 1, close_index),
+            TextObjectScope::Around => Range::new(open_index, close_index + 1)
+        };
+        if range == selection.range{
+            // already selected. grow outward
+            if open_index == 0{return Err(SelectionError::ResultsInSameState);}
+            (before, after) = (open_index - 1, close_index + 1);
+            continue;
+        }
+        if range.start == range.end && semantics == CursorSemantics::Block{return Err(SelectionError::ResultsInSameState);}
+        return Ok(Selection::new(range, Direction::Forward));
+    }
+}
+
+/// Returns the indices of the innermost `open`/`close` pair with its open char at or before `before`, and close char at or after `after`.
+fn surrounding_pair(before: usize, after: usize, open: char, close: char, text: &Rope) -> Option<(usize, usize)>{
+    if before >= text.len_chars(){return None;}
+    if open == close{
+        // quotes can't be told apart from their closing counterparts, so pair them in order, within the line
+        let line = text.char_to_line(before);
+        let line_start = text.line_to_char(line);
+        let line_end = line_start + text.line(line).len_chars();
+        let quotes: Vec<usize> = (line_start..line_end)
+            .filter(|&index| text.char(index) == open && !text_util::is_escaped(index, text))
+            .collect();
+        return quotes.chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .rfind(|&(open_index, close_index)| open_index <= before && close_index >= after);
+    }
+
+    let mut index = before + 1;
+    loop{
+        // a close char at `before` itself(cursor over a close char) is matched back to its open char
+        let open_index = if index == before + 1 && text.char(before) == close && !text_util::is_escaped(before, text){
+            text_util::matching_open(before, open, close, text)?
+        }else if index == before + 1 && text.char(before) == open && !text_util::is_escaped(before, text){
+            before
+        }else{
+            text_util::matching_open(index, open, close, text)?
+        };
+        // `matching_open` scans back from `index` for the first unmatched open char
+        let close_index = text_util::matching_close(open_index, open, close, text)?;
+        if close_index >= after{return Some((open_index, close_index));}
+        index = open_index;
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::select_surrounding_pair;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics, TextObjectScope},
+        text_util::DEFAULT_PAIRS,
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, scope: TextObjectScope, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = select_surrounding_pair::document_impl(&mut doc, scope, DEFAULT_PAIRS, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, scope: TextObjectScope, pairs: &[(char, char)], text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(select_surrounding_pair::document_impl(&mut doc, scope, pairs, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn inside_and_around_brackets(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0 1
+        // ( i d k [ s o m e ] ) \n
+        test(CursorSemantics::Bar, TextObjectScope::Inside, "(idk[some])\n", vec![(2, 2, None)], 0, vec![(1, 10, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Inside, "(idk[some])\n", vec![(6, 7, None)], 0, vec![(5, 9, None)], 0);
+        test(CursorSemantics::Bar, TextObjectScope::Around, "(idk[some])\n", vec![(6, 6, None)], 0, vec![(4, 10, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Around, "(idk[some])\n", vec![(2, 3, None)], 0, vec![(0, 11, None)], 0);
+    }
+    #[test] fn skips_nested_and_escaped_pairs(){
+        //                     1                   2
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0
+        // ( i d k ( s o m e )   \ )   x )
+        test(CursorSemantics::Bar, TextObjectScope::Inside, "(idk(some) \\) x)\n", vec![(14, 14, None)], 0, vec![(1, 15, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Inside, "(idk(some) \\) x)\n", vec![(1, 2, None)], 0, vec![(1, 15, None)], 0);
+    }
+    #[test] fn cursor_over_pair_char_selects_that_pair(){
+        test(CursorSemantics::Block, TextObjectScope::Around, "x(idk)\n", vec![(1, 2, None)], 0, vec![(1, 6, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Around, "x(idk)\n", vec![(5, 6, None)], 0, vec![(1, 6, None)], 0);
+    }
+    #[test] fn repeated_calls_grow_outward(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0 1
+        // { ( " i d k " ) }
+        test(CursorSemantics::Bar, TextObjectScope::Inside, "{(\"idk\")}\n", vec![(3, 6, None)], 0, vec![(2, 7, None)], 0);
+        test(CursorSemantics::Bar, TextObjectScope::Inside, "{(\"idk\")}\n", vec![(2, 7, None)], 0, vec![(1, 8, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Around, "{(\"idk\")}\n", vec![(2, 7, None)], 0, vec![(1, 8, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Around, "{(\"idk\")}\n", vec![(1, 8, None)], 0, vec![(0, 9, None)], 0);
+    }
+    #[test] fn quotes_pair_in_order_within_line(){
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2
+        // " i d k "   " s \ " o "
+        test(CursorSemantics::Bar, TextObjectScope::Inside, "\"idk\" \"s\\\"o\"\n", vec![(8, 8, None)], 0, vec![(7, 11, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Around, "\"idk\" \"s\\\"o\"\n", vec![(2, 3, None)], 0, vec![(0, 5, None)], 0);
+    }
+    #[test] fn with_multiple_selections(){
+        test(CursorSemantics::Block, TextObjectScope::Inside, "(idk) [some]\n", vec![(1, 2, None), (8, 9, None)], 1, vec![(1, 4, None), (7, 11, None)], 1);
+    }
+    #[test] fn uses_caller_supplied_pairs(){
+        test_error(CursorSemantics::Bar, TextObjectScope::Inside, &[('[', ']')], "(idk)\n", vec![(2, 2, None)], 0);
+    }
+    #[test] fn errors_without_surrounding_pair(){
+        test_error(CursorSemantics::Bar, TextObjectScope::Inside, DEFAULT_PAIRS, "idk (some)\n", vec![(1, 1, None)], 0);
+        test_error(CursorSemantics::Block, TextObjectScope::Inside, DEFAULT_PAIRS, "()\n", vec![(0, 1, None)], 0);   //empty pair
+        test_error(CursorSemantics::Bar, TextObjectScope::Around, DEFAULT_PAIRS, "(idk)\n", vec![(0, 5, None)], 0);  //no pair further out
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    selection_history::SelectionChange,
+    document::{Document, DocumentError},
+    selection::{Selection, SelectionError, CursorSemantics, Direction, TextObjectScope},
+    range::Range,
+    text_util
+};
+use ropey::Rope;
+
+pub fn document_impl(document: &mut Document, scope: TextObjectScope, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    match document.selections.move_cursor_potentially_overlapping(&document.text, semantics, |selection, text, semantics| selection_impl(selection, scope, text, semantics)){
+        Ok(new_selections) => {document.set_selections(new_selections, SelectionChange::Jump);}
+        Err(e) => {return Err(DocumentError::SelectionsError(e))}   //though, should only return SelectionsError::ResultsInSameState
+    }
+    Ok(())
+}
+
+/// Returns a new instance of [`Selection`] over the word at the cursor. A word is a run of word chars, or a single punctuation char.
+/// If the cursor is over spaces/tabs, the run of spaces/tabs is selected instead.
+/// Around also selects the spaces/tabs after the word(or before it, if there are none after), or the word after selected spaces/tabs.
+/// Errors if the cursor is at a line end, or text end.
+pub fn selection_impl(selection: &Selection, scope: TextObjectScope, text: &Rope, semantics: CursorSemantics) -> Result<Selection, SelectionError>{
+    selection.assert_invariants(text, semantics);
+    let cursor = selection.cursor(text, semantics);
+    if cursor >= text.len_chars() || text.char(cursor) == '\n'{return Err(SelectionError::ResultsInSameState);}
+
+    let is_space = |char: char| char == ' ' || char == '\t';
+    let (start, end) = if is_space(text.char(cursor)){
+        let mut start = cursor;
+        while start > 0 && is_space(text.char(start - 1)){start -= 1;}
+        let mut end = cursor;
+        while end < text.len_chars() && is_space(text.char(end)){end += 1;}
+        match scope{
+            TextObjectScope::Inside => (start, end),
+            TextObjectScope::Around if end < text.len_chars() && text.char(end) != '\n' => (start, text_util::next_word_boundary(end, text)),
+            TextObjectScope::Around => (start, end)
+        }
+    }else{
+        let (start, end) = (text_util::previous_word_boundary(cursor + 1, text), text_util::next_word_boundary(cursor, text));
+        match scope{
+            TextObjectScope::Inside => (start, end),
+            TextObjectScope::Around => text_util::include_adjacent_whitespace(start, end, text)
+        }
+    };
+
+    if selection.range == Range::new(start, end) && selection.direction == Direction::Forward{return Err(SelectionError::ResultsInSameState);}
+    Ok(Selection::new(Range::new(start, end), Direction::Forward))
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::select_word;
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics, TextObjectScope},
+    };
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, scope: TextObjectScope, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, tuple_expected_selections: Vec<(usize, usize, Option<usize>)>, expected_primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        let result = select_word::document_impl(&mut doc, scope, semantics);
+        assert!(result.is_ok());
+        let mut vec_expected_selections = Vec::new();
+        for tuple in tuple_expected_selections{
+            vec_expected_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let expected_selections = Selections::new(vec_expected_selections, expected_primary, &text, semantics);
+        assert_eq!(expected_selections, doc.selections);
+        assert!(!doc.is_modified());
+    }
+    fn test_error(semantics: CursorSemantics, scope: TextObjectScope, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        assert!(select_word::document_impl(&mut doc, scope, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+
+    #[test] fn inside_word(){
+        //  0 1 2 3 4 5 6 7 8 9
+        //  i d k _ s o m e . \n
+        test(CursorSemantics::Bar, TextObjectScope::Inside, "idk some.\n", vec![(5, 5, None)], 0, vec![(4, 8, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Inside, "idk some.\n", vec![(5, 6, None)], 0, vec![(4, 8, None)], 0);
+    }
+    #[test] fn inside_word_over_punctuation_and_spaces(){
+        test(CursorSemantics::Bar, TextObjectScope::Inside, "idk some.\n", vec![(8, 8, None)], 0, vec![(8, 9, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Inside, "idk  some\n", vec![(4, 5, None)], 0, vec![(3, 5, None)], 0);
+    }
+    #[test] fn around_word(){
+        // trailing spaces, or leading spaces if there are no trailing spaces
+        test(CursorSemantics::Bar, TextObjectScope::Around, "idk some\n", vec![(1, 1, None)], 0, vec![(0, 4, None)], 0);
+        test(CursorSemantics::Block, TextObjectScope::Around, "idk some\n", vec![(5, 6, None)], 0, vec![(3, 8, None)], 0);
+        // spaces, and the word after them
+        test(CursorSemantics::Bar, TextObjectScope::Around, "idk some\n", vec![(3, 3, None)], 0, vec![(3, 8, None)], 0);
+    }
+    #[test] fn with_multiple_selections(){
+        test(CursorSemantics::Block, TextObjectScope::Inside, "idk some\nshit\n", vec![(0, 1, None), (10, 11, None)], 1, vec![(0, 3, None), (9, 13, None)], 1);
+    }
+    #[test] fn errors_if_results_in_same_state(){
+        test_error(CursorSemantics::Bar, TextObjectScope::Inside, "idk some\n", vec![(8, 8, None)], 0);   //at line end
+        test_error(CursorSemantics::Block, TextObjectScope::Inside, "idk some\n", vec![(0, 3, None)], 0); //already selected
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    document::{Document, DocumentError},
+    selection::CursorSemantics,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Randomly reorders the selections' text. The order always changes, unless every selection holds the same text.
+pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    let original = document.selection_contents();
+    let mut seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_nanos() as u64) | 1;  //xorshift seed must be non zero
+    let mut contents = original.clone();
+    // a few retries are enough to get a new order, unless there is none
+    for _ in 0..16{
+        shuffle(&mut contents, &mut seed);
+        if contents != original{break;}
+    }
+    document.replace_selection_contents(contents, semantics)
+}
+
+/// Fisher-Yates shuffles `contents`, drawing from a xorshift generator with state `seed`.
+fn shuffle(contents: &mut [String], seed: &mut u64){
+    for i in (1..contents.len()).rev(){
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 7;
+        *seed ^= *seed << 17;
+        contents.swap(i, (*seed % (i as u64 + 1)) as usize);
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{shuffle_selection_contents, undo};
+    use crate::{
+        document::Document,
+        selections::Selections,
+        selection::{Selection, CursorSemantics},
+    };
+    use ropey::Rope;
+
+    #[test] fn reorders_contents(){
+        let semantics = CursorSemantics::Bar;
+        //                     1
+        // 0 1 2 3 4 5 6 7 8 9 0 1 2 3
+        // i d k   s o m e   s h i t \n
+        let text = Rope::from("idk some shit\n");
+        let selections = Selections::new(vec![
+            Selection::new_from_components(0, 3, None, &text, semantics),
+            Selection::new_from_components(4, 8, None, &text, semantics),
+            Selection::new_from_components(9, 13, None, &text, semantics),
+        ], 1, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections.clone());
+        assert!(shuffle_selection_contents::document_impl(&mut doc, semantics).is_ok());
+        assert_ne!(text, doc.text);
+        let mut contents: Vec<String> = doc.selections.iter().map(|selection| selection.contents_as_string(&doc.text)).collect();
+        contents.sort();
+        assert_eq!(vec!["idk", "shit", "some"], contents);
+        assert_eq!(1, doc.selections.primary_selection_index);
+        // a single undo restores the text and selections
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(text, doc.text);
+        assert_eq!(selections, doc.selections);
+    }
+    #[test] fn errors_if_every_selection_holds_the_same_text(){
+        let semantics = CursorSemantics::Block;
+        let text = Rope::from("aaa\n");
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(Selections::new(vec![
+                Selection::new_from_components(0, 1, None, &text, semantics),
+                Selection::new_from_components(2, 3, None, &text, semantics),
+            ], 0, &text, semantics));
+        assert!(shuffle_selection_contents::document_impl(&mut doc, semantics).is_err());
+        assert!(!doc.is_modified());
+    }
+}
@@ -1,61 +1,79 @@
 use crate::{
     document::{Document, DocumentError},
     selection::CursorSemantics,
-    history::Operation
+    history::{ChangeSet, Operation}
 };
-use std::cmp::Ordering;
 
 /// Reverts the last set of changes made to the document.
+/// Change sets grouped together(consecutive typing or deletion) are reverted as a single unit.
 pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{    //should this be a HistoryError instead?...
     // Check if there is something to undo
-    if let Some(change_set) = document.undo_stack.pop(){
-        let changes = change_set.changes();
-        
-        document.selections = change_set.clone().selections_after_changes();    //set selections to selections_after_changes to account for any selection movements that may have occurred since edit
-        assert!(document.selections.count() == changes.len());
-
-        for (i, change) in changes.iter().enumerate().take(document.selections.count()){
-            let selection = document.selections.nth_mut(i);
-            match change.operation(){
-                Operation::Insert{inserted_text} => {
-                    selection.shift_and_extend(inserted_text.len(), &document.text, semantics);
-                    let _ = Document::apply_delete(&mut document.text, selection, semantics);
-                    document.selections.shift_subsequent_selections_backward(i, inserted_text.len());
-                }
-                Operation::Delete => {
-                    if let Operation::Insert{inserted_text} = change.inverse(){
-                        let _ = Document::apply_insert(&mut document.text, &inserted_text, selection, semantics);   //apply inverse operation
-                        document.selections.shift_subsequent_selections_forward(i, inserted_text.len());
-                    }
+    let Some(mut change_set) = undo_change_set(document, semantics) else{return Err(DocumentError::NoChangesToUndo);};
+    while change_set.is_grouped_with_previous(){
+        match undo_change_set(document, semantics){
+            Some(previous) => change_set = previous,
+            None => break
+        }
+    }
+    Ok(())
+}
+/// Moves the document to the state at undo tree node `target`, by undoing change sets up to the nearest state shared with `target`,
+/// then redoing change sets down the branch leading to `target`. `target` becomes reachable by redo from then on.
+pub(crate) fn move_to_state(document: &mut Document, target: usize, semantics: CursorSemantics){
+    let (undone, redone) = document.undo_tree.path(document.undo_tree.current(), target);
+    for _ in undone{
+        undo_change_set(document, semantics);
+    }
+    for id in redone{
+        document.undo_tree.set_active_child(id);
+        crate::utilities::redo::redo_change_set(document, semantics);
+    }
+}
+/// Reverts the [`ChangeSet`] leading to the current undo tree node, and makes the node's parent current.
+/// Returns the reverted [`ChangeSet`], or `None` if the current node is the root.
+pub(crate) fn undo_change_set(document: &mut Document, semantics: CursorSemantics) -> Option<ChangeSet>{
+    let change_set = document.undo_tree.next_undo()?.clone();
+    let recorded_semantics = change_set.semantics();
+    // each change's selections were recorded with all previous changes in the set applied, so reverting the changes in reverse order
+    // lets each be reverted where it was recorded, without shifting positions
+    for change in change_set.changes().iter().rev(){
+        let mut selection = change.selection_after_change();
+        match change.operation(){
+            Operation::Insert{inserted_text} => {
+                selection.shift_and_extend(inserted_text.chars().count(), &document.text, recorded_semantics);
+                let _ = Document::apply_delete(&mut document.text, &mut selection, recorded_semantics);
+            }
+            Operation::Delete => {
+                if let Operation::Insert{inserted_text} = change.inverse(){
+                    let _ = Document::apply_insert(&mut document.text, &inserted_text, &mut selection, recorded_semantics);   //apply inverse operation
                 }
-                Operation::Replace{replacement_text} => {
-                    let inserted_text = replacement_text;
-                    if let Operation::Replace{replacement_text} = change.inverse(){
-                        selection.shift_and_extend(inserted_text.len(), &document.text, semantics);
-                        let _ = Document::apply_replace(&mut document.text, &replacement_text, selection, semantics);
-                        match inserted_text.len().cmp(&replacement_text.len()){    //old selected text vs new text
-                            Ordering::Greater => {document.selections.shift_subsequent_selections_backward(i, inserted_text.len().saturating_sub(replacement_text.len()));}
-                            Ordering::Less => {document.selections.shift_subsequent_selections_forward(i, replacement_text.len().saturating_sub(inserted_text.len()));}
-                            Ordering::Equal => {}   // no change to subsequent selections
-                        }
-                    }
+            }
+            Operation::Replace{replacement_text} => {
+                if let Operation::Replace{replacement_text: replaced_text} = change.inverse(){
+                    selection.shift_and_extend(replacement_text.chars().count(), &document.text, recorded_semantics);
+                    let _ = Document::apply_replace(&mut document.text, &replaced_text, &mut selection, recorded_semantics);
                 }
-                Operation::NoOp => {}
             }
+            Operation::NoOp => {}
         }
-        // selections should be the same as they were before changes were made, because we are restoring that previous state
-        document.selections = change_set.selections_before_changes();
-
-        // Push inverted changes onto redo stack
-        document.redo_stack.push(change_set);
+    }
+    // selections should be the same as they were before changes were made, because we are restoring that previous state.
+    // selections recorded by a client with other cursor semantics don't fit this client, so its own are moved across the undo instead
+    document.selections = if recorded_semantics == semantics{
+        change_set.selections_before_changes()
+    }else{
+        document.selections.map_edits(&change_set.inverse_edits(), &document.text, semantics)
+    };
 
-        Ok(())
-    }else{Err(DocumentError::NoChangesToUndo)}
+    document.selection_history.map_edits(&change_set.inverse_edits(), &document.text, semantics);
+    document.word_index.update(&change_set.inverse_edits(), &document.text);
+    document.undo_tree.move_to_parent();
+    Some(change_set)
 }
 
 #[cfg(test)]
 mod tests{
-    use crate::utilities::undo;
+    use crate::utilities::{undo, insert_string, backspace, delete, move_cursor_left};
     use crate::{
         document::Document, 
         selections::Selections,
@@ -166,7 +184,8 @@ mod tests{
                         )
                     ], 
                     Selections::new(vec![Selection::new(Range::new(4, 5), Direction::Forward)], 0, &Rope::from("idk\nshit\n"), CursorSemantics::Block), 
-                    Selections::new(vec![Selection::new(Range::new(9, 10), Direction::Forward)], 0, &Rope::from("idk\nsome\nshit\n"), CursorSemantics::Block)
+                    Selections::new(vec![Selection::new(Range::new(9, 10), Direction::Forward)], 0, &Rope::from("idk\nsome\nshit\n"), CursorSemantics::Block),
+                    CursorSemantics::Block
                 )
             ], 
             "idk\nshit\n", 
@@ -187,4 +206,141 @@ mod tests{
         test_error(CursorSemantics::Block);
         test_error(CursorSemantics::Bar);
     }
+
+    fn test_grouping(semantics: CursorSemantics, text: &str, tuple_selections: Vec<(usize, usize, Option<usize>)>, primary: usize, edit: impl Fn(&mut Document), expected_text_after_one_undo: &str){
+        let text = Rope::from(text);
+        let mut vec_selections = Vec::new();
+        for tuple in tuple_selections{
+            vec_selections.push(Selection::new_from_components(tuple.0, tuple.1, tuple.2, &text, semantics));
+        }
+        let selections = Selections::new(vec_selections, primary, &text, semantics);
+        let mut doc = Document::new(semantics)
+            .with_text(text.clone())
+            .with_selections(selections);
+        edit(&mut doc);
+        let result = undo::document_impl(&mut doc, semantics);
+        assert!(result.is_ok());
+        assert_eq!(Rope::from(expected_text_after_one_undo), doc.text);
+    }
+
+    #[test] fn undo_reverts_consecutive_typing_as_a_single_unit(){
+        for (semantics, selection) in [(CursorSemantics::Block, (0, 1, None)), (CursorSemantics::Bar, (0, 0, None))]{
+            test_grouping(
+                semantics, 
+                "idk\n", 
+                vec![selection], 0, 
+                |doc| {
+                    for string in ["s", "o", "m", "e"]{
+                        let _ = insert_string::document_impl(doc, string, false, 4, semantics);
+                    }
+                }, 
+                "idk\n"
+            );
+        }
+    }
+    #[test] fn undo_reverts_consecutive_typing_with_multiple_selections_as_a_single_unit(){
+        test_grouping(
+            CursorSemantics::Bar, 
+            "idk\nsome\n", 
+            vec![
+                (0, 0, None),
+                (4, 4, None)
+            ], 0, 
+            |doc| {
+                for string in ["x", "y"]{
+                    let _ = insert_string::document_impl(doc, string, false, 4, CursorSemantics::Bar);
+                }
+            }, 
+            "idk\nsome\n"
+        );
+    }
+    #[test] fn undo_reverts_consecutive_backspaces_as_a_single_unit(){
+        for (semantics, selection) in [(CursorSemantics::Block, (3, 4, None)), (CursorSemantics::Bar, (3, 3, None))]{
+            test_grouping(
+                semantics, 
+                "idk\n", 
+                vec![selection], 0, 
+                |doc| {
+                    for _ in 0..3{
+                        let _ = backspace::document_impl(doc, 1, false, 4, semantics);
+                    }
+                }, 
+                "idk\n"
+            );
+        }
+    }
+    #[test] fn undo_reverts_consecutive_deletes_as_a_single_unit(){
+        for (semantics, selection) in [(CursorSemantics::Block, (0, 1, None)), (CursorSemantics::Bar, (0, 0, None))]{
+            test_grouping(
+                semantics, 
+                "idk\n", 
+                vec![selection], 0, 
+                |doc| {
+                    for _ in 0..3{
+                        let _ = delete::document_impl(doc, 1, semantics);
+                    }
+                }, 
+                "idk\n"
+            );
+        }
+    }
+    #[test] fn undo_insert_of_multi_codepoint_graphemes_restores_text_and_selections(){
+        for (semantics, width) in [(CursorSemantics::Block, 1), (CursorSemantics::Bar, 0)]{
+            for string in ["\u{e9}", "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}", "e\u{301}"]{
+                let text = Rope::from("idk\nsome\n");
+                let selections = Selections::new(
+                    vec![
+                        Selection::new_from_components(0, width, None, &text, semantics),
+                        Selection::new_from_components(4, 4 + width, None, &text, semantics)
+                    ], 0, &text, semantics
+                );
+                let mut doc = Document::new(semantics)
+                    .with_text(text.clone())
+                    .with_selections(selections.clone());
+                assert!(insert_string::document_impl(&mut doc, string, false, 4, semantics).is_ok());
+                assert!(undo::document_impl(&mut doc, semantics).is_ok());
+                assert_eq!(text, doc.text);
+                assert_eq!(selections, doc.selections);
+            }
+        }
+    }
+    #[test] fn switching_from_typing_to_deleting_starts_a_new_unit(){
+        test_grouping(
+            CursorSemantics::Bar, 
+            "idk\n", 
+            vec![(3, 3, None)], 0, 
+            |doc| {
+                let _ = insert_string::document_impl(doc, "s", false, 4, CursorSemantics::Bar);
+                let _ = insert_string::document_impl(doc, "o", false, 4, CursorSemantics::Bar);
+                let _ = backspace::document_impl(doc, 1, false, 4, CursorSemantics::Bar);
+            }, 
+            "idkso\n"
+        );
+    }
+    #[test] fn moving_cursor_between_edits_starts_a_new_unit(){
+        test_grouping(
+            CursorSemantics::Bar, 
+            "idk\n", 
+            vec![(3, 3, None)], 0, 
+            |doc| {
+                let _ = insert_string::document_impl(doc, "s", false, 4, CursorSemantics::Bar);
+                let _ = move_cursor_left::document_impl(doc, 1, CursorSemantics::Bar);
+                let _ = insert_string::document_impl(doc, "o", false, 4, CursorSemantics::Bar);
+            }, 
+            "idks\n"
+        );
+    }
+    #[test] fn newline_insertion_starts_a_new_unit(){
+        test_grouping(
+            CursorSemantics::Bar, 
+            "idk\n", 
+            vec![(3, 3, None)], 0, 
+            |doc| {
+                let _ = insert_string::document_impl(doc, "s", false, 4, CursorSemantics::Bar);
+                let _ = insert_string::document_impl(doc, "\n", false, 4, CursorSemantics::Bar);
+                let _ = insert_string::document_impl(doc, "o", false, 4, CursorSemantics::Bar);
+            }, 
+            "idks\n\n"
+        );
+    }
 }
@@ -0,0 +1,69 @@
+use crate::{
+    document::{Document, DocumentError},
+    selection::CursorSemantics,
+    utilities::undo
+};
+
+/// Moves the document to the state it was in before the most recent change reachable from the current state, by time instead
+/// of by position on the current branch. Like vim's `g-`, this can move onto undone branches that undo alone cannot reach.
+/// States in the middle of grouped change sets(consecutive typing or deletion) are skipped.
+pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    let current = document.undo_tree.current();
+    let Some(target) = (0..current).rev().find(|&id| id == 0 || !document.undo_tree.is_mid_group(id)) else{return Err(DocumentError::NoChangesToUndo);};
+    undo::move_to_state(document, target, semantics);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{undo_earlier, insert_string, undo};
+    use crate::document::Document;
+    use crate::selection::CursorSemantics;
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, edits: &[&str], undos_before_last_edit: usize, steps: usize, expected_text: &str){
+        let mut doc = Document::new(semantics);
+        for (i, edit) in edits.iter().enumerate(){
+            if i == edits.len() - 1{
+                for _ in 0..undos_before_last_edit{assert!(undo::document_impl(&mut doc, semantics).is_ok());}
+            }
+            assert!(insert_string::document_impl(&mut doc, edit, false, 4, semantics).is_ok());
+        }
+        for _ in 0..steps{
+            assert!(undo_earlier::document_impl(&mut doc, semantics).is_ok());
+        }
+        assert_eq!(Rope::from(expected_text), doc.text);
+        // the current node must match the text, so undoing everything gets back to the empty document
+        while undo::document_impl(&mut doc, semantics).is_ok(){}
+        assert_eq!(Rope::from(""), doc.text);
+    }
+    fn test_error(semantics: CursorSemantics){
+        let mut doc = Document::new(semantics);
+        assert!(undo_earlier::document_impl(&mut doc, semantics).is_err());
+    }
+
+    #[test] fn undo_earlier_moves_back_along_single_branch(){
+        test(CursorSemantics::Bar, &["idk\n", "some\n"], 0, 1, "idk\n");
+        test(CursorSemantics::Block, &["idk\n", "some\n"], 0, 1, "idk\n");
+    }
+    #[test] fn undo_earlier_moves_onto_undone_branch(){
+        // "idk\n" then "some\n", undone, then "shit\n" on a new branch. the state before "shit\n" is the state with "some\n"
+        test(CursorSemantics::Bar, &["idk\n", "some\n", "shit\n"], 1, 1, "idk\nsome\n");
+        test(CursorSemantics::Block, &["idk\n", "some\n", "shit\n"], 1, 1, "idk\nsome\n");
+        test(CursorSemantics::Bar, &["idk\n", "some\n", "shit\n"], 1, 2, "idk\n");
+        test(CursorSemantics::Block, &["idk\n", "some\n", "shit\n"], 1, 3, "");
+    }
+    #[test] fn undo_earlier_skips_grouped_states(){
+        let semantics = CursorSemantics::Bar;
+        let mut doc = Document::new(semantics);
+        for char in ["i", "d", "k"]{
+            assert!(insert_string::document_impl(&mut doc, char, false, 4, semantics).is_ok());
+        }
+        assert!(undo_earlier::document_impl(&mut doc, semantics).is_ok());
+        assert_eq!(Rope::from(""), doc.text);
+    }
+    #[test] fn errors_if_at_earliest_state(){
+        test_error(CursorSemantics::Bar);
+        test_error(CursorSemantics::Block);
+    }
+}
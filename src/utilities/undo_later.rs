@@ -0,0 +1,60 @@
+use crate::{
+    document::{Document, DocumentError},
+    selection::CursorSemantics,
+    utilities::undo
+};
+
+/// Moves the document to the state it was in after the next change made, by time instead of by position on the current branch.
+/// Like vim's `g+`, this reverses [`undo_earlier`](crate::utilities::undo_earlier).
+/// States in the middle of grouped change sets(consecutive typing or deletion) are skipped.
+pub fn document_impl(document: &mut Document, semantics: CursorSemantics) -> Result<(), DocumentError>{
+    let current = document.undo_tree.current();
+    let Some(target) = (current + 1..document.undo_tree.len()).find(|&id| !document.undo_tree.is_mid_group(id)) else{return Err(DocumentError::NoChangesToRedo);};
+    undo::move_to_state(document, target, semantics);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::utilities::{undo_earlier, undo_later, insert_string, undo};
+    use crate::document::Document;
+    use crate::selection::CursorSemantics;
+    use ropey::Rope;
+
+    fn test(semantics: CursorSemantics, earlier_steps: usize, later_steps: usize, expected_text: &str){
+        // "idk\n" then "some\n", undone, then "shit\n" on a new branch
+        let mut doc = Document::new(semantics);
+        assert!(insert_string::document_impl(&mut doc, "idk\n", false, 4, semantics).is_ok());
+        assert!(insert_string::document_impl(&mut doc, "some\n", false, 4, semantics).is_ok());
+        assert!(undo::document_impl(&mut doc, semantics).is_ok());
+        assert!(insert_string::document_impl(&mut doc, "shit\n", false, 4, semantics).is_ok());
+        for _ in 0..earlier_steps{
+            assert!(undo_earlier::document_impl(&mut doc, semantics).is_ok());
+        }
+        for _ in 0..later_steps{
+            assert!(undo_later::document_impl(&mut doc, semantics).is_ok());
+        }
+        assert_eq!(Rope::from(expected_text), doc.text);
+        // the current node must match the text, so undoing everything gets back to the empty document
+        while undo::document_impl(&mut doc, semantics).is_ok(){}
+        assert_eq!(Rope::from(""), doc.text);
+    }
+    fn test_error(semantics: CursorSemantics){
+        let mut doc = Document::new(semantics);
+        assert!(insert_string::document_impl(&mut doc, "idk", false, 4, semantics).is_ok());
+        assert!(undo_later::document_impl(&mut doc, semantics).is_err());
+    }
+
+    #[test] fn undo_later_reverses_undo_earlier(){
+        test(CursorSemantics::Bar, 3, 1, "idk\n");
+        test(CursorSemantics::Block, 3, 1, "idk\n");
+        test(CursorSemantics::Bar, 3, 2, "idk\nsome\n");
+        test(CursorSemantics::Block, 3, 2, "idk\nsome\n");
+        test(CursorSemantics::Bar, 3, 3, "idk\nshit\n");
+        test(CursorSemantics::Block, 3, 3, "idk\nshit\n");
+    }
+    #[test] fn errors_if_at_latest_state(){
+        test_error(CursorSemantics::Bar);
+        test_error(CursorSemantics::Block);
+    }
+}